@@ -0,0 +1,56 @@
+//! Benchmark full filter scans, like `desc(text)`, over a linear history.
+//!
+//! Run with `cargo bench --bench filter`. Set `GITREVSET_BENCH_COMMITS` to
+//! change the number of commits (default: 100000).
+
+use gitrevset::git2;
+use gitrevset::Repo;
+use std::time::Instant;
+
+fn main() {
+    let count: usize = std::env::var("GITREVSET_BENCH_COMMITS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(100_000);
+    let dir = tempfile::tempdir().unwrap();
+    let git_repo = git2::Repository::init(dir.path()).unwrap();
+
+    let start = Instant::now();
+    {
+        let sig = git2::Signature::new("test", "test@example.com", &git2::Time::new(0, 0));
+        let sig = sig.unwrap();
+        let tree_id = git_repo.treebuilder(None).unwrap().write().unwrap();
+        let tree = git_repo.find_tree(tree_id).unwrap();
+        let mut parent = None;
+        for i in 0..count {
+            let parents: Vec<_> = parent.iter().collect();
+            let message = format!("commit {}", i);
+            let oid = git_repo
+                .commit(None, &sig, &sig, &message, &tree, &parents)
+                .unwrap();
+            parent = Some(git_repo.find_commit(oid).unwrap());
+        }
+        let head = parent.unwrap().id();
+        git_repo
+            .reference("refs/heads/master", head, true, "bench")
+            .unwrap();
+    }
+    eprintln!("create {} commits: {:?}", count, start.elapsed());
+
+    let repo = Repo::open_from_repo(Box::new(git_repo)).unwrap();
+
+    // Baseline: test commits one by one in the current thread.
+    let start = Instant::now();
+    let mut matched = 0;
+    for oid in gitrevset::SetExt::to_oid_vec(&repo.revs("all()").unwrap()).unwrap() {
+        let commit = repo.git_repo().find_commit(oid).unwrap();
+        if commit.message().unwrap().contains("99") {
+            matched += 1;
+        }
+    }
+    eprintln!("serial scan: {:?} ({} matched)", start.elapsed(), matched);
+
+    let start = Instant::now();
+    let matched = repo.revs("desc(99)").unwrap().count().unwrap();
+    eprintln!("desc(99) scan: {:?} ({} matched)", start.elapsed(), matched);
+}
@@ -1,8 +1,10 @@
 use crate::Error;
+use crate::Repo;
 use crate::Result;
 use gitdag::dag::Set;
 use gitdag::git2::Oid;
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::fmt;
 
 /// A node in the parsed AST.
@@ -16,18 +18,22 @@ pub enum Expr {
 
     /// An inlined Set.
     Inlined(Set),
+
+    /// A regex pattern, like `/foo/`. Used as an argument of functions like
+    /// `grep(/foo/)`. It cannot be evaluated as a set.
+    Regex(String),
 }
 
 impl fmt::Debug for Expr {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Expr::Name(s) => f.write_str(&s)?,
+            Expr::Name(s) => f.write_str(s)?,
             Expr::Fn(name, args) => {
                 if args.is_empty() {
                     f.write_str(name)?;
                     f.write_str("()")?;
                 } else {
-                    let mut list = f.debug_tuple(&name);
+                    let mut list = f.debug_tuple(name);
                     for arg in args {
                         list.field(arg);
                     }
@@ -35,6 +41,7 @@ impl fmt::Debug for Expr {
                 }
             }
             Expr::Inlined(set) => set.fmt(f)?,
+            Expr::Regex(pattern) => write!(f, "/{}/", pattern.replace('/', "\\/"))?,
         }
         Ok(())
     }
@@ -68,14 +75,126 @@ impl Expr {
                     }
                 }
             }
-            Expr::Inlined(_) => (),
+            Expr::Inlined(_) | Expr::Regex(_) => (),
         }
     }
 
     /// Parse AST from a string.
     pub fn parse(s: &str) -> Result<Self> {
-        crate::parser::parse(s).map_err(|e| Error::ParseError(e.to_string()))
+        crate::parser::parse(s)
+    }
+}
+
+/// Lower git rev-list style `(included, expr)` items, like `a ^b`, to
+/// `::a - ::b`. Used by the parser.
+pub(crate) fn rev_list(items: Vec<(bool, Expr)>) -> Expr {
+    let union = |a: Expr, b: Expr| Expr::Fn("union".into(), vec![a, b]);
+    let mut included: Option<Expr> = None;
+    let mut excluded: Option<Expr> = None;
+    for (is_included, expr) in items {
+        let side = if is_included {
+            &mut included
+        } else {
+            &mut excluded
+        };
+        *side = Some(match side.take() {
+            Some(prev) => union(prev, expr),
+            None => expr,
+        });
+    }
+    let ancestors = |e: Option<Expr>| {
+        let e = e.unwrap_or_else(|| Expr::Fn("none".into(), vec![]));
+        Expr::Fn("ancestors".into(), vec![e])
+    };
+    Expr::Fn(
+        "difference".into(),
+        vec![ancestors(included), ancestors(excluded)],
+    )
+}
+
+/// A parsed expression that can be evaluated multiple times.
+///
+/// Parsing and alias expansion happen once. Names like `master` are still
+/// resolved on each evaluation, so evaluating after
+/// [`Repo::reload`](struct.Repo.html#method.reload) picks up new commits and
+/// references. Sets inlined in the expression are not updated.
+#[derive(Clone, Debug)]
+pub struct Revset {
+    expr: Expr,
+    aliases: bool,
+}
+
+impl Revset {
+    /// Parse an expression. User-defined aliases are ignored, like
+    /// [`Repo::revs`](struct.Repo.html#method.revs).
+    pub fn parse(ast: impl ParseToExpr) -> Result<Self> {
+        let expr = ast.parse_to_expr()?;
+        Ok(Self {
+            expr,
+            aliases: false,
+        })
+    }
+
+    /// Parse an expression. Expand calls to user-defined aliases in the
+    /// config of `repo`, like [`Repo::anyrevs`](struct.Repo.html#method.anyrevs).
+    /// Changes to aliases after parsing are ignored.
+    pub fn parse_with_aliases(repo: &Repo, ast: impl ParseToExpr) -> Result<Self> {
+        let aliases = crate::repo::read_aliases(repo.git_repo())?;
+        let expr = expand_aliases(&ast.parse_to_expr()?, &aliases, 0)?;
+        Ok(Self {
+            expr,
+            aliases: true,
+        })
+    }
+
+    /// The parsed expression.
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+
+    /// Evaluate the expression. Return the resulting set.
+    pub fn eval(&self, repo: &Repo) -> Result<Set> {
+        if self.aliases {
+            // Names like `foo` can still refer to aliases.
+            repo.anyrevs(self.expr.clone())
+        } else {
+            repo.revs(self.expr.clone())
+        }
+    }
+}
+
+/// Replace calls to aliases like `f(x)` with their definitions.
+fn expand_aliases(expr: &Expr, aliases: &HashMap<String, Expr>, depth: usize) -> Result<Expr> {
+    const MAX_DEPTH: usize = 100;
+    match expr {
+        Expr::Fn(name, args) => {
+            let args = args
+                .iter()
+                .map(|arg| expand_aliases(arg, aliases, depth))
+                .collect::<Result<Vec<_>>>()?;
+            match aliases.get(name.as_ref()) {
+                Some(_) if depth >= MAX_DEPTH => Err(Error::ParseError(format!(
+                    "alias {} is too deeply nested or recursive",
+                    name
+                ))),
+                Some(body) => {
+                    let body = substitute_alias_args(body, &args);
+                    expand_aliases(&body, aliases, depth + 1)
+                }
+                None => Ok(Expr::Fn(name.clone(), args)),
+            }
+        }
+        _ => Ok(expr.clone()),
+    }
+}
+
+/// Replace arguments in the alias `body`, ex. `$1` -> `args[0]`, ...
+pub(crate) fn substitute_alias_args(body: &Expr, args: &[Expr]) -> Expr {
+    let mut body = body.clone();
+    for (i, arg) in args.iter().enumerate() {
+        body.replace(&format!("${}", i + 1), arg);
     }
+    body
 }
 
 /// Convert to `Expr` by parsing.
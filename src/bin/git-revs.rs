@@ -1,33 +1,620 @@
+use chrono::FixedOffset;
+use chrono::TimeZone;
+use gitrevset::dag;
+use gitrevset::dag::Set;
+use gitrevset::dag::Vertex;
+use gitrevset::ext::VertexExt;
+use gitrevset::git2;
+use gitrevset::Error;
 use gitrevset::Expr;
 use gitrevset::Repo;
 use gitrevset::Result;
+use gitrevset::SetExt;
+use std::collections::HashMap;
 use std::env;
+use std::io;
+use std::io::BufRead;
+use std::io::IsTerminal;
+use std::io::Write;
+use std::process;
 
-fn try_main() -> Result<()> {
-    let repo = Repo::open_from_env()?;
+/// What to print after each commit hash.
+#[derive(Clone, Copy, PartialEq)]
+enum Show {
+    Nothing,
+    Patch,
+    Stat,
+}
+
+/// Order and number of commits to print.
+#[derive(Clone, Copy, Default)]
+struct Order {
+    /// Oldest first.
+    reverse: bool,
+    /// Stop after printing this many commits.
+    limit: Option<usize>,
+}
+
+impl Order {
+    /// Iterate `set` lazily. The limit applies after reversing.
+    fn iter(&self, set: &Set) -> Result<impl Iterator<Item = dag::Result<Vertex>>> {
+        let iter = if self.reverse {
+            set.iter_rev()?
+        } else {
+            set.iter()?
+        };
+        Ok(iter.take(self.limit.unwrap_or(usize::MAX)))
+    }
+}
+
+/// A field in a `--template` string.
+#[derive(Clone, Copy)]
+enum Field {
+    /// `{hash}`: Full commit hash.
+    Hash,
+    /// `{short}`: Abbreviated commit hash.
+    Short,
+    /// `{author}`: Author name.
+    Author,
+    /// `{date}`: Author date, like `2020-01-02 03:04:05 +0800`.
+    Date,
+    /// `{summary}`: First line of the commit message.
+    Summary,
+}
+
+enum Segment {
+    Text(String),
+    Field(Field),
+}
+
+/// Parsed `--template` string, like `{short} {summary}`.
+struct Template(Vec<Segment>);
+
+impl Template {
+    fn parse(template: &str) -> Result<Self> {
+        let mut segments = Vec::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                segments.push(Segment::Text(rest[..start].to_string()));
+            }
+            let end = match rest[start..].find('}') {
+                Some(end) => start + end,
+                None => {
+                    return Err(Error::ParseError(format!(
+                        "unclosed '{{' in template: {}",
+                        template
+                    )))
+                }
+            };
+            let field = match &rest[start + 1..end] {
+                "hash" => Field::Hash,
+                "short" => Field::Short,
+                "author" => Field::Author,
+                "date" => Field::Date,
+                "summary" => Field::Summary,
+                name => {
+                    return Err(Error::ParseError(format!(
+                        "unknown template field: {{{}}} (expect {{hash}}, {{short}}, {{author}}, {{date}}, or {{summary}})",
+                        name
+                    )))
+                }
+            };
+            segments.push(Segment::Field(field));
+            rest = &rest[end + 1..];
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Text(rest.to_string()));
+        }
+        Ok(Self(segments))
+    }
+
+    fn render(&self, repo: &Repo, oid: git2::Oid) -> Result<String> {
+        let commit = repo.git_repo().find_commit(oid)?;
+        let mut result = String::new();
+        for segment in &self.0 {
+            match segment {
+                Segment::Text(text) => result.push_str(text),
+                Segment::Field(Field::Hash) => result.push_str(&oid.to_string()),
+                Segment::Field(Field::Short) => {
+                    let short = commit.as_object().short_id()?;
+                    result.push_str(short.as_str().unwrap_or(""));
+                }
+                Segment::Field(Field::Author) => {
+                    result.push_str(commit.author().name().unwrap_or(""));
+                }
+                Segment::Field(Field::Date) => {
+                    let when = commit.author().when();
+                    let offset = FixedOffset::east_opt(when.offset_minutes() * 60)
+                        .unwrap_or_else(|| FixedOffset::east_opt(0).unwrap());
+                    if let Some(date) = offset.timestamp_opt(when.seconds(), 0).single() {
+                        result.push_str(&date.format("%Y-%m-%d %H:%M:%S %z").to_string());
+                    }
+                }
+                Segment::Field(Field::Summary) => {
+                    result.push_str(commit.summary().unwrap_or(""));
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Return `false` if `--contains` is used and a set does not contain the
+/// commit.
+fn try_main() -> Result<bool> {
+    let (repo_path, args) = extract_repo_path(env::args().skip(1))?;
+    let repo = match repo_path {
+        Some(path) => Repo::open_from_path(path)?,
+        None => Repo::open_from_env()?,
+    };
     let mut print_ast = false;
-    for arg in env::args().skip(1) {
+    let mut print_plan = false;
+    let mut separator = "\n";
+    let mut show = Show::Nothing;
+    let mut order = Order::default();
+    let mut count = false;
+    let mut template = None;
+    let mut json = false;
+    let mut graph = false;
+    let mut contains = None;
+    let mut quiet = false;
+    let mut pick = false;
+    let mut all_contain = true;
+    let mut codes = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
         let arg: &str = &arg;
         if arg == "--ast" {
             print_ast = true;
             continue;
         }
+        if arg == "--plan" {
+            print_plan = true;
+            continue;
+        }
+        if arg == "-z" || arg == "--null" {
+            separator = "\0";
+            continue;
+        }
+        if arg == "-p" || arg == "--patch" {
+            show = Show::Patch;
+            continue;
+        }
+        if arg == "--stat" {
+            show = Show::Stat;
+            continue;
+        }
+        if arg == "--reverse" {
+            order.reverse = true;
+            continue;
+        }
+        if arg == "--limit" || arg == "-n" {
+            let n = args.next().unwrap_or_default();
+            match n.parse() {
+                Ok(n) => order.limit = Some(n),
+                Err(_) => return Err(Error::ParseError(format!("invalid limit: {}", n))),
+            }
+            continue;
+        }
+        if arg == "--template" {
+            let text = args.next().unwrap_or_default();
+            template = Some(Template::parse(&text)?);
+            continue;
+        }
+        if arg == "--graph" {
+            graph = true;
+            continue;
+        }
+        if arg == "--json" {
+            json = true;
+            continue;
+        }
+        if arg == "--count" {
+            count = true;
+            continue;
+        }
+        if arg == "--contains" {
+            let name = args.next().unwrap_or_default();
+            let oids = repo
+                .anyrevs(name.as_str())?
+                .to_oids()?
+                .take(2)
+                .collect::<Result<Vec<_>>>()?;
+            match oids[..] {
+                [oid] => contains = Some(oid),
+                [] => return Err(Error::UnresolvedName(name)),
+                _ => {
+                    return Err(Error::ParseError(format!(
+                        "--contains {} resolves to multiple commits",
+                        name
+                    )))
+                }
+            }
+            continue;
+        }
+        if arg == "-q" || arg == "--quiet" {
+            quiet = true;
+            continue;
+        }
+        if arg == "--pick" {
+            pick = true;
+            continue;
+        }
+        if arg == "--since-last-tag" {
+            let code = match repo.last_tag()? {
+                Some((_name, oid)) => format!("only(., {})", oid),
+                None => "::.".to_string(),
+            };
+            codes.push(code);
+            continue;
+        }
+        codes.push(arg.to_string());
+    }
+    // Flags apply to all expressions, regardless of their positions.
+    for code in codes {
+        let arg: &str = &code;
         if print_ast {
             let ast = Expr::parse(arg)?;
             println!("{:?}", ast);
+        } else if print_plan {
+            print!("{}", repo.plan(arg)?);
+        } else if let Some(oid) = contains {
+            let set = eval(&repo, arg, pick)?;
+            let result = repo.set_contains(&set, oid)?;
+            if !quiet {
+                println!("{}", result);
+            }
+            all_contain &= result;
+        } else if count {
+            let set = eval(&repo, arg, pick)?;
+            println!("{}", set.count()?);
+        } else if graph {
+            let set = eval(&repo, arg, pick)?;
+            print_graph(&repo, &set, order.limit)?;
+        } else if json {
+            let set = eval(&repo, arg, pick)?;
+            print_json(&repo, &set, separator, order)?;
         } else {
-            let set = repo.anyrevs(arg)?;
-            for v in set.iter()? {
-                println!("{}", v?.to_hex());
+            let set = eval(&repo, arg, pick)?;
+            print_set(&repo, &set, separator, show, order, template.as_ref())?;
+        }
+    }
+    Ok(all_contain)
+}
+
+/// Take `-C <path>` or `--repo <path>` out of `args`, so the repo can be
+/// opened before handling other flags. The last path wins.
+fn extract_repo_path(args: impl Iterator<Item = String>) -> Result<(Option<String>, Vec<String>)> {
+    let mut path = None;
+    let mut rest = Vec::new();
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        if arg == "-C" || arg == "--repo" {
+            match args.next() {
+                Some(value) => path = Some(value),
+                None => return Err(Error::ParseError(format!("{} requires a path", arg))),
             }
+        } else {
+            rest.push(arg);
+        }
+    }
+    Ok((path, rest))
+}
+
+/// Evaluate `code`. If `pick` is set, and a short hash is ambiguous, ask
+/// the user to choose a commit. This only happens if stdin and stderr are
+/// terminals. Otherwise, the error lists the candidates.
+fn eval(repo: &Repo, code: &str, pick: bool) -> Result<Set> {
+    let interactive = pick && io::stdin().is_terminal() && io::stderr().is_terminal();
+    let mut expr = Expr::parse(code)?;
+    loop {
+        match repo.anyrevs(expr.clone()) {
+            Err(e) => {
+                let oids = match e.ambiguous_oids() {
+                    Some(oids) => oids,
+                    None => return Err(e),
+                };
+                if !interactive {
+                    let candidates = list_candidates(repo, &oids)?.join("\n");
+                    return Err(Error::ParseError(format!(
+                        "{}\nCandidates:\n{}",
+                        e, candidates
+                    )));
+                }
+                match prompt_pick(repo, &oids)? {
+                    Some(oid) if replace_prefix(&mut expr, &oids, oid) => continue,
+                    _ => return Err(e),
+                }
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Describe `oids` with their summaries, one line per commit.
+fn list_candidates(repo: &Repo, oids: &[git2::Oid]) -> Result<Vec<String>> {
+    let git_repo = repo.git_repo();
+    let mut result = Vec::with_capacity(oids.len());
+    for (i, oid) in oids.iter().enumerate() {
+        let commit = git_repo.find_commit(*oid)?;
+        result.push(format!(
+            "  {}) {} {}",
+            i + 1,
+            oid,
+            commit.summary().unwrap_or("")
+        ));
+    }
+    Ok(result)
+}
+
+/// List `oids` with their summaries. Ask the user to choose one.
+fn prompt_pick(repo: &Repo, oids: &[git2::Oid]) -> Result<Option<git2::Oid>> {
+    eprintln!("The short hash is ambiguous. Candidates:");
+    for line in list_candidates(repo, oids)? {
+        eprintln!("{}", line);
+    }
+    eprint!("Pick a commit [1-{}]: ", oids.len());
+    let _ = io::stderr().flush();
+    let mut line = String::new();
+    if io::stdin().lock().read_line(&mut line).is_err() {
+        return Ok(None);
+    }
+    let picked = match line.trim().parse::<usize>() {
+        Ok(i) if i >= 1 => oids.get(i - 1).cloned(),
+        _ => None,
+    };
+    Ok(picked)
+}
+
+/// Replace names in `expr` that are prefixes of all `oids` with the full hash
+/// of `picked`. Return `true` if anything was replaced.
+fn replace_prefix(expr: &mut Expr, oids: &[git2::Oid], picked: git2::Oid) -> bool {
+    match expr {
+        Expr::Name(name) => {
+            let prefix = name.to_lowercase();
+            let is_prefix = prefix.bytes().all(|b| b.is_ascii_hexdigit())
+                && oids.iter().all(|oid| oid.to_string().starts_with(&prefix));
+            if is_prefix {
+                *name = picked.to_string();
+            }
+            is_prefix
+        }
+        Expr::Fn(_, args) => {
+            let mut replaced = false;
+            for arg in args.iter_mut() {
+                replaced |= replace_prefix(arg, oids, picked);
+            }
+            replaced
+        }
+        Expr::Inlined(_) | Expr::Regex(_) => false,
+    }
+}
+
+/// Print commit hashes in the set, or render `template` for each commit.
+fn print_set(
+    repo: &Repo,
+    set: &Set,
+    separator: &str,
+    show: Show,
+    order: Order,
+    template: Option<&Template>,
+) -> Result<()> {
+    for v in order.iter(set)? {
+        let v = v?;
+        match template {
+            Some(template) => print!("{}{}", template.render(repo, v.to_oid()?)?, separator),
+            None => print!("{}{}", v.to_hex(), separator),
+        }
+        if show != Show::Nothing {
+            print_diff(repo, v.to_oid()?, show)?;
         }
     }
     Ok(())
 }
 
+/// Columns of an ASCII commit graph. Each column is the commit it is
+/// waiting for, or `None` if it is unused.
+#[derive(Default)]
+struct Graph {
+    columns: Vec<Option<git2::Oid>>,
+}
+
+impl Graph {
+    /// Draw `oid`, with its `parents` in the set. `truncated` means some
+    /// parents are not in the set. Return lines before the commit, the graph
+    /// part of the commit line, and lines after the commit.
+    fn next(
+        &mut self,
+        oid: git2::Oid,
+        parents: &[git2::Oid],
+        truncated: bool,
+    ) -> (Vec<String>, String, Vec<String>) {
+        let mut before = Vec::new();
+        let mut after = Vec::new();
+
+        // Columns waiting for this commit join the leftmost one.
+        let waiting: Vec<usize> = (0..self.columns.len())
+            .filter(|&i| self.columns[i] == Some(oid))
+            .collect();
+        let col = match waiting.first() {
+            Some(&col) => col,
+            None => match self.columns.iter().position(|c| c.is_none()) {
+                Some(col) => col,
+                None => {
+                    self.columns.push(None);
+                    self.columns.len() - 1
+                }
+            },
+        };
+        for &j in waiting.iter().skip(1).rev() {
+            before.push(self.join_line(col, j));
+            self.columns.remove(j);
+        }
+        self.columns[col] = Some(oid);
+        let row = self.line(|i| if i == col { '*' } else { '|' });
+
+        if parents.is_empty() && truncated {
+            after.push(self.line(|i| if i == col { '~' } else { '|' }));
+        }
+
+        // Continue the column with the first parent. Open new columns for
+        // other parents of a merge.
+        self.columns[col] = parents.first().cloned();
+        for (i, &parent) in parents.iter().enumerate().skip(1) {
+            let new_col = col + i;
+            after.push(self.split_line(new_col));
+            self.columns.insert(new_col, Some(parent));
+        }
+        while let Some(None) = self.columns.last() {
+            self.columns.pop();
+        }
+        (before, row, after)
+    }
+
+    /// Render used columns with `f(index)`, and unused columns as spaces.
+    fn line(&self, f: impl Fn(usize) -> char) -> String {
+        let cells: Vec<String> = (0..self.columns.len())
+            .map(|i| match self.columns[i] {
+                Some(_) => f(i).to_string(),
+                None => " ".to_string(),
+            })
+            .collect();
+        cells.join(" ").trim_end().to_string()
+    }
+
+    /// Draw column `j` joining column `col`. Columns after `j` shift left.
+    fn join_line(&self, col: usize, j: usize) -> String {
+        let mut chars: Vec<char> = self.line(|_| '|').chars().collect();
+        chars.resize(self.columns.len() * 2, ' ');
+        for c in chars[col * 2 + 1..j * 2 - 1].iter_mut() {
+            if *c == ' ' {
+                *c = '_';
+            }
+        }
+        chars[j * 2] = ' ';
+        chars[j * 2 - 1] = '/';
+        for k in j + 1..self.columns.len() {
+            if chars[k * 2] != ' ' {
+                chars[k * 2] = ' ';
+                chars[k * 2 - 1] = '/';
+            }
+        }
+        chars.into_iter().collect::<String>().trim_end().to_string()
+    }
+
+    /// Draw a new column at `new_col` branching from the left. Columns at
+    /// and after `new_col` shift right.
+    fn split_line(&self, new_col: usize) -> String {
+        let mut chars: Vec<char> = self.line(|_| '|').chars().collect();
+        chars.resize(self.columns.len() * 2 + 1, ' ');
+        for k in (new_col..self.columns.len()).rev() {
+            if chars[k * 2] != ' ' {
+                chars[k * 2] = ' ';
+                chars[k * 2 + 1] = '\\';
+            }
+        }
+        chars[new_col * 2 - 1] = '\\';
+        chars.into_iter().collect::<String>().trim_end().to_string()
+    }
+}
+
+/// Print commits in the set as an ASCII graph, newest first, like
+/// `git log --graph`. Edges to parents outside the set are not drawn. Roots
+/// of the set with such parents are marked by `~`. Stop after `limit`
+/// commits.
+fn print_graph(repo: &Repo, set: &Set, limit: Option<usize>) -> Result<()> {
+    let mut parents: HashMap<git2::Oid, Vec<git2::Oid>> = HashMap::new();
+    for (parent, child) in repo.edges(set)? {
+        parents.entry(child).or_default().push(parent);
+    }
+    let mut truncated: HashMap<git2::Oid, bool> = HashMap::new();
+    for (_parent, child) in repo.dangling_edges(set)? {
+        truncated.insert(child, true);
+    }
+    let git_repo = repo.git_repo();
+    let mut graph = Graph::default();
+    for v in set.iter()?.take(limit.unwrap_or(usize::MAX)) {
+        let oid = v?.to_oid()?;
+        let commit = git_repo.find_commit(oid)?;
+        let short = commit.as_object().short_id()?;
+        let (before, row, after) = graph.next(
+            oid,
+            parents.get(&oid).map(|p| p.as_slice()).unwrap_or_default(),
+            truncated.contains_key(&oid),
+        );
+        for line in before {
+            println!("{}", line);
+        }
+        println!(
+            "{} {} {}",
+            row,
+            short.as_str().unwrap_or(""),
+            commit.summary().unwrap_or("")
+        );
+        for line in after {
+            println!("{}", line);
+        }
+    }
+    Ok(())
+}
+
+/// Print commits in the set as a JSON array. Nothing is printed if any
+/// commit cannot be resolved.
+fn print_json(repo: &Repo, set: &Set, separator: &str, order: Order) -> Result<()> {
+    let git_repo = repo.git_repo();
+    let flags = repo.flags_for(set)?;
+    let mut items = Vec::new();
+    for v in order.iter(set)? {
+        let oid = v?.to_oid()?;
+        let commit = git_repo.find_commit(oid)?;
+        let author = commit.author();
+        let flags = flags.get(&oid).copied().unwrap_or_default();
+        items.push(serde_json::json!({
+            "oid": oid.to_string(),
+            "summary": commit.summary().unwrap_or(""),
+            "author_name": author.name().unwrap_or(""),
+            "author_email": author.email().unwrap_or(""),
+            "committer_time": commit.committer().when().seconds(),
+            "obsolete": flags.obsolete,
+            "public": flags.public,
+            "head": flags.head,
+        }));
+    }
+    print!("{}{}", serde_json::Value::Array(items), separator);
+    Ok(())
+}
+
+/// Print the diff of a commit against its first parent.
+fn print_diff(repo: &Repo, oid: git2::Oid, show: Show) -> Result<()> {
+    let diff = repo.diff_first_parent(oid)?;
+    match show {
+        Show::Patch => {
+            diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
+                if let '+' | '-' | ' ' = line.origin() {
+                    print!("{}", line.origin());
+                }
+                print!("{}", String::from_utf8_lossy(line.content()));
+                true
+            })?;
+        }
+        Show::Stat => {
+            let stats = diff.stats()?;
+            let buf = stats.to_buf(git2::DiffStatsFormat::FULL, 80)?;
+            print!("{}", buf.as_str().unwrap_or(""));
+        }
+        Show::Nothing => (),
+    }
+    Ok(())
+}
+
 fn main() {
     match try_main() {
-        Ok(()) => (),
-        Err(e) => eprintln!("{}", e),
+        Ok(true) => (),
+        Ok(false) => process::exit(1),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
     }
 }
@@ -0,0 +1,139 @@
+use crate::ast::Expr;
+use gitdag::dag::Set;
+use std::collections::HashMap;
+
+/// Structural key of an `Expr`. Expressions with inlined sets have no keys.
+/// Expressions that can change without reloading the commit graph index,
+/// like `.` after a checkout, or `date("today")`, have no keys either.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub(crate) enum ExprKey {
+    Name(String),
+    Fn(String, Vec<ExprKey>),
+    Regex(String),
+}
+
+impl ExprKey {
+    pub(crate) fn from_expr(expr: &Expr) -> Option<Self> {
+        match expr {
+            Expr::Name(name) if is_volatile_name(name) => None,
+            Expr::Name(name) => Some(ExprKey::Name(name.clone())),
+            Expr::Fn(name, _) if VOLATILE_FNS.contains(&name.as_ref()) => None,
+            Expr::Fn(name, args) => {
+                let args = args.iter().map(Self::from_expr).collect::<Option<_>>()?;
+                Some(ExprKey::Fn(name.to_string(), args))
+            }
+            Expr::Inlined(_) => None,
+            Expr::Regex(pattern) => Some(ExprKey::Regex(pattern.clone())),
+        }
+    }
+}
+
+/// Functions that depend on the clock, reflogs, or the git config.
+const VOLATILE_FNS: &[&str] = &[
+    "date",
+    "committerdate",
+    "workdate",
+    "changedsince",
+    "recent",
+    "unreachable",
+    "secret",
+    "fixes",
+];
+
+/// Test if `name` depends on `HEAD`, reflogs, or the git config. For
+/// example, `.`, `@{-1}`, `master@{upstream}`, or `"HEAD~2"`.
+fn is_volatile_name(name: &str) -> bool {
+    name == "."
+        || name == "@"
+        || name.contains("HEAD")
+        || name.contains("@{")
+        || name.contains(['~', '^'])
+}
+
+/// Evaluated sets keyed by expressions. Least recently used entries are
+/// evicted when the cache is full.
+///
+/// Entries are tagged with the version of the commit graph index. Entries
+/// of other versions are not used.
+pub(crate) struct ExprCache {
+    capacity: usize,
+    entries: HashMap<(ExprKey, bool), Entry>,
+    /// Incremented on each access. Used to find the least recently used entry.
+    clock: u64,
+    hits: usize,
+}
+
+struct Entry {
+    set: Set,
+    dag_version: u64,
+    last_used: u64,
+}
+
+impl ExprCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Default::default(),
+            clock: 0,
+            hits: 0,
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Get a cached set. `with_config` is `true` if the expression was
+    /// evaluated with aliases from the git config.
+    pub(crate) fn get(&mut self, key: &(ExprKey, bool), dag_version: u64) -> Option<Set> {
+        self.clock += 1;
+        let entry = self.entries.get_mut(key)?;
+        if entry.dag_version != dag_version {
+            self.entries.remove(key);
+            return None;
+        }
+        entry.last_used = self.clock;
+        self.hits += 1;
+        Some(entry.set.clone())
+    }
+
+    pub(crate) fn insert(&mut self, key: (ExprKey, bool), set: Set, dag_version: u64) {
+        if !self.is_enabled() {
+            return;
+        }
+        self.clock += 1;
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        let entry = Entry {
+            set,
+            dag_version,
+            last_used: self.clock,
+        };
+        self.entries.insert(key, entry);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Number of cache hits.
+    pub(crate) fn hits(&self) -> usize {
+        self.hits
+    }
+}
@@ -1,4 +1,6 @@
+use crate::ext::VertexIterExt;
 use gitdag::dag::Vertex;
+use gitdag::git2::Oid;
 use std::convert::Infallible;
 use thiserror::Error;
 
@@ -17,6 +19,14 @@ pub enum Error {
     #[error("ambiguous prefix: {0:?}")]
     AmbiguousPrefix(Vec<Vertex>),
 
+    /// A name can be resolved to multiple references.
+    #[error("name {0:?} is ambiguous: {1:?}")]
+    AmbiguousName(String, Vec<String>),
+
+    /// `HEAD` points to a reference that does not exist.
+    #[error("HEAD points to {0:?}, which does not exist")]
+    UnbornHead(String),
+
     /// A name cannot be resolved.
     #[error("name {0:?} cannot be resolved")]
     UnresolvedName(String),
@@ -29,11 +39,29 @@ pub enum Error {
     #[error("expect string, got {0}")]
     ExpectString(String),
 
+    /// A vertex is not a commit hash.
+    #[error("vertex {0:?} is not a commit hash")]
+    InvalidVertex(Vertex),
+
+    /// A date or a named period like `thisweek` cannot be parsed.
+    #[error("invalid date: {0}")]
+    InvalidDate(String),
+
     /// An expression cannot be parsed into an AST.
     #[error("{0}")]
     ParseError(String),
 }
 
+impl Error {
+    /// Candidate commits of an `AmbiguousPrefix` error.
+    pub fn ambiguous_oids(&self) -> Option<Vec<Oid>> {
+        match self {
+            Error::AmbiguousPrefix(vertexes) => vertexes.iter().cloned().to_oid_vec().ok(),
+            _ => None,
+        }
+    }
+}
+
 impl From<Infallible> for Error {
     fn from(_e: Infallible) -> Self {
         unreachable!()
@@ -1,22 +1,38 @@
 use crate::ast::Expr;
+use crate::ext::VertexExt;
+use crate::plan::PlanKind;
 use crate::repo::Repo;
 use crate::Error;
 use crate::Result;
+use chrono::Datelike;
+use chrono::Duration;
+use chrono::NaiveDate;
+use dag::nameset::hints::Flags;
 use dag::ops::DagAlgorithm;
 use dag::ops::PrefixLookup;
 use dag::Set;
 use dag::Vertex;
 use gitdag::dag;
+use gitdag::errors::MapDagError;
 use gitdag::git2;
 use globset::Glob;
+use globset::GlobMatcher;
 use hgtime::HgTime;
+use regex::Regex;
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::convert::TryFrom;
 use std::ops::Deref;
+use std::ops::RangeInclusive;
 use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
 
-type EvalFn = Box<dyn Fn(&str, &Repo, &[Expr], &Context) -> Result<Set>>;
+type Function = dyn Fn(&str, &Repo, &[Expr], &Context) -> Result<Set>;
+type EvalFn = Box<Function>;
+type FilterFn = dyn Fn(&git2::Repository, &git2::Commit) -> bool + Send + Sync + 'static;
 
 /// Extra context for `eval`. It can define customized aliases.
 #[derive(Default)]
@@ -28,11 +44,55 @@ pub struct Context {
     /// Extra functions. For example, if "foo" is defined here, "foo(x)" will
     /// use the "foo" function.
     pub fns: HashMap<String, EvalFn>,
+
+    /// Commits to scan by builtin filters, like `author(x)`. Set by `eval_in`.
+    scope: Option<Set>,
+}
+
+impl Context {
+    /// Define a pre-calculated set as `name`.
+    pub fn with_name(&mut self, name: impl Into<String>, set: Set) -> &mut Self {
+        self.names.insert(name.into(), set);
+        self
+    }
+
+    /// Define a function as `name`. `func` takes unevaluated arguments and
+    /// the context. Use
+    /// [`Repo::revs_with_context`](struct.Repo.html#method.revs_with_context)
+    /// to evaluate them, so names and functions defined by the context are
+    /// available.
+    pub fn with_fn(
+        &mut self,
+        name: impl Into<String>,
+        func: impl Fn(&Repo, &[Expr], &Context) -> Result<Set> + 'static,
+    ) -> &mut Self {
+        let func =
+            move |_name: &str, repo: &Repo, args: &[Expr], ctx: &Context| func(repo, args, ctx);
+        self.fns.insert(name.into(), Box::new(func));
+        self
+    }
 }
 
 /// Evaluate an AST. Return the resulting set.
 /// `context` can be used to define customized names or functions.
 pub fn eval(repo: &Repo, expr: &Expr, context: &Context) -> Result<Set> {
+    let key = match expr {
+        Expr::Fn(..) => repo.expr_cache_key(expr, context),
+        _ => None,
+    };
+    if let Some(key) = &key {
+        if let Some(set) = repo.cached_expr(key) {
+            return Ok(set);
+        }
+    }
+    let set = eval_uncached(repo, expr, context)?;
+    if let Some(key) = key {
+        repo.cache_expr(key, set.clone());
+    }
+    Ok(set)
+}
+
+fn eval_uncached(repo: &Repo, expr: &Expr, context: &Context) -> Result<Set> {
     match expr {
         Expr::Name(name) => lookup(repo, name, context),
         Expr::Fn(name, args) => {
@@ -40,6 +100,7 @@ pub fn eval(repo: &Repo, expr: &Expr, context: &Context) -> Result<Set> {
             func(name, repo, args, context)
         }
         Expr::Inlined(set) => Ok(set.clone()),
+        Expr::Regex(_) => Err(Error::UnresolvedName(format!("{:?}", expr))),
     }
 }
 
@@ -53,33 +114,61 @@ fn lookup(repo: &Repo, name: &str, context: &Context) -> Result<Set> {
         return func(name, repo, &[], context);
     }
 
+    if name == "@main" {
+        return main_branch("main", repo, &[], context);
+    }
+
     let args = [Expr::Name(name.to_string())];
 
     // Resolve references.
-    if let Ok(set) = r#ref("lookup", repo, &args, context) {
-        return Ok(set);
+    match r#ref("lookup", repo, &args, context) {
+        Ok(set) => return Ok(set),
+        Err(e @ Error::AmbiguousName(..)) => return Err(e),
+        Err(_) => (),
+    }
+
+    // Git revision syntax, like "HEAD~2" or "master^2" in quoted strings.
+    if name.contains(['~', '^']) {
+        return revparse(repo, name);
     }
 
     // Resolve as commit hash.
     id("lookup", repo, &args, context)
 }
 
+/// Resolve a single commit using git's revision syntax.
+fn revparse(repo: &Repo, name: &str) -> Result<Set> {
+    let commit = repo
+        .git_repo()
+        .revparse_single(name)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|_| Error::UnresolvedName(name.to_string()))?;
+    repo.set_from_oids(std::iter::once(commit.id()))
+}
+
 /// Resolve a function name.
-fn get_function<'a>(
-    name: &str,
-    context: &'a Context,
-) -> Result<&'a dyn Fn(&str, &Repo, &[Expr], &Context) -> Result<Set>> {
+pub(crate) fn get_function<'a>(name: &str, context: &'a Context) -> Result<&'a Function> {
     if let Some(func) = context.fns.get(name) {
         return Ok(func);
     }
     match name {
         "parents" => Ok(&parents),
+        "nthparent" => Ok(&nth_parent),
+        "nthancestor" => Ok(&nth_ancestor),
         "children" => Ok(&children),
         "ancestors" => Ok(&ancestors),
         "descendants" => Ok(&descendants),
         "heads" => Ok(&heads),
         "roots" => Ok(&roots),
+        "linear" => Ok(&linear),
+        "branchpoint" => Ok(&branchpoint),
+        "mergepoint" => Ok(&mergepoint),
+        "mergeof" => Ok(&merge_of),
+        "simplifymerges" => Ok(&simplify_merges),
         "range" => Ok(&range),
+        "paths" => Ok(&range),
+        "allpaths" => Ok(&range),
+        "shortestpath" => Ok(&shortestpath),
         "only" => Ok(&only),
         "ancestor" => Ok(&gca),
         "gca" => Ok(&gca),
@@ -89,8 +178,16 @@ fn get_function<'a>(
         "negate" => Ok(&negate),
         "first" => Ok(&first),
         "last" => Ok(&last),
+        "limit" => Ok(&limit),
+        "reverse" => Ok(&reverse),
+        "min" => Ok(&min),
+        "max" => Ok(&max),
+        "sort" => Ok(&sort),
         "head" => Ok(&head),
         "all" => Ok(&all),
+        "unreachable" => Ok(&unreachable),
+        "secret" => Ok(&secret),
+        "recent" => Ok(&recent),
         "publichead" => Ok(&publichead),
         "drafthead" => Ok(&drafthead),
         "public" => Ok(&public),
@@ -99,12 +196,35 @@ fn get_function<'a>(
         "date" => Ok(&date),
         "committer" => Ok(&committer),
         "committerdate" => Ok(&committer_date),
+        "workdate" => Ok(&work_date),
         "desc" => Ok(&desc),
+        "descexact" => Ok(&desc_exact),
+        "summary" => Ok(&summary),
+        "grep" => Ok(&grep),
         "modifies" => Ok(&modifies),
+        "follow" => Ok(&follow),
+        "emptycommit" => Ok(&emptycommit),
+        "merge" => Ok(&merge),
+        "nonmerge" => Ok(&nonmerge),
+        "bigdiff" => Ok(&bigdiff),
+        "touched" => Ok(&touched),
+        "changedsince" => Ok(&changed_since),
+        "file" => Ok(&file),
+        "duplicates" => Ok(&duplicates),
+        "reverts" => Ok(&reverts),
+        "revertcommits" => Ok(&revert_commits),
+        "fixes" => Ok(&fixes),
+        "cherrypickedfrom" => Ok(&cherry_picked_from),
+        "cherrypicks" => Ok(&cherry_picks),
         "predecessors" => Ok(&predecessors),
         "successors" => Ok(&successors),
+        "rootpredecessor" => Ok(&root_predecessor),
         "obsolete" => Ok(&obsolete),
+        "orphan" => Ok(&orphan),
+        "divergent" => Ok(&divergent),
+        "visibledescendants" => Ok(&visible_descendants),
         "id" => Ok(&id),
+        "main" => Ok(&main_branch),
         "ref" => Ok(&r#ref),
         "tag" => Ok(&tag),
         "present" => Ok(&present),
@@ -141,6 +261,161 @@ fn resolve_string(expr: &Expr) -> Result<String> {
     }
 }
 
+/// Expr -> Date range (inclusive, in seconds since epoch)
+fn resolve_date_range(repo: &Repo, expr: &Expr) -> Result<RangeInclusive<i64>> {
+    let date_str = resolve_string(expr)?;
+    let invalid = || Error::InvalidDate(date_str.clone());
+    let now = repo.now().ok_or_else(invalid)?;
+    if let "today" | "yesterday" | "thisweek" | "thismonth" | "thisyear" = date_str.as_str() {
+        return named_period_range(&date_str, now).ok_or_else(invalid);
+    }
+    let absolute = absolute_date(&date_str, now.unixtime);
+    match HgTime::parse_range(&absolute) {
+        Some(range) => Ok(range.start.unixtime..=range.end.unixtime),
+        None => Err(invalid()),
+    }
+}
+
+/// Rewrite relative dates like `2 days ago` in `date` to absolute ones, so
+/// they are relative to the repo's `now`.
+fn absolute_date(date: &str, now: i64) -> String {
+    if let Some((start, end)) = date.split_once(" to ") {
+        return format!(
+            "{} to {}",
+            absolute_date(start, now),
+            absolute_date(end, now)
+        );
+    }
+    for prefix in [">", "<", "since ", "before "] {
+        if let Some(rest) = date.strip_prefix(prefix) {
+            return format!("{}{}", prefix, absolute_date(rest.trim_start(), now));
+        }
+    }
+    if let Some(days) = date.strip_prefix('-') {
+        if days.parse::<u64>().is_ok() {
+            return absolute_date(&format!("since {} days ago", days), now);
+        }
+    }
+    if date == "now" {
+        return format!("{} 0", now);
+    }
+    if let Some(duration) = date.strip_suffix(" ago") {
+        if let Ok(duration) = duration.parse::<humantime::Duration>() {
+            return format!("{} 0", now - duration.as_secs() as i64);
+        }
+    }
+    date.to_string()
+}
+
+/// Date range of a named period containing `now`, like `thisweek`. Days
+/// are in local time. Weeks start on Monday.
+fn named_period_range(name: &str, now: HgTime) -> Option<RangeInclusive<i64>> {
+    let today = now.to_local().date_naive();
+    let (year, month) = (today.year(), today.month());
+    let (start, end) = match name {
+        "today" => (today, today.succ_opt()?),
+        "yesterday" => (today.pred_opt()?, today),
+        "thisweek" => {
+            let weekday = today.weekday().num_days_from_monday();
+            let start = today - Duration::days(weekday.into());
+            (start, start + Duration::days(7))
+        }
+        "thismonth" => {
+            let end = match month {
+                12 => NaiveDate::from_ymd_opt(year + 1, 1, 1)?,
+                _ => NaiveDate::from_ymd_opt(year, month + 1, 1)?,
+            };
+            (today.with_day(1)?, end)
+        }
+        "thisyear" => (
+            NaiveDate::from_ymd_opt(year, 1, 1)?,
+            NaiveDate::from_ymd_opt(year + 1, 1, 1)?,
+        ),
+        _ => return None,
+    };
+    let start = HgTime::try_from(start.and_hms_opt(0, 0, 0)?).ok()?;
+    let end = HgTime::try_from(end.and_hms_opt(0, 0, 0)?).ok()?;
+    Some(start.unixtime..=end.unixtime - 1)
+}
+
+/// Expr -> usize
+fn resolve_usize(expr: &Expr) -> Result<usize> {
+    let s = resolve_string(expr)?;
+    s.parse()
+        .map_err(|_| Error::ParseError(format!("invalid number: {}", s)))
+}
+
+/// Expr -> usize predicate. For example, ">10" means "more than 10".
+/// Supported operators are `>`, `>=`, `<`, `<=`, `=`. No operator means `=`.
+fn resolve_usize_predicate(expr: &Expr) -> Result<Box<dyn Fn(usize) -> bool + Send + Sync>> {
+    let s = resolve_string(expr)?;
+    let s = s.trim();
+    let (op, n) = [">=", "<=", ">", "<", "="]
+        .iter()
+        .find_map(|op| s.strip_prefix(op).map(|n| (*op, n)))
+        .unwrap_or(("=", s));
+    let n: usize = n
+        .trim()
+        .parse()
+        .map_err(|_| Error::ParseError(format!("invalid comparison: {}", s)))?;
+    Ok(match op {
+        ">=" => Box::new(move |v| v >= n),
+        "<=" => Box::new(move |v| v <= n),
+        ">" => Box::new(move |v| v > n),
+        "<" => Box::new(move |v| v < n),
+        _ => Box::new(move |v| v == n),
+    })
+}
+
+type SignatureMatcher = Box<dyn Fn(&git2::Signature) -> bool + Send + Sync>;
+
+/// Expr -> Regex. Accept regex literals like `/foo/`, and plain strings.
+fn resolve_regex(expr: &Expr) -> Result<Regex> {
+    let pattern = match expr {
+        Expr::Regex(pattern) => pattern.clone(),
+        _ => resolve_string(expr)?,
+    };
+    Regex::new(&pattern).map_err(|e| Error::ParseError(format!("invalid regex: {}", e)))
+}
+
+/// Expr -> signature predicate. With the `re:` prefix, or as a regex literal
+/// like `/^Alice </`, match `name <email>` using a regex. Otherwise, match
+/// substrings of the name or email.
+fn resolve_signature_matcher(expr: &Expr) -> Result<SignatureMatcher> {
+    let regex = match expr {
+        Expr::Regex(_) => resolve_regex(expr)?,
+        _ => {
+            let pattern = resolve_string(expr)?;
+            match pattern.strip_prefix("re:") {
+                Some(pattern) => resolve_regex(&Expr::Name(pattern.to_string()))?,
+                None => {
+                    return Ok(Box::new(move |sig| {
+                        sig.name().unwrap_or("").contains(&pattern)
+                            || sig.email().unwrap_or("").contains(&pattern)
+                    }))
+                }
+            }
+        }
+    };
+    Ok(Box::new(move |sig| {
+        let text = format!(
+            "{} <{}>",
+            sig.name().unwrap_or(""),
+            sig.email().unwrap_or("")
+        );
+        regex.is_match(&text)
+    }))
+}
+
+/// Expr -> Glob matcher
+fn resolve_glob_matcher(expr: &Expr) -> Result<GlobMatcher> {
+    let pattern = resolve_string(expr)?;
+    match Glob::new(&pattern) {
+        Ok(glob) => Ok(glob.compile_matcher()),
+        Err(e) => Err(Error::ParseError(format!("invalid pattern: {}", e))),
+    }
+}
+
 /// Resolve args to a single set.
 fn resolve_single_set(
     func_name: &str,
@@ -171,14 +446,73 @@ fn parents(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Re
     Ok(repo.dag().parents(set)?)
 }
 
+fn nth_parent(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 2, context)?;
+    let set = resolve_set(repo, &args[0], context)?;
+    let n = resolve_usize(&args[1])?;
+    if n == 0 {
+        return Ok(set);
+    }
+    // Parents are in git order. The first parent comes first.
+    let dag = repo.dag();
+    let mut result = Vec::new();
+    for v in set.iter()? {
+        if let Some(parent) = dag.parent_names(v?)?.into_iter().nth(n - 1) {
+            result.push(parent);
+        }
+    }
+    repo.to_set(result)
+}
+
+fn nth_ancestor(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 2, context)?;
+    let set = resolve_set(repo, &args[0], context)?;
+    let n = resolve_usize(&args[1])?;
+    let dag = repo.dag();
+    let mut result = Vec::new();
+    'next: for v in set.iter()? {
+        let mut v = v?;
+        for _ in 0..n {
+            match dag.parent_names(v)?.into_iter().next() {
+                Some(parent) => v = parent,
+                None => continue 'next,
+            }
+        }
+        result.push(v);
+    }
+    repo.to_set(result)
+}
+
 fn children(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    if args.len() == 2 {
+        return bounded_children(func_name, repo, args, context);
+    }
     let set = resolve_single_set(func_name, repo, args, context)?;
     let dag = repo.dag();
     let roots = set.clone();
-    let visible = dag.ancestors(dag.git_heads())?;
+    let visible = all("all", repo, &[], context)?;
     Ok(dag.children(roots)? & visible)
 }
 
+/// `children(x, bound)`: Same as `children(x) & bound`, but only checks
+/// parents of commits in `bound` instead of calculating all children of `x`.
+fn bounded_children(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    let (set, bound) = resolve_double_sets(func_name, repo, args, context)?;
+    let dag = repo.dag();
+    let mut result = Vec::new();
+    for v in bound.iter()? {
+        let v = v?;
+        for parent in dag.parent_names(v.clone())? {
+            if set.contains(&parent)? {
+                result.push(v);
+                break;
+            }
+        }
+    }
+    let visible = all("all", repo, &[], context)?;
+    Ok(repo.to_set(result)? & visible)
+}
+
 fn ancestors(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
     let set = resolve_single_set(func_name, repo, args, context)?;
     Ok(repo.dag().ancestors(set)?)
@@ -188,7 +522,8 @@ fn descendants(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -
     let set = resolve_single_set(func_name, repo, args, context)?;
     let dag = repo.dag();
     let roots = set.clone();
-    Ok(dag.range(roots, dag.git_heads())? | set)
+    let heads = head("head", repo, &[], context)?;
+    Ok(dag.range(roots, heads)? | set)
 }
 
 fn heads(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
@@ -201,11 +536,152 @@ fn roots(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Resu
     Ok(repo.dag().roots(set)?)
 }
 
+fn linear(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    let set = resolve_single_set(func_name, repo, args, context)?;
+    let dag = repo.dag();
+    let visible = all("all", repo, &[], context)?;
+    let visible_children = |v: &Vertex| -> Result<Vec<Vertex>> {
+        let children = dag.children(v.clone().into())? & visible.clone();
+        Ok(children.iter()?.collect::<dag::Result<Vec<_>>>()?)
+    };
+
+    // An edge is "linear" if the child has a single parent, and the parent
+    // has a single visible child. Follow linear edges in both directions.
+    // Merges and branch points end the segment.
+    let mut result = HashSet::new();
+    let mut to_visit = set.iter()?.collect::<dag::Result<Vec<_>>>()?;
+    while let Some(v) = to_visit.pop() {
+        if !result.insert(v.clone()) {
+            continue;
+        }
+        if let [parent] = &dag.parent_names(v.clone())?[..] {
+            if visible_children(parent)?.len() == 1 {
+                to_visit.push(parent.clone());
+            }
+        }
+        if let [child] = &visible_children(&v)?[..] {
+            if dag.parent_names(child.clone())?.len() == 1 {
+                to_visit.push(child.clone());
+            }
+        }
+    }
+    repo.to_set(result)
+}
+
+fn branchpoint(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    let set = resolve_single_set(func_name, repo, args, context)?;
+    let dag = repo.dag();
+    let visible = all("all", repo, &[], context)?;
+    let mut result = Vec::new();
+    for v in set.iter()? {
+        let v = v?;
+        let children = dag.children(v.clone().into())? & visible.clone();
+        if children.count()? > 1 {
+            result.push(v);
+        }
+    }
+    repo.to_set(result)
+}
+
+fn mergepoint(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    let set = resolve_single_set(func_name, repo, args, context)?;
+    let dag = repo.dag();
+    let mut result = Vec::new();
+    for v in set.iter()? {
+        let v = v?;
+        if dag.parent_names(v.clone())?.len() > 1 {
+            result.push(v);
+        }
+    }
+    repo.to_set(result)
+}
+
+fn merge_of(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    let (set, heads) = resolve_double_sets(func_name, repo, args, context)?;
+    let dag = repo.dag();
+    let reaches_set = |v: Vertex| -> Result<bool> {
+        let ancestors = dag.ancestors(v.into())?;
+        Ok(!(ancestors & set.clone()).is_empty()?)
+    };
+    // A merge that brings `x` in has a first parent not reaching `x`, and
+    // other parents (one or more for octopus merges) with one reaching `x`.
+    let mut result = Vec::new();
+    for v in dag.range(set.clone(), heads)?.iter()? {
+        let v = v?;
+        let parents = dag.parent_names(v.clone())?;
+        if parents.len() < 2 || reaches_set(parents[0].clone())? {
+            continue;
+        }
+        for parent in parents.into_iter().skip(1) {
+            if reaches_set(parent)? {
+                result.push(v);
+                break;
+            }
+        }
+    }
+    repo.to_set(result)
+}
+
+fn simplify_merges(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    let set = resolve_single_set(func_name, repo, args, context)?;
+    let dag = repo.dag();
+    // A merge is redundant if the history it joins, restricted to `set`,
+    // has a single head. That is, one parent side already reaches all
+    // commits in `set` that the other parents reach.
+    let mut redundant = Vec::new();
+    for v in set.iter()? {
+        let v = v?;
+        let parents = dag.parent_names(v.clone())?;
+        if parents.len() < 2 {
+            continue;
+        }
+        let joined = dag.ancestors(repo.to_set(parents)?)? & set.clone();
+        if dag.heads(joined)?.count()? == 1 {
+            redundant.push(v);
+        }
+    }
+    Ok(set - repo.to_set(redundant)?)
+}
+
 fn range(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
     let (roots, heads) = resolve_double_sets(func_name, repo, args, context)?;
     Ok(repo.dag().range(roots, heads)?)
 }
 
+fn shortestpath(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    let (roots, heads) = resolve_double_sets(func_name, repo, args, context)?;
+    let dag = repo.dag();
+    let range = dag.range(roots.clone(), heads.clone())?;
+
+    // BFS from heads to roots, following parents within the range.
+    // `next` points to the child that discovered the vertex.
+    let mut next: HashMap<Vertex, Option<Vertex>> = HashMap::new();
+    let mut queue = VecDeque::new();
+    for v in (heads & range.clone()).iter()? {
+        let v = v?;
+        next.insert(v.clone(), None);
+        queue.push_back(v);
+    }
+    while let Some(v) = queue.pop_front() {
+        if roots.contains(&v)? {
+            let mut path = vec![v.clone()];
+            let mut current = v;
+            while let Some(Some(child)) = next.get(&current) {
+                path.push(child.clone());
+                current = child.clone();
+            }
+            return repo.to_set(path);
+        }
+        for parent in dag.parent_names(v.clone())? {
+            if !next.contains_key(&parent) && range.contains(&parent)? {
+                next.insert(parent.clone(), Some(v.clone()));
+                queue.push_back(parent);
+            }
+        }
+    }
+    Ok(Set::empty())
+}
+
 fn only(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
     let (reachable, unreachable) = resolve_double_sets(func_name, repo, args, context)?;
     Ok(repo.dag().only(reachable, unreachable)?)
@@ -218,12 +694,86 @@ fn gca(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result
         let subset = resolve_set(repo, arg, context)?;
         set = set | subset;
     }
-    Ok(repo.dag().gca_all(set)?)
+    gca_all(repo, set)
+}
+
+/// Greatest common ancestors of `set`. Similar to `dag.gca_all`, but stops
+/// intersecting ancestors once the result is empty. This is faster for
+/// large sets with unrelated histories.
+fn gca_all(repo: &Repo, set: Set) -> Result<Set> {
+    let dag = repo.dag();
+    // Common ancestors of `set` are common ancestors of `roots(set)`.
+    let roots = dag.roots(set)?;
+    if roots.count()? <= 2 {
+        return Ok(dag.gca_all(roots)?);
+    }
+    let mut common: Option<Set> = None;
+    for v in roots.iter()? {
+        let ancestors = dag.ancestors(v?.into())?;
+        let next = match common {
+            None => ancestors,
+            Some(common) => common & ancestors,
+        };
+        if next.is_empty()? {
+            return Ok(Set::empty());
+        }
+        common = Some(next);
+    }
+    match common {
+        Some(common) => Ok(dag.heads_ancestors(common)?),
+        None => Ok(Set::empty()),
+    }
 }
 
 fn intersection(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 2, context)?;
+    // Only scan commits in the other side for builtin filters, like
+    // `x & author(y)`.
+    match (
+        is_scopable_filter(&args[0], context),
+        is_scopable_filter(&args[1], context),
+    ) {
+        (false, true) => return eval_in(repo, &eval(repo, &args[0], context)?, &args[1], context),
+        (true, false) => return eval_in(repo, &eval(repo, &args[1], context)?, &args[0], context),
+        _ => {}
+    }
     let (a, b) = resolve_double_sets(func_name, repo, args, context)?;
-    Ok(a & b)
+    // Intersection iterates the left side and tests the right side. Testing
+    // a filter is cheap, iterating it scans all commits.
+    if a.hints().contains(Flags::FILTER) && !b.hints().contains(Flags::FILTER) {
+        Ok(b & a)
+    } else {
+        Ok(a & b)
+    }
+}
+
+/// Test if `expr` is a builtin filter without set arguments, like
+/// `author(x)`. Such filters can scan a smaller base set.
+fn is_scopable_filter(expr: &Expr, context: &Context) -> bool {
+    match expr {
+        Expr::Fn(name, _) if !context.fns.contains_key(name.as_ref()) => matches!(
+            crate::plan::classify(name),
+            (PlanKind::Filter | PlanKind::DiffFilter, 0)
+        ),
+        _ => false,
+    }
+}
+
+/// Evaluate `base & expr`. If `expr` is a builtin filter, only commits in
+/// `base` are tested.
+pub(crate) fn eval_in(repo: &Repo, base: &Set, expr: &Expr, context: &Context) -> Result<Set> {
+    if !is_scopable_filter(expr, context) {
+        return Ok(base.clone() & eval(repo, expr, context)?);
+    }
+    // Arguments of builtin filters are not sets. They do not need names or
+    // functions from `context`. Bypass the expression cache, since the
+    // result depends on `base`.
+    let scoped = Context {
+        scope: Some(base.clone()),
+        ..Default::default()
+    };
+    let set = eval_uncached(repo, expr, &scoped)?;
+    Ok(base.clone() & set)
 }
 
 fn union(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
@@ -261,9 +811,164 @@ fn last(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Resul
     repo.to_set(std::iter::empty())
 }
 
+fn limit(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 2, context)?;
+    let set = resolve_set(repo, &args[0], context)?;
+    let n = resolve_usize(&args[1])?;
+    let vertexes = set.iter()?.take(n).collect::<dag::Result<Vec<_>>>()?;
+    repo.to_set(vertexes)
+}
+
+fn reverse(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    let set = resolve_single_set(func_name, repo, args, context)?;
+    let mut vertexes = set.iter()?.collect::<dag::Result<Vec<_>>>()?;
+    vertexes.reverse();
+    Ok(Set::from_static_names(vertexes))
+}
+
+fn min(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    let set = resolve_single_set(func_name, repo, args, context)?;
+    let items = committer_dates(repo, &set)?;
+    repo.to_set(items.into_iter().min().map(|(_, v)| v))
+}
+
+fn max(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    let set = resolve_single_set(func_name, repo, args, context)?;
+    let items = committer_dates(repo, &set)?;
+    repo.to_set(items.into_iter().max().map(|(_, v)| v))
+}
+
+/// Committer dates of commits in `set`, paired with vertexes. Comparing the
+/// pairs breaks ties of dates by vertexes.
+fn committer_dates(repo: &Repo, set: &Set) -> Result<Vec<(i64, Vertex)>> {
+    let git_repo = repo.git_repo();
+    let mut result = Vec::new();
+    for v in set.iter()? {
+        let vertex = v?;
+        let commit = git_repo.find_commit(vertex.to_oid()?)?;
+        result.push((commit.committer().when().seconds(), vertex));
+    }
+    Ok(result)
+}
+
+fn sort(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 2, context)?;
+    let set = resolve_set(repo, &args[0], context)?;
+    let keys = resolve_string(&args[1])?
+        .split(',')
+        .map(SortKey::parse)
+        .collect::<Result<Vec<_>>>()?;
+
+    struct Item {
+        vertex: Vertex,
+        topo: usize,
+        date: i64,
+        author_date: i64,
+        author: String,
+    }
+
+    let git_repo = repo.git_repo();
+    let mut items = Vec::new();
+    for (topo, v) in repo.dag().sort(&set)?.iter()?.enumerate() {
+        let vertex = v?;
+        let commit = git_repo.find_commit(vertex.to_oid()?)?;
+        let date = commit.committer().when().seconds();
+        let author_date = commit.author().when().seconds();
+        let author = commit.author().name().unwrap_or("").to_string();
+        items.push(Item {
+            vertex,
+            topo,
+            date,
+            author_date,
+            author,
+        });
+    }
+
+    // Apply keys from right to left. Stable sorts keep the order decided by
+    // later keys for ties.
+    for (key, reversed) in keys.iter().rev() {
+        items.sort_by(|a, b| {
+            let ordering = match key {
+                SortKey::Topo => a.topo.cmp(&b.topo),
+                SortKey::Date => a.date.cmp(&b.date),
+                SortKey::AuthorDate => a.author_date.cmp(&b.author_date),
+                SortKey::Author => a.author.cmp(&b.author),
+            };
+            if *reversed {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+
+    Ok(Set::from_static_names(items.into_iter().map(|i| i.vertex)))
+}
+
+/// Key used by `sort`.
+enum SortKey {
+    /// The commit graph order. Heads first.
+    Topo,
+    /// Committer date. Older first.
+    Date,
+    /// Author date. Older first.
+    AuthorDate,
+    /// Author name.
+    Author,
+}
+
+impl SortKey {
+    /// Parse a key like `date`, or `-date` (reversed).
+    fn parse(key: &str) -> Result<(Self, bool)> {
+        let key = key.trim();
+        let (name, reversed) = match key.strip_prefix('-') {
+            Some(name) => (name, true),
+            None => (key, false),
+        };
+        let key = match name {
+            "topo" => SortKey::Topo,
+            "date" => SortKey::Date,
+            "authordate" => SortKey::AuthorDate,
+            "author" => SortKey::Author,
+            _ => return Err(Error::ParseError(format!("unknown sort key: {}", key))),
+        };
+        Ok((key, reversed))
+    }
+}
+
 fn head(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
     ensure_arg_count(func_name, args, 0, context)?;
-    Ok(repo.dag().git_heads())
+    repo.cached_set("head", |repo| {
+        let dag = repo.dag();
+        let matchers = hidden_ref_matchers(repo)?;
+        if matchers.is_empty() {
+            return Ok(dag.git_heads());
+        }
+        let refs = dag.git_references();
+        let iter = refs
+            .iter()
+            .filter(|(k, _)| !matchers.iter().any(|m| m.is_match(k)))
+            .map(|(_, v)| v.clone());
+        Ok(dag.heads_ancestors(repo.to_set(iter)?)?)
+    })
+}
+
+/// Glob matchers of references to hide from `head()`, configured by
+/// `revs.hiddenrefs`. For example, `refs/wip/**`.
+fn hidden_ref_matchers(repo: &Repo) -> Result<Vec<GlobMatcher>> {
+    let config = repo.git_repo().config()?;
+    let mut result = Vec::new();
+    if let Ok(entries) = config.multivar("revs.hiddenrefs", None) {
+        for entry in &entries {
+            if let Some(pattern) = entry?.value() {
+                match Glob::new(pattern) {
+                    Ok(glob) => result.push(glob.compile_matcher()),
+                    Err(e) => return Err(Error::ParseError(format!("invalid pattern: {}", e))),
+                }
+            }
+        }
+    }
+    Ok(result)
 }
 
 fn all(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
@@ -274,6 +979,60 @@ fn all(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result
     })
 }
 
+fn unreachable(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 0, context)?;
+    let dag = repo.dag();
+    // Commits are never removed from the index. Indexed commits that are
+    // not reachable from references are either referred by reflogs, or
+    // dangling.
+    let set = dag.all()? - dag.ancestors(dag.git_heads())?;
+    existing_commits(repo, set)
+}
+
+fn secret(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 0, context)?;
+    let dag = repo.dag();
+    // Indexed draft commits not reachable from any references, including
+    // hidden ones. They are only referred by reflogs, or nothing.
+    let set = dag.all()? - dag.ancestors(dag.git_heads())? - public("public", repo, &[], context)?;
+    existing_commits(repo, set)
+}
+
+/// Commits in `set` that exist in the object database. Skip commits deleted
+/// by garbage collection.
+fn existing_commits(repo: &Repo, set: Set) -> Result<Set> {
+    let odb = repo.git_repo().odb()?;
+    let mut result = Vec::new();
+    for v in set.iter()? {
+        let v = v?;
+        if odb.exists(v.to_oid()?) {
+            result.push(v);
+        }
+    }
+    repo.to_set(result)
+}
+
+fn recent(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 1, context)?;
+    let n = resolve_usize(&args[0])?;
+    if n == 0 {
+        return Ok(Set::empty());
+    }
+    // Walk parents generation by generation. The cost is proportional to
+    // the window size, not the size of the repo.
+    let dag = repo.dag();
+    let mut frontier = dag.sort(&head("head", repo, &[], context)?)?;
+    let mut result = frontier.clone();
+    for _ in 1..n {
+        frontier = dag.parents(frontier)? - result.clone();
+        if frontier.is_empty()? {
+            break;
+        }
+        result = result | frontier.clone();
+    }
+    Ok(result)
+}
+
 fn publichead(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
     ensure_arg_count(func_name, args, 0, context)?;
     repo.cached_set("publichead", |repo| {
@@ -295,6 +1054,7 @@ fn drafthead(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) ->
 
 fn public(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
     ensure_arg_count(func_name, args, 0, context)?;
+    repo.wait_for_phases()?;
     repo.cached_set("public", |repo| {
         let dag = repo.dag();
         Ok(dag.ancestors(publichead("publichead", repo, &[], context)?)?)
@@ -303,6 +1063,7 @@ fn public(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Res
 
 fn draft(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
     ensure_arg_count(func_name, args, 0, context)?;
+    repo.wait_for_phases()?;
     repo.cached_set("draft", |repo| {
         let dag = repo.dag();
         Ok(dag.ancestors(head("drafthead", repo, &[], context)?)?
@@ -312,26 +1073,16 @@ fn draft(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Resu
 
 fn author(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
     ensure_arg_count(func_name, args, 1, context)?;
-    let name = resolve_string(&args[0])?;
-    filter_set(repo, move |commit| {
-        let author = commit.author();
-        author.name().unwrap_or("").contains(&name) || author.email().unwrap_or("").contains(&name)
+    let matcher = resolve_signature_matcher(&args[0])?;
+    filter_set_in(repo, context.scope.clone(), move |commit| {
+        matcher(&commit.author())
     })
 }
 
 fn date(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
     ensure_arg_count(func_name, args, 1, context)?;
-    let date_str = resolve_string(&args[0])?;
-    let date_range = match HgTime::parse_range(&date_str) {
-        Some(range) => range.start.unixtime..=range.end.unixtime,
-        None => {
-            return Err(crate::Error::ParseError(format!(
-                "invalid date: {}",
-                date_str
-            )))
-        }
-    };
-    filter_set(repo, move |commit| {
+    let date_range = resolve_date_range(repo, &args[0])?;
+    filter_set_in(repo, context.scope.clone(), move |commit| {
         let author = commit.author();
         let epoch = author.when().seconds();
         date_range.contains(&epoch)
@@ -340,54 +1091,137 @@ fn date(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Resul
 
 fn committer_date(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
     ensure_arg_count(func_name, args, 1, context)?;
-    let date_str = resolve_string(&args[0])?;
-    let date_range = match HgTime::parse_range(&date_str) {
-        Some(range) => range.start.unixtime..=range.end.unixtime,
-        None => {
-            return Err(crate::Error::ParseError(format!(
-                "invalid date: {}",
-                date_str
-            )))
-        }
-    };
-    filter_set(repo, move |commit| {
+    let date_range = resolve_date_range(repo, &args[0])?;
+    filter_set_in(repo, context.scope.clone(), move |commit| {
         let committer = commit.committer();
         let epoch = committer.when().seconds();
         date_range.contains(&epoch)
     })
 }
 
+fn work_date(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 1, context)?;
+    let date_range = resolve_date_range(repo, &args[0])?;
+    filter_set_in(repo, context.scope.clone(), move |commit| {
+        // For merges, approximate the time of the merged work by the latest
+        // author date of the merged (non-first) parents.
+        let epoch = commit
+            .parents()
+            .skip(1)
+            .map(|parent| parent.author().when().seconds())
+            .max()
+            .unwrap_or_else(|| commit.author().when().seconds());
+        date_range.contains(&epoch)
+    })
+}
+
 fn committer(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
     ensure_arg_count(func_name, args, 1, context)?;
-    let name = resolve_string(&args[0])?;
-    filter_set(repo, move |commit| {
-        let author = commit.committer();
-        author.name().unwrap_or("").contains(&name) || author.email().unwrap_or("").contains(&name)
+    let matcher = resolve_signature_matcher(&args[0])?;
+    filter_set_in(repo, context.scope.clone(), move |commit| {
+        matcher(&commit.committer())
     })
 }
 
 fn desc(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 1, context)?;
+    let text = resolve_string(&args[0])?.to_lowercase();
+    filter_set_in(repo, context.scope.clone(), move |commit| {
+        let message = String::from_utf8_lossy(commit.message_bytes());
+        message.to_lowercase().contains(&text)
+    })
+}
+
+fn desc_exact(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
     ensure_arg_count(func_name, args, 1, context)?;
     let text = resolve_string(&args[0])?;
-    filter_set(repo, move |commit| {
+    filter_set_in(repo, context.scope.clone(), move |commit| {
         commit.summary().unwrap_or("").contains(&text)
     })
 }
 
+fn grep(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 1, context)?;
+    let regex = resolve_regex(&args[0])?;
+    filter_set_in(repo, context.scope.clone(), move |commit| {
+        regex.is_match(commit.message().unwrap_or(""))
+    })
+}
+
+fn summary(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 1, context)?;
+    let text = resolve_string(&args[0])?;
+    filter_set_in(repo, context.scope.clone(), move |commit| {
+        commit.summary() == Some(text.as_str())
+    })
+}
+
+fn follow(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 1, context)?;
+    let path = PathBuf::from(resolve_string(&args[0])?);
+    let dag = repo.dag();
+    let git_repo = repo.git_repo();
+
+    // Names of the file to track in ancestors, learned from renames in
+    // descendants. Visit descendants first so names are known before
+    // visiting ancestors.
+    let mut renamed_from: HashMap<Vertex, HashSet<PathBuf>> = HashMap::new();
+    let mut result = Vec::new();
+    for v in all("all", repo, &[], context)?.iter()? {
+        let v = v?;
+        let mut names = renamed_from.remove(&v).unwrap_or_default();
+        names.insert(path.clone());
+        let commit = git_repo.find_commit(v.to_oid()?)?;
+        let mut diff = diff_first_parent(git_repo, &commit)?;
+        let is_tracked = |p: Option<&Path>| matches!(p, Some(p) if names.contains(p));
+        // Rename detection is expensive. Only do it if a tracked file is added.
+        let maybe_renamed = diff
+            .deltas()
+            .any(|d| d.status() == git2::Delta::Added && is_tracked(d.new_file().path()));
+        if maybe_renamed {
+            diff.find_similar(None)?;
+        }
+        let mut touched = false;
+        let mut old_names = Vec::new();
+        for delta in diff.deltas() {
+            let (old_path, new_path) = (delta.old_file().path(), delta.new_file().path());
+            if is_tracked(old_path) || is_tracked(new_path) {
+                touched = true;
+                if delta.status() == git2::Delta::Renamed && is_tracked(new_path) {
+                    old_names.extend(old_path.map(|p| p.to_path_buf()));
+                }
+            }
+        }
+        if touched {
+            result.push(v.clone());
+        }
+        // Merges are compared with their first parents. Tracked names are
+        // passed to all parents.
+        names.extend(old_names);
+        for parent in dag.parent_names(v)? {
+            renamed_from
+                .entry(parent)
+                .or_default()
+                .extend(names.iter().cloned());
+        }
+    }
+    repo.to_set(result)
+}
+
 fn modifies(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
     ensure_arg_count(func_name, args, 1, context)?;
     let path = resolve_string(&args[0])?;
-    filter_set(repo, move |commit| {
+    filter_set_in(repo, context.scope.clone(), move |commit| {
         let path = Path::new(&path);
         (|| -> Result<bool> {
             let tree = commit.tree()?;
-            let entry = tree.get_path(&path)?;
+            let entry = tree.get_path(path)?;
             let mut parent_not_found_count = 0;
             let parents = commit.parents();
             let parents_len = parents.len();
             for parent in parents {
                 let parent_tree = parent.tree()?;
-                let parent_entry = match parent_tree.get_path(&path) {
+                let parent_entry = match parent_tree.get_path(path) {
                     Err(e) => match e.code() {
                         git2::ErrorCode::NotFound => {
                             parent_not_found_count += 1;
@@ -410,6 +1244,177 @@ fn modifies(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> R
     })
 }
 
+fn emptycommit(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 0, context)?;
+    filter_set_in(repo, context.scope.clone(), move |commit| {
+        match commit.parent(0) {
+            Ok(parent) => parent.tree_id() == commit.tree_id(),
+            Err(_) => commit.tree().map(|t| t.is_empty()).unwrap_or(false),
+        }
+    })
+}
+
+fn merge(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 0, context)?;
+    filter_set_in(repo, context.scope.clone(), |commit| {
+        commit.parent_count() >= 2
+    })
+}
+
+fn nonmerge(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 0, context)?;
+    filter_set_in(repo, context.scope.clone(), |commit| {
+        commit.parent_count() < 2
+    })
+}
+
+fn bigdiff(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 1, context)?;
+    let lines = resolve_usize(&args[0])?;
+    filter_set_with_repo(repo, context.scope.clone(), move |git_repo, commit| {
+        (|| -> Result<bool> {
+            let stats = diff_first_parent(git_repo, commit)?.stats()?;
+            Ok(stats.insertions() + stats.deletions() > lines)
+        })()
+        .unwrap_or(false)
+    })
+}
+
+fn touched(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 1, context)?;
+    let predicate = resolve_usize_predicate(&args[0])?;
+    filter_set_with_repo(repo, context.scope.clone(), move |git_repo, commit| {
+        match diff_first_parent(git_repo, commit) {
+            Ok(diff) => predicate(diff.deltas().len()),
+            Err(_) => false,
+        }
+    })
+}
+
+fn file(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 1, context)?;
+    let matcher = resolve_glob_matcher(&args[0])?;
+    filter_set_with_repo(repo, context.scope.clone(), move |git_repo, commit| {
+        diff_touches_glob(git_repo, commit, &matcher)
+    })
+}
+
+fn changed_since(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 2, context)?;
+    let matcher = resolve_glob_matcher(&args[0])?;
+    let date_range = resolve_date_range(repo, &args[1])?;
+    filter_set_with_repo(repo, context.scope.clone(), move |git_repo, commit| {
+        let epoch = commit.author().when().seconds();
+        date_range.contains(&epoch) && diff_touches_glob(git_repo, commit, &matcher)
+    })
+}
+
+fn duplicates(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    let (set, other) = resolve_double_sets(func_name, repo, args, context)?;
+    let mut patch_ids = HashSet::new();
+    for v in other.iter()? {
+        if let Some(id) = repo.patch_id(v?.to_oid()?)? {
+            patch_ids.insert(id);
+        }
+    }
+    let mut result = Vec::new();
+    for v in set.iter()? {
+        let v = v?;
+        if let Some(id) = repo.patch_id(v.to_oid()?)? {
+            if patch_ids.contains(&id) {
+                result.push(v);
+            }
+        }
+    }
+    repo.to_set(result)
+}
+
+fn reverts(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    let set = resolve_single_set(func_name, repo, args, context)?;
+    filter_set(repo, move |commit| {
+        referenced_oids(commit.message().unwrap_or(""), REVERT_PREFIX)
+            .into_iter()
+            .any(|oid| {
+                set.contains(&Vertex::copy_from(oid.as_bytes()))
+                    .unwrap_or(false)
+            })
+    })
+}
+
+fn revert_commits(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 0, context)?;
+    filter_set_in(repo, context.scope.clone(), move |commit| {
+        !referenced_oids(commit.message().unwrap_or(""), REVERT_PREFIX).is_empty()
+    })
+}
+
+fn cherry_picked_from(
+    func_name: &str,
+    repo: &Repo,
+    args: &[Expr],
+    context: &Context,
+) -> Result<Set> {
+    let set = resolve_single_set(func_name, repo, args, context)?;
+    filter_set(repo, move |commit| {
+        referenced_oids(commit.message().unwrap_or(""), CHERRY_PICK_PREFIX)
+            .into_iter()
+            .any(|oid| {
+                set.contains(&Vertex::copy_from(oid.as_bytes()))
+                    .unwrap_or(false)
+            })
+    })
+}
+
+fn cherry_picks(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 0, context)?;
+    filter_set_in(repo, context.scope.clone(), move |commit| {
+        !referenced_oids(commit.message().unwrap_or(""), CHERRY_PICK_PREFIX).is_empty()
+    })
+}
+
+/// Written by `git revert`.
+const REVERT_PREFIX: &str = "This reverts commit ";
+
+/// Written by `git cherry-pick -x`.
+const CHERRY_PICK_PREFIX: &str = "(cherry picked from commit ";
+
+/// Parse commit hashes from lines like "This reverts commit <hash>." in
+/// `message`. `prefix` is the text before the hash.
+fn referenced_oids(message: &str, prefix: &str) -> Vec<git2::Oid> {
+    message
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix(prefix))
+        .filter_map(|rest| {
+            let hex = rest.split(|c: char| !c.is_ascii_hexdigit()).next()?;
+            if hex.len() == 40 {
+                git2::Oid::from_str(hex).ok()
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+fn fixes(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 1, context)?;
+    let issue = resolve_string(&args[0])?;
+    let pattern = repo
+        .git_repo()
+        .config()
+        .and_then(|config| config.get_string("revs.issue-pattern"))
+        .unwrap_or_else(|_| DEFAULT_ISSUE_PATTERN.to_string());
+    let regex = Regex::new(&pattern)
+        .map_err(|e| Error::ParseError(format!("invalid issue pattern: {}", e)))?;
+    filter_set_in(repo, context.scope.clone(), move |commit| {
+        let message = commit.message().unwrap_or("");
+        regex.find_iter(message).any(|m| m.as_str() == issue)
+    })
+}
+
+/// Issue references like `#123`. Matches both trailers (`Fixes: #123`) and
+/// inline mentions (`fix crash (#123)`).
+const DEFAULT_ISSUE_PATTERN: &str = r"#[0-9]+\b";
+
 fn predecessors(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
     let set = resolve_single_set(func_name, repo, args, context)?;
     let dag = repo.dag();
@@ -426,6 +1431,17 @@ fn successors(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) ->
     Ok(dag.sort(&set)?.flatten()?)
 }
 
+fn root_predecessor(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    let set = resolve_single_set(func_name, repo, args, context)?;
+    let dag = repo.dag();
+    let mutdag = repo.mutation_dag()?;
+    let tracked = set.clone() & mutdag.all()?;
+    // Only consider predecessors that are still visible.
+    let visible = mutdag.ancestors(tracked.clone())? & dag.all()?;
+    let set = mutdag.roots(visible)? | (set - tracked);
+    Ok(dag.sort(&set)?.flatten()?)
+}
+
 fn obsolete(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
     ensure_arg_count(func_name, args, 0, context)?;
     let mutdag = repo.mutation_dag()?;
@@ -435,16 +1451,68 @@ fn obsolete(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> R
     Ok(repo.dag().sort(&set)?.flatten()?)
 }
 
+fn orphan(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 0, context)?;
+    let dag = repo.dag();
+    let draft = draft("draft", repo, &[], context)?;
+    let obsolete = obsolete("obsolete", repo, &[], context)?;
+    let set = (dag.descendants(obsolete.clone())? & draft) - obsolete;
+    Ok(dag.sort(&set)?.flatten()?)
+}
+
+fn divergent(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 0, context)?;
+    let mutdag = repo.mutation_dag()?;
+    let draft = draft("draft", repo, &[], context)?;
+    let obsolete = obsolete("obsolete", repo, &[], context)?;
+    let visible = draft - obsolete;
+    // Commits rewritten at least once. They might be no longer visible.
+    let rewritten = mutdag.all()? - mutdag.heads(mutdag.all()?)?;
+    let mut set = Set::empty();
+    for v in rewritten.iter()? {
+        let successors = mutdag.descendants(Set::from_static_names(vec![v?]))? & visible.clone();
+        if successors.count()? > 1 {
+            set = set | successors;
+        }
+    }
+    Ok(repo.dag().sort(&set)?.flatten()?)
+}
+
+fn visible_descendants(
+    func_name: &str,
+    repo: &Repo,
+    args: &[Expr],
+    context: &Context,
+) -> Result<Set> {
+    let set = descendants(func_name, repo, args, context)?;
+    let obsolete = obsolete("obsolete", repo, &[], context)?;
+    Ok(set - obsolete)
+}
+
 fn id(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
     ensure_arg_count(func_name, args, 1, context)?;
     let name = resolve_string(&args[0])?;
     match name.as_ref() {
         "." | "@" | "HEAD" => {
-            let id = repo.git_repo().head()?.peel_to_commit()?.id();
+            let id = resolve_head(repo)?;
             let v = Vertex::copy_from(id.as_bytes());
             repo.to_set(std::iter::once(v))
         }
+        "ORIG_HEAD" | "MERGE_HEAD" | "FETCH_HEAD" | "CHERRY_PICK_HEAD" => {
+            let ids = resolve_pseudo_ref(repo, &name)?;
+            repo.to_set(ids.into_iter().map(|id| Vertex::copy_from(id.as_bytes())))
+        }
         _ => {
+            if let Some(n) = previous_checkout_index(&name) {
+                let id = resolve_previous_checkout(repo, &name, n)?;
+                let v = Vertex::copy_from(id.as_bytes());
+                return repo.to_set(std::iter::once(v));
+            }
+            if let Some((branch, push)) = tracking_suffix(&name) {
+                let id = resolve_tracking(repo, &name, branch, push)?;
+                let v = Vertex::copy_from(id.as_bytes());
+                return repo.to_set(std::iter::once(v));
+            }
             if let Some(bin_hex) = normalize_hex(&name) {
                 let matched = repo.dag().vertexes_by_hex_prefix(&bin_hex, 3)?;
                 match matched.len() {
@@ -459,6 +1527,155 @@ fn id(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<
     }
 }
 
+/// Resolve `HEAD` to a commit. Follow the symbolic `HEAD` explicitly so a
+/// missing target (common in bare repos, where `HEAD` can point to a branch
+/// that was never pushed) is reported clearly.
+fn resolve_head(repo: &Repo) -> Result<git2::Oid> {
+    let git_repo = repo.git_repo();
+    let head = git_repo.find_reference("HEAD")?;
+    let head = match head.symbolic_target() {
+        Some(target) => match git_repo.find_reference(target) {
+            Ok(reference) => reference,
+            Err(e) if e.code() == git2::ErrorCode::NotFound => {
+                return Err(Error::UnbornHead(target.to_string()));
+            }
+            Err(e) => return Err(e.into()),
+        },
+        None => head,
+    };
+    Ok(head.peel_to_commit()?.id())
+}
+
+/// Parse `@{-n}` into `n`. `n` must be positive.
+fn previous_checkout_index(name: &str) -> Option<usize> {
+    let n = name.strip_prefix("@{-")?.strip_suffix('}')?;
+    n.parse().ok().filter(|&n| n > 0)
+}
+
+/// Parse `branch@{upstream}`, `branch@{u}`, or `branch@{push}` into the
+/// branch name, and whether it is `push`. The branch name can be empty.
+fn tracking_suffix(name: &str) -> Option<(&str, bool)> {
+    if let Some(branch) = name.strip_suffix("@{push}") {
+        return Some((branch, true));
+    }
+    ["@{upstream}", "@{u}"]
+        .iter()
+        .find_map(|suffix| name.strip_suffix(suffix))
+        .map(|branch| (branch, false))
+}
+
+/// Resolve the upstream, or the push destination of a local branch, like
+/// git. An empty branch name means the current branch.
+fn resolve_tracking(repo: &Repo, name: &str, branch: &str, push: bool) -> Result<git2::Oid> {
+    let git_repo = repo.git_repo();
+    let unresolved = |reason: String| Error::UnresolvedName(format!("{} ({})", name, reason));
+    let branch = match branch {
+        "" | "HEAD" => {
+            let head = git_repo.find_reference("HEAD")?;
+            match head
+                .symbolic_target()
+                .and_then(|t| t.strip_prefix("refs/heads/"))
+            {
+                Some(branch) => branch.to_string(),
+                None => return Err(unresolved("HEAD is not on a branch".to_string())),
+            }
+        }
+        _ => branch.to_string(),
+    };
+    let config = git_repo.config()?;
+    let push_default = config.get_string("push.default").unwrap_or_default();
+    if push && push_default != "upstream" && push_default != "tracking" {
+        // Push to the branch with the same name, on the push remote.
+        let remote = [
+            format!("branch.{}.pushRemote", branch),
+            "remote.pushDefault".to_string(),
+            format!("branch.{}.remote", branch),
+        ]
+        .iter()
+        .find_map(|key| config.get_string(key).ok());
+        let remote = match remote {
+            Some(remote) => remote,
+            None => {
+                let reason = format!("no push remote configured for branch {}", branch);
+                return Err(unresolved(reason));
+            }
+        };
+        let ref_name = format!("refs/remotes/{}/{}", remote, branch);
+        return git_repo
+            .refname_to_id(&ref_name)
+            .map_err(|_| unresolved(format!("{} does not exist", ref_name)));
+    }
+    let local = git_repo
+        .find_branch(&branch, git2::BranchType::Local)
+        .map_err(|_| unresolved(format!("branch {} does not exist", branch)))?;
+    let upstream = local
+        .upstream()
+        .map_err(|_| unresolved(format!("no upstream configured for branch {}", branch)))?;
+    let oid = upstream.get().peel_to_commit()?.id();
+    Ok(oid)
+}
+
+/// Resolve `@{-n}`, the `n`-th branch or commit checked out before the
+/// current one, by scanning `checkout:` entries in the `HEAD` reflog.
+fn resolve_previous_checkout(repo: &Repo, name: &str, n: usize) -> Result<git2::Oid> {
+    let git_repo = repo.git_repo();
+    let unresolved = || Error::UnresolvedName(name.to_string());
+    let reflog = git_repo.reflog("HEAD")?;
+    // Entries are ordered from the newest to the oldest.
+    let from = reflog
+        .iter()
+        .filter_map(|entry| {
+            let message = entry.message()?.strip_prefix("checkout: moving from ")?;
+            let (from, _to) = message.split_once(" to ")?;
+            Some(from.to_string())
+        })
+        .nth(n - 1)
+        .ok_or_else(unresolved)?;
+    // `from` is a branch name, or a commit hash for a detached `HEAD`.
+    if let Ok(reference) = git_repo.find_reference(&format!("refs/heads/{}", from)) {
+        return Ok(reference.peel_to_commit()?.id());
+    }
+    match git2::Oid::from_str(&from) {
+        Ok(id) if from.len() == 40 => Ok(id),
+        _ => Err(unresolved()),
+    }
+}
+
+fn main_branch(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
+    ensure_arg_count(func_name, args, 0, context)?;
+    let name = repo.main_branch_name();
+    match resolve_precise_name(repo, &[name.to_string()])? {
+        Some(set) => Ok(set),
+        None => Err(Error::UnresolvedName(name.to_string())),
+    }
+}
+
+/// Resolve pseudo references like `ORIG_HEAD` by reading files in the git
+/// directory. `MERGE_HEAD` can have multiple commits for octopus merges.
+/// `FETCH_HEAD` resolves to its first commit, like `git rev-parse`.
+fn resolve_pseudo_ref(repo: &Repo, name: &str) -> Result<Vec<git2::Oid>> {
+    let path = repo.git_repo().path().join(name);
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return Err(Error::UnresolvedName(name.to_string())),
+    };
+    let mut ids = Vec::new();
+    for line in content.lines() {
+        let hex = line.split_whitespace().next().unwrap_or("");
+        match git2::Oid::from_str(hex) {
+            Ok(id) if hex.len() == 40 => ids.push(id),
+            _ => continue,
+        }
+        if name == "FETCH_HEAD" {
+            break;
+        }
+    }
+    if ids.is_empty() {
+        return Err(Error::UnresolvedName(name.to_string()));
+    }
+    Ok(ids)
+}
+
 fn resolve_precise_name(repo: &Repo, candidates: &[String]) -> Result<Option<Set>> {
     let refs = repo.dag().git_references();
     for name in candidates.iter() {
@@ -469,6 +1686,73 @@ fn resolve_precise_name(repo: &Repo, candidates: &[String]) -> Result<Option<Set
     Ok(None)
 }
 
+/// Resolve `name` as a remote branch, like `refs/remotes/<remote>/<name>`.
+/// If multiple remotes match, prefer the default remote.
+fn resolve_remote_name(repo: &Repo, name: &str) -> Result<Option<Set>> {
+    if name == "HEAD" {
+        return Ok(None);
+    }
+    let refs = repo.dag().git_references();
+    let matched: Vec<(&String, &Vertex)> = refs
+        .iter()
+        .filter(|(k, _)| match k.strip_prefix("refs/remotes/") {
+            Some(rest) => rest.split_once('/').map(|(_, branch)| branch) == Some(name),
+            None => false,
+        })
+        .collect();
+    match &matched[..] {
+        [] => Ok(None),
+        [(_, v)] => Ok(Some(repo.to_set(std::iter::once((*v).clone()))?)),
+        _ => {
+            if let Some(remote) = repo.default_remote() {
+                let preferred = format!("refs/remotes/{}/{}", remote, name);
+                if let Some(v) = refs.get(&preferred) {
+                    return Ok(Some(repo.to_set(std::iter::once(v.clone()))?));
+                }
+            }
+            let names = matched.into_iter().map(|(k, _)| k.clone()).collect();
+            Err(Error::AmbiguousName(name.to_string(), names))
+        }
+    }
+}
+
+/// Whether references can be resolved ignoring case. Configured by
+/// `revs.refs-case-insensitive`.
+fn refs_case_insensitive(repo: &Repo) -> bool {
+    match repo.git_repo().config() {
+        Ok(config) => config
+            .get_bool("revs.refs-case-insensitive")
+            .unwrap_or(false),
+        Err(_) => false,
+    }
+}
+
+/// Similar to `resolve_precise_name`, but ignore case. Error if a candidate
+/// matches multiple references that only differ by case.
+fn resolve_case_insensitive_name(
+    repo: &Repo,
+    name: &str,
+    candidates: &[String],
+) -> Result<Option<Set>> {
+    let refs = repo.dag().git_references();
+    for candidate in candidates {
+        let candidate = candidate.to_lowercase();
+        let matched: Vec<_> = refs
+            .iter()
+            .filter(|(k, _)| k.to_lowercase() == candidate)
+            .collect();
+        match &matched[..] {
+            [] => continue,
+            [(_, v)] => return Ok(Some(repo.to_set(std::iter::once((*v).clone()))?)),
+            _ => {
+                let names = matched.into_iter().map(|(k, _)| k.clone()).collect();
+                return Err(Error::AmbiguousName(name.to_string(), names));
+            }
+        }
+    }
+    Ok(None)
+}
+
 fn resolve_glob_name(repo: &Repo, glob: &str) -> Result<Option<Set>> {
     let refs = repo.dag().git_references();
     if let Ok(glob) = Glob::new(&format!("refs/{}", glob)) {
@@ -485,7 +1769,7 @@ fn resolve_glob_name(repo: &Repo, glob: &str) -> Result<Option<Set>> {
 fn r#ref(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
     let refs = repo.dag().git_references();
     // No arguments: all references.
-    if args.len() == 0 {
+    if args.is_empty() {
         return repo.to_set(refs.values().cloned());
     }
     ensure_arg_count(func_name, args, 1, context)?;
@@ -501,6 +1785,14 @@ fn r#ref(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Resu
         if let Some(set) = resolve_precise_name(repo, &candidates)? {
             return Ok(set);
         }
+        if let Some(set) = resolve_remote_name(repo, &name)? {
+            return Ok(set);
+        }
+        if refs_case_insensitive(repo) {
+            if let Some(set) = resolve_case_insensitive_name(repo, &name, &candidates)? {
+                return Ok(set);
+            }
+        }
     }
     // Try glob pattern lookup.
     if func_name != "lookup" && name.contains('*') {
@@ -514,7 +1806,7 @@ fn r#ref(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Resu
 fn tag(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
     let refs = repo.dag().git_references();
     // No arguments: all tags.
-    if args.len() == 0 {
+    if args.is_empty() {
         return repo.to_set(refs.iter().filter_map(|(name, vertex)| {
             if name.starts_with("refs/tags/") {
                 Some(vertex.clone())
@@ -528,7 +1820,7 @@ fn tag(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result
     // Try precise lookup.
     let ref_name = format!("refs/tags/{}", name);
     if let Some(vertex) = refs.get(&ref_name) {
-        return Ok(repo.to_set(std::iter::once(vertex.clone()))?);
+        return repo.to_set(std::iter::once(vertex.clone()));
     }
     // Try glob pattern lookup.
     if func_name != "lookup" && name.contains('*') {
@@ -555,7 +1847,7 @@ fn none(func_name: &str, _repo: &Repo, args: &[Expr], context: &Context) -> Resu
 }
 
 fn apply(func_name: &str, repo: &Repo, args: &[Expr], context: &Context) -> Result<Set> {
-    if args.len() < 1 {
+    if args.is_empty() {
         return Err(Error::MismatchedArguments(
             func_name.to_string(),
             1,
@@ -590,48 +1882,217 @@ fn normalize_hex(s: &str) -> Option<Vec<u8>> {
     Some(result)
 }
 
-fn filter_set(
-    repo: &Repo,
-    func: impl Fn(&git2::Commit) -> bool + Send + Sync + 'static,
-) -> Result<Set> {
-    #[derive(Clone)]
-    struct State {
-        git_repo: Arc<Mutex<git2::Repository>>,
-        func: Arc<dyn Fn(&git2::Commit) -> bool + Send + Sync + 'static>,
+/// Diff a commit against its first parent, or the empty tree for roots.
+pub(crate) fn diff_first_parent<'a>(
+    git_repo: &'a git2::Repository,
+    commit: &git2::Commit,
+) -> Result<git2::Diff<'a>> {
+    let tree = commit.tree()?;
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    Ok(git_repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?)
+}
+
+/// Test if a commit changes a path matching the glob pattern, compared to its
+/// first parent.
+fn diff_touches_glob(
+    git_repo: &git2::Repository,
+    commit: &git2::Commit,
+    matcher: &GlobMatcher,
+) -> bool {
+    let diff = match diff_first_parent(git_repo, commit) {
+        Ok(diff) => diff,
+        Err(_) => return false,
+    };
+    diff.deltas().any(|delta| {
+        [delta.old_file().path(), delta.new_file().path()]
+            .iter()
+            .flatten()
+            .any(|path| matcher.is_match(path))
+    })
+}
+
+/// Make an iterator `Sync` so it can be used by `Set::from_iter`.
+struct SyncIter<I>(Mutex<I>);
+
+impl<I: Iterator> Iterator for SyncIter<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.get_mut().unwrap().next()
+    }
+}
+
+/// Upper bound of commits tested in one chunk by a filter scan.
+const FILTER_MAX_CHUNK_SIZE: usize = 1024;
+
+/// Chunks smaller than this are tested in the current thread.
+const FILTER_PARALLEL_MIN_SIZE: usize = 64;
+
+/// Upper bound of threads used by a filter scan.
+const FILTER_MAX_THREADS: usize = 8;
+
+#[derive(Clone)]
+struct FilterState {
+    git_dir: PathBuf,
+    /// Idle git repo handles shared by the repo. Each thread takes one.
+    git_repos: Arc<Mutex<Vec<git2::Repository>>>,
+    func: Arc<FilterFn>,
+}
+
+impl FilterState {
+    fn contains(&self, name: &Vertex) -> dag::Result<bool> {
+        Ok(self.contains_all(std::slice::from_ref(name))?[0])
     }
 
-    impl State {
-        fn contains(&self, name: &Vertex) -> bool {
-            if let Ok(oid) = git2::Oid::from_bytes(name.as_ref()) {
-                if let Ok(commit) = self.git_repo.lock().unwrap().find_commit(oid) {
-                    return self.func.deref()(&commit);
+    /// Test `names` using a single git repo handle.
+    fn contains_all(&self, names: &[Vertex]) -> dag::Result<Vec<bool>> {
+        let git_repo = self.git_repos.lock().unwrap().pop();
+        let git_repo = match git_repo {
+            Some(git_repo) => git_repo,
+            None => git2::Repository::open(&self.git_dir)
+                .with_context(|| format!("opening {} for filtering", self.git_dir.display()))?,
+        };
+        let result = names
+            .iter()
+            .map(|name| match git2::Oid::from_bytes(name.as_ref()) {
+                Ok(oid) => match git_repo.find_commit(oid) {
+                    Ok(commit) => self.func.deref()(&git_repo, &commit),
+                    Err(_) => false,
+                },
+                Err(_) => false,
+            })
+            .collect();
+        self.git_repos.lock().unwrap().push(git_repo);
+        Ok(result)
+    }
+
+    /// Test `names`, split across threads if there are many.
+    fn contains_all_parallel(&self, names: &[Vertex]) -> dag::Result<Vec<bool>> {
+        let threads = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+            .min(FILTER_MAX_THREADS)
+            .min(names.len() / FILTER_PARALLEL_MIN_SIZE);
+        if threads <= 1 {
+            return self.contains_all(names);
+        }
+        let shard_size = names.len().div_ceil(threads);
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = names
+                .chunks(shard_size)
+                .map(|shard| scope.spawn(move || self.contains_all(shard)))
+                .collect();
+            let results = handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<dag::Result<Vec<_>>>()?;
+            Ok(results.concat())
+        })
+    }
+}
+
+/// Yields names from `scan` that pass the filter, in the same order.
+struct FilterIter<I> {
+    scan: I,
+    state: FilterState,
+    chunk_size: usize,
+    ready: VecDeque<dag::Result<Vertex>>,
+    /// Set after an error testing commits. Stop scanning.
+    failed: bool,
+}
+
+impl<I: Iterator<Item = dag::Result<Vertex>>> Iterator for FilterIter<I> {
+    type Item = dag::Result<Vertex>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.ready.is_empty() {
+            if self.failed {
+                return None;
+            }
+            let chunk: Vec<_> = self.scan.by_ref().take(self.chunk_size).collect();
+            if chunk.is_empty() {
+                return None;
+            }
+            self.chunk_size = (self.chunk_size * 2).min(FILTER_MAX_CHUNK_SIZE);
+            let names: Vec<Vertex> = chunk.iter().flatten().cloned().collect();
+            let mut matched = match self.state.contains_all_parallel(&names) {
+                Ok(matched) => matched.into_iter(),
+                Err(e) => {
+                    self.failed = true;
+                    return Some(Err(e));
+                }
+            };
+            for name in chunk {
+                // Errors are passed through.
+                if name.is_err() || matched.next() == Some(true) {
+                    self.ready.push_back(name);
                 }
             }
-            false
         }
+        self.ready.pop_front()
     }
+}
 
-    let state = State {
-        git_repo: Arc::new(Mutex::new(git2::Repository::open(repo.git_repo().path())?)),
+pub(crate) fn filter_set(
+    repo: &Repo,
+    func: impl Fn(&git2::Commit) -> bool + Send + Sync + 'static,
+) -> Result<Set> {
+    filter_set_in(repo, None, func)
+}
+
+/// Similar to `filter_set`, but only scan commits in `base`, or `all()` if
+/// `base` is `None`. The result is a subset of `base`. This is cheaper if
+/// `base` is known to be small.
+pub(crate) fn filter_set_in(
+    repo: &Repo,
+    base: Option<Set>,
+    func: impl Fn(&git2::Commit) -> bool + Send + Sync + 'static,
+) -> Result<Set> {
+    filter_set_with_repo(repo, base, move |_, commit| func(commit))
+}
+
+/// Similar to `filter_set_in`, but `func` can also access the git repo.
+///
+/// Commits are tested in chunks that grow exponentially, so consumers taking
+/// only a few commits can stop early. Large chunks are split across threads,
+/// each with its own git repo handle.
+fn filter_set_with_repo(
+    repo: &Repo,
+    base: Option<Set>,
+    func: impl Fn(&git2::Repository, &git2::Commit) -> bool + Send + Sync + 'static,
+) -> Result<Set> {
+    let state = FilterState {
+        git_dir: repo.git_repo().path().to_path_buf(),
+        git_repos: repo.git_repo_pool()?,
         func: Arc::new(func),
     };
 
+    // Scan lazily so consumers taking only a few commits can stop early.
     let evaluate = {
-        let all = all("all", repo, &[], &Default::default())?;
+        let scan = match base.clone() {
+            Some(base) => base,
+            None => all("all", repo, &[], &Default::default())?,
+        };
         let state = state.clone();
         move || -> dag::Result<Set> {
-            let iter = all
-                .iter()?
-                .filter(|name| match name {
-                    Ok(name) => state.contains(name),
-                    Err(_) => false,
-                })
-                .map(|name| name.unwrap());
-            Ok(Set::from_static_names(iter.into_iter()))
+            let iter = FilterIter {
+                scan: scan.iter()?,
+                state: state.clone(),
+                chunk_size: 1,
+                ready: VecDeque::new(),
+                failed: false,
+            };
+            Ok(Set::from_iter(SyncIter(Mutex::new(iter))))
         }
     };
 
-    Ok(Set::from_evaluate_contains(evaluate, move |_, name| {
-        Ok(state.contains(name))
-    }))
+    let set = Set::from_evaluate_contains(evaluate, move |_, name| match &base {
+        Some(base) if !base.contains(name)? => Ok(false),
+        _ => state.contains(name),
+    });
+    set.hints().add_flags(Flags::FILTER);
+    Ok(set)
 }
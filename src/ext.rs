@@ -3,7 +3,6 @@ use crate::Result;
 use gitdag::dag::Set;
 use gitdag::dag::Vertex;
 use gitdag::git2::Oid;
-use std::collections::HashMap;
 
 /// Extended methods on `Oid`.
 pub trait OidExt {
@@ -17,6 +16,12 @@ pub trait VertexExt {
     fn to_oid(&self) -> Result<Oid>;
 }
 
+/// Extended methods on `Vertex` iterator.
+pub trait VertexIterExt {
+    /// Convert to `Oid`s. Report the first vertex that is not a commit hash.
+    fn to_oid_vec(self) -> Result<Vec<Oid>>;
+}
+
 /// Extended methods on `Oid` iterator.
 pub trait OidIterExt {
     /// Convert to `Set`.
@@ -35,21 +40,17 @@ impl VertexExt for Vertex {
     }
 }
 
-impl<T: IntoIterator<Item = Oid>> OidIterExt for T {
-    fn to_set(self) -> Set {
-        Set::from_static_names(self.into_iter().map(|oid| oid.to_vertex()))
+impl<T: IntoIterator<Item = Vertex>> VertexIterExt for T {
+    fn to_oid_vec(self) -> Result<Vec<Oid>> {
+        self.into_iter()
+            .map(|v| v.to_oid().map_err(|_| Error::InvalidVertex(v)))
+            .collect()
     }
 }
 
-pub(crate) trait Merge {
-    fn merge(&mut self, other: Self);
-}
-
-impl<K: std::cmp::Eq + std::hash::Hash, V> Merge for HashMap<K, V> {
-    fn merge(&mut self, other: Self) {
-        for (k, v) in other {
-            self.insert(k, v);
-        }
+impl<T: IntoIterator<Item = Oid>> OidIterExt for T {
+    fn to_set(self) -> Set {
+        Set::from_static_names(self.into_iter().map(|oid| oid.to_vertex()))
     }
 }
 
@@ -57,6 +58,9 @@ impl<K: std::cmp::Eq + std::hash::Hash, V> Merge for HashMap<K, V> {
 pub trait SetExt {
     /// Convert to a convenient iterator of `Oid`s.
     fn to_oids(&self) -> Result<Box<dyn Iterator<Item = Result<Oid>>>>;
+
+    /// Convert to a `Vec` of `Oid`s. Stop at the first error.
+    fn to_oid_vec(&self) -> Result<Vec<Oid>>;
 }
 
 impl SetExt for Set {
@@ -70,4 +74,8 @@ impl SetExt for Set {
         });
         Ok(Box::new(iter))
     }
+
+    fn to_oid_vec(&self) -> Result<Vec<Oid>> {
+        self.to_oids()?.collect()
+    }
 }
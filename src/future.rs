@@ -0,0 +1,91 @@
+use crate::Result;
+use gitdag::dag::Set;
+use once_cell::sync::OnceCell;
+use std::future::Future;
+use std::panic;
+use std::pin::Pin;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Waker;
+use std::thread;
+
+/// Result of [`Repo::revs_async`](struct.Repo.html#method.revs_async).
+/// Resolves to the set once the background evaluation completes.
+///
+/// It does not depend on a specific async runtime.
+pub struct RevsFuture {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    result: Option<thread::Result<Result<Set>>>,
+    waker: Option<Waker>,
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Maximum number of background threads running evaluations.
+const MAX_THREADS: usize = 4;
+
+/// Send `job` to a pool of background threads. The pool is started on first
+/// use. Jobs are queued if all threads are busy.
+fn run_in_pool(job: Job) {
+    static POOL: OnceCell<Mutex<mpsc::Sender<Job>>> = OnceCell::new();
+    let sender = POOL.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let count = thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .min(MAX_THREADS);
+        for _ in 0..count {
+            let receiver = receiver.clone();
+            thread::spawn(move || loop {
+                let job = match receiver.lock().unwrap().recv() {
+                    Ok(job) => job,
+                    Err(_) => break,
+                };
+                job();
+            });
+        }
+        Mutex::new(sender)
+    });
+    sender.lock().unwrap().send(job).unwrap();
+}
+
+impl RevsFuture {
+    /// Run `func` in a background thread. The future resolves to its result.
+    pub(crate) fn spawn(func: impl FnOnce() -> Result<Set> + Send + 'static) -> Self {
+        let state: Arc<Mutex<State>> = Default::default();
+        let thread_state = state.clone();
+        run_in_pool(Box::new(move || {
+            let result = panic::catch_unwind(panic::AssertUnwindSafe(func));
+            let mut state = thread_state.lock().unwrap();
+            state.result = Some(result);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        }));
+        Self { state }
+    }
+}
+
+impl Future for RevsFuture {
+    type Output = Result<Set>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+        match state.result.take() {
+            Some(Ok(result)) => Poll::Ready(result),
+            // Propagate the panic, like `JoinHandle::join().unwrap()`.
+            Some(Err(payload)) => panic::resume_unwind(payload),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
@@ -6,7 +6,29 @@
 //! Specifying a commit:
 //! - Reference names like `master`, `release-foo`, or `origin/master`.
 //! - Hex commit hashes or hash prefixes.
-//! - A dot `.`, or the at sign `@` refers to `HEAD`.
+//! - A dot `.`, or the at sign `@` refers to `HEAD`. It is an error if `HEAD`
+//!   points to a branch that does not exist, which can happen in bare repos.
+//! - `@main` refers to the main branch. Same as `main()`.
+//! - `@{-n}` refers to the `n`-th branch or commit checked out before the
+//!   current one, like `git checkout -`. It is resolved using `checkout:`
+//!   entries in the `HEAD` reflog.
+//! - `ORIG_HEAD`, `MERGE_HEAD`, `FETCH_HEAD`, `CHERRY_PICK_HEAD` refer to
+//!   commits recorded by in-progress or previous git operations.
+//! - `branch@{upstream}`, or `branch@{u}` refers to the upstream of a local
+//!   branch. `branch@{push}` refers to the remote branch `git push` would
+//!   update. Without `branch`, like `@{u}`, the current branch is used.
+//! - Quoted names with `~` or `^`, like `"HEAD~2"` or `"master^2"`, are
+//!   resolved by git's revision syntax, if they are not reference names.
+//!
+//! A name like `foo` that is not a local reference can refer to a remote
+//! branch like `origin/foo`. If multiple remotes have `foo`, the remote
+//! specified by the `revs.default-remote` config, or the remote of the main
+//! branch, is preferred. Otherwise the name is ambiguous.
+//!
+//! If the `revs.refs-case-insensitive` config is `true`, names like `MAIN`
+//! that do not match a reference exactly are resolved ignoring case. Names
+//! matching multiple references that only differ by case, like `Main` and
+//! `main`, are ambiguous. This is off by default.
 //!
 //! Operators:
 //! - `x + y`, `x | y`, `x or y`, `union(x, y)`: Union of `x` and `y` (1).
@@ -16,32 +38,177 @@
 //! - `::x`, `ancestors(x)`: Ancestors of `x`, including `x`.
 //! - `x::`, `descendants(x)`: Descendants of `x`, including `x`.
 //! - `x^`, `parents(x)`: Parents of `x` (2).
+//! - `x^n`, `nthparent(x, n)`: The `n`-th parent of `x`, like git. `x^1` is
+//!   the first parent. `x^0` is `x` itself. Commits with less than `n`
+//!   parents are skipped.
+//! - `x~n`, `nthancestor(x, n)`: Follow first parents of `x` `n` times, like
+//!   git. `x~` is `x~1`. `x~0` is `x` itself. Commits with less than `n`
+//!   first-parent ancestors are skipped.
 //! - `x % y`, `only(x, y)`: Reachable from `x`, not `y`, or `::x - ::y`.
+//! - `y..x`: Same as `x % y`, like git.
+//! - `x...y`: Reachable from either `x` or `y`, but not both, like git.
+//!   Same as `x % y + y % x`.
+//! - `x ^y`: Exclude ancestors of `y`, like `git rev-list x ^y`. Multiple
+//!   commits can be listed, like `x ^y z ^w`, which is `::(x + z) - ::(y + w)`.
+//!   Included expressions need to be separated by excluded names. `^y` must
+//!   be a name that is not all digits, since `x^2` is the second parent of
+//!   `x`. `x ^2` is an error. Use `x % 2` to exclude ancestors of `2`.
+//!   `^y` needs whitespace before it. `x^y` is an error.
 //! - `x:y`, `range(x, y)`: A DAG range, descendants of `x` and ancestors of
 //!   `y`, or `x:: & ::y` (3).
+//! - `paths(x, y)`, `allpaths(x, y)`: Commits on any path from `x` to `y`.
+//!   Same as `range(x, y)`.
+//!
+//! Regex literals like `/^Fix(es)? #\d+/` can be used as arguments of
+//! `grep`, `author`, and `committer`, without escaping backslashes in
+//! quoted strings. Use `\/` for `/`. For example, `author(/^Alice </)`.
 //!
 //! Functions:
 //! - `children(x)`: Commits with at least one parent in the `x` set.
+//! - `children(x, bound)`: Same as `children(x) & bound`. Faster if `bound`
+//!   is small.
 //! - `heads(x)`: Heads of a set, `x - parents(x)`.
 //! - `roots(x)`: Roots of a set, `x - children(x)`.
+//! - `linear(x)`: The linear segments containing `x`. A segment stops at
+//!   merges and branch points.
+//! - `branchpoint(x)`: Commits in `x` with more than one visible child.
+//! - `mergepoint(x)`: Commits in `x` with more than one parent.
+//! - `mergeof(x, y)`: Merges in `::y` that bring `x` in. Their first
+//!   parents are not descendants of `x`, and one of their other parents is.
+//! - `simplifymerges(x)`: `x` without redundant merges, similar to
+//!   `git log --simplify-merges`. A merge is redundant if its ancestors in
+//!   `x` have a single head, for example, when one parent is an ancestor of
+//!   another parent, or when other merged branches are excluded from `x`.
+//!   Unlike git, file changes are not considered, and merges without
+//!   ancestors in `x` are kept.
+//! - `shortestpath(x, y)`: Commits on one of the shortest paths from `x` to
+//!   `y`, or empty if `y` is not reachable from `x`.
 //! - `gca(x, y, ...)`, `ancestor(x, y, ...)`: Heads of common ancestors (4).
 //! - `first(x, ...)`: First item in `x`, or `first(...)` if `x` is empty.
 //! - `last(x)`: Last item in `x`, or empty.
-//! - `head()`: Visible heads (references).
+//! - `limit(x, n)`: First `n` items in `x`, or `x` if it has fewer items.
+//! - `reverse(x)`: `x` in the reversed order. For example,
+//!   `limit(reverse(x), 5)` selects the 5 oldest commits in `x`.
+//! - `min(x)`, `max(x)`: The commit in `x` with the earliest, or the latest
+//!   committer date, or empty if `x` is empty. Ties are broken by commit
+//!   hashes.
+//! - `sort(x, keys)`: Sort `x` by comma-separated `keys`. Later keys break
+//!   ties of earlier keys. A key can be `topo` (the default order),
+//!   `date` (committer date, older first), `authordate` (author date, older
+//!   first), or `author` (author name). Prefix a key with `-` to reverse it.
+//!   For example, `sort(x, "-date,author")`. The sorted set iterates in the
+//!   requested order, which is useful for building changelogs.
+//! - `head()`: Visible heads (references). References matching globs in the
+//!   `revs.hiddenrefs` config, like `refs/wip/**`, are excluded. They can
+//!   still be resolved explicitly by `ref(name)`.
 //! - `all()`: Visible commits, aka. `::head()`.
+//! - `secret()`: Draft commits that are not reachable from any references,
+//!   including hidden ones (see `head()`). They are only reachable from
+//!   reflogs, or nothing. Similar to `unreachable()`, commits in reflogs are
+//!   only included if indexed.
+//! - `unreachable()`: Commits not reachable from any references, like the
+//!   ones before `git reset`. Only commits in the commit graph index are
+//!   included. Commits are indexed when they were reachable from references
+//!   at the time the repo was opened. Use
+//!   [`RepoBuilder::index_reflogs`](struct.RepoBuilder.html#method.index_reflogs)
+//!   to also index commits in reflogs, at the cost of reading reflogs.
+//! - `recent(n)`: Commits at most `n - 1` parent steps away from `head()`,
+//!   like `head() + head()^ + head()^^ ...`. This approximates recent
+//!   history by graph depth, not by date. It is cheaper than filtering
+//!   `all()` for large repos.
 //! - `publichead()`: Heads referred by remotes, `ref("remotes/**")`.
 //! - `drafthead()`: Heads not referred by remotes, `head() - publichead()`.
 //! - `public()`: Commits reachable from `publichead()`, `::publichead()`.
 //! - `draft()`: Commits only reachable from draft heads, `all() - public()`.
-//! - `author(name)`: Filter by author name or email.
-//! - `committer(name)`: Filter by committer name or email.
+//! - `author(name)`: Filter by author name or email. With the `re:` prefix,
+//!   like `author("re:^Alice <")`, match `name <email>` using a regex.
+//! - `committer(name)`: Filter by committer name or email. Support the `re:`
+//!   prefix like `author`.
 //! - `date(date)`: Filter by author date.
 //! - `committerdate(date)`: Filter by committer date.
-//! - `desc(text)`: Filter by commit message.
+//!
+//!   Besides dates like `2020-01-01`, or ranges like `since 2 days ago`, date
+//!   functions accept named periods: `today`, `yesterday`, `thisweek`,
+//!   `thismonth`, and `thisyear`. They cover whole days in the local
+//!   timezone, up to the end of the period. Weeks start on Monday. Relative
+//!   dates use the system time, unless
+//!   [`Repo::set_now`](struct.Repo.html#method.set_now) sets a fixed time.
+//! - `workdate(date)`: Filter by author date, but use the latest author date
+//!   of the merged (non-first) parents for merges. This approximates when
+//!   the merged work happened, since a merge can be made long after.
+//! - `desc(text)`: Commits with messages, including the bodies, containing
+//!   `text`, ignoring case. This used to match summaries case sensitively.
+//!   Use `descexact(text)` for the old behavior.
+//! - `descexact(text)`: Commits with summaries (first lines of the commit
+//!   messages) containing `text`, case sensitively.
+//! - `grep(regex)`: Commits with messages, including the bodies, matching
+//!   the regular expression. For example, `grep("Fixes #\\d+")`.
+//! - `summary(text)`: Commits with the summary (first line of the commit
+//!   message) equal to `text`.
 //! - `modifies(path)`: Filter by modified path.
+//! - `follow(path)`: Commits changing `path`, or the files it was renamed
+//!   from, like `git log --follow`. Commits are compared with their first
+//!   parents. This is expensive.
+//! - `emptycommit()`: Commits without changes compared to their first parents,
+//!   or root commits with an empty tree.
+//! - `merge()`: Merge commits, with two or more parents. Unlike
+//!   `mergepoint(x)`, this filters commits lazily.
+//! - `nonmerge()`: Commits with at most one parent.
+//! - `bigdiff(n)`: Commits changing more than `n` lines compared to their
+//!   first parents. This is expensive. Consider using it with a smaller set
+//!   like `bigdiff(100) & draft()`.
+//! - `touched(n)`: Commits changing `n` files compared to their first parents.
+//!   `n` can be prefixed by `>`, `>=`, `<`, `<=`, or `=`. For example,
+//!   `touched(">10")` selects commits changing more than 10 files.
+//! - `file(pattern)`: Commits changing paths matching the glob `pattern`
+//!   compared to their first parents, like `file("src/**")`. Root commits
+//!   are compared with the empty tree. This is expensive. Consider using it
+//!   with a smaller set like `file("src/**") & ::master`.
+//! - `changedsince(pattern, date)`: Commits changing paths matching the glob
+//!   `pattern` compared to their first parents, with author date matching
+//!   `date`.
+//! - `duplicates(x, y)`: Commits in `x` with the same patch-id as a commit in
+//!   `y`. For example, `duplicates(draft(), public())` finds draft commits
+//!   already applied upstream. This is expensive.
+//! - `reverts(x)`: Commits reverting commits in `x`, detected by the
+//!   `This reverts commit <hash>` lines in commit messages. Only full
+//!   40-digit hashes are recognized.
+//! - `revertcommits()`: Commits reverting other commits.
+//! - `cherrypickedfrom(x)`: Commits cherry-picked from commits in `x`,
+//!   detected by the `(cherry picked from commit <hash>)` lines written by
+//!   `git cherry-pick -x`. Only full 40-digit hashes are recognized.
+//! - `cherrypicks()`: Commits cherry-picked with `git cherry-pick -x`.
+//! - `fixes(issue)`: Commits referring to `issue`, like `#123`, in their
+//!   messages, either inline or in trailers like `Fixes: #123`. The issue
+//!   syntax is a regular expression configured by `revs.issue-pattern`.
+//!   For example, `[A-Z]+-[0-9]+` matches `JIRA-123`.
 //! - `predecessors(x)`: Previous versions of `x`, including `x`.
 //! - `successors(x)`: Newer versions of `x`, including `x`.
+//! - `rootpredecessor(x)`: The earliest versions of `x`. A commit folded from
+//!   multiple commits has multiple earliest versions. Commits that were
+//!   never rewritten are their own earliest versions.
 //! - `obsolete()`: Commits with at least one newer versions.
+//!
+//!   Versions of commits are read from sources configured by
+//!   `revs.mutation-source`:
+//!   - `reflog` (default): Inferred from "amend", "rebase" (including
+//!     squashes and fixups) and "reset" entries in reflogs of local
+//!     branches, and `HEAD` reflogs of worktrees.
+//!   - `notes`: Read from notes in `refs/notes/mutation`. A note attached to
+//!     a commit lists full hashes of its previous versions, one per line.
+//!     Unlike reflogs, notes can be pushed and fetched, which makes the
+//!     result reproducible in fresh clones, like CI checkouts.
+//!   - `both`: Combine the above.
+//! - `orphan()`: Commits that are not obsolete, but have obsolete ancestors.
+//!   They usually need to be rebased onto the newer versions.
+//! - `divergent()`: Commits that are not obsolete, and share a previous
+//!   version with other commits that are not obsolete. For example, a commit
+//!   amended separately in two branches.
+//! - `visibledescendants(x)`: Descendants that are not obsolete,
+//!   `descendants(x) - obsolete()`.
+//! - `main()`: The main branch. Configured by `revs.main-branch`, or guessed
+//!   from `origin/master`, `origin/main`, `upstream/master`, `upstream/main`.
+//!   Useful for aliases shared by repos with different main branch names.
 //! - `id(hexhash)`: Resolve a commit explicitly by a hex hash string.
 //! - `ref()`: All references.
 //! - `ref(name)`: Resolve commits by a reference name or glob.
@@ -50,16 +217,18 @@
 //! - `none()`: Empty set.
 //! - `present(set)`: Empty set on "unresolved name" error. Otherwise just `set`.
 //! - `apply(expr, $1, $2, ...)`: Replace `$1`, `$2` in `expr` with evaluated
-//!    sets. Then evaluate `expr`. Useful to avoid evaluate same sets multiple
-//!    times.
+//!   sets. Then evaluate `expr`. Useful to avoid evaluate same sets multiple
+//!   times.
 //!
 //! Differences from Mercurial:
 //! 1. `x + y` does not make sure `y` comes after `x`. For example,
 //!    `first(x + y)` might be `first(x)` or `first(y)`. In Mercurial,
 //!    `first(x + y)` would be `first(x)`.
-//! 2. `x^` selects all parents, not just first parents.
+//! 2. `x^` selects all parents, not just first parents. Use `x^1` for first
+//!    parents.
 //! 3. `x:y` selects DAG range `x` to `y`, not revision number range.
-//!    `x::y` is invalid syntax, for easier parsing.
+//!    `x::y` is invalid syntax, for easier parsing. `x..y` follows git,
+//!    not Mercurial.
 //! 4. `ancestor(x, y)` can return multiple commits for criss-cross merges.
 //!
 //! ## Quick Start
@@ -119,6 +288,9 @@
 //! for oid in set.to_oids()? {
 //!     dbg!(oid?);
 //! }
+//! // Or, collect them into a `Vec`.
+//! let oids = set.to_oid_vec()?;
+//! # assert!(oids.is_empty());
 //! # }
 //! # Ok(())
 //! # }
@@ -139,6 +311,19 @@
 //! `gca(x, y)`, `x & ::y`, `x % y` usually complete under 1 millisecond
 //! regardless of the distance between `x` and `y`.
 //!
+//! Filters like `author(name)` or `desc(text)` are evaluated lazily. Testing
+//! membership, or taking the first few commits, only checks the commits
+//! needed. Intersecting a filter with a non-filter set, like
+//! `modifies(path) & x:y` or `x:y & modifies(path)`, only checks commits in
+//! the non-filter set. Graph functions like `heads(x)` or `roots(x)` need the
+//! full content of `x`, and force the filter to scan all commits. Intersect
+//! with a smaller set first, like `heads(author(alice) & draft())`, to limit
+//! the scan.
+//!
+//! Async services can use [`revs_async`](struct.Repo.html#method.revs_async)
+//! to evaluate slow queries in a background thread without blocking the
+//! executor.
+//!
 //! The index is not optimized for many visible heads. Having too many
 //! references might have a visible performance penalty on
 //! [`Repo`](struct.Repo.html) construction.
@@ -153,11 +338,15 @@
 pub mod ext;
 
 mod ast;
+mod cache;
 mod error;
 mod eval;
+mod future;
 mod mutation;
 mod parser;
+mod plan;
 mod repo;
+mod version;
 
 #[cfg(any(test, feature = "testutil"))]
 mod testrepo;
@@ -176,6 +365,14 @@ pub use gitdag::git2;
 pub type Result<T> = std::result::Result<T, Error>;
 
 pub use ast::Expr;
+pub use ast::Revset;
 pub use eval::Context as EvalContext;
 pub use ext::SetExt;
+pub use future::RevsFuture;
+pub use plan::PlanKind;
+pub use plan::PlanNode;
+pub use repo::BranchStatus;
+pub use repo::CommitFlags;
+pub use repo::OpenOptions;
 pub use repo::Repo;
+pub use repo::RepoBuilder;
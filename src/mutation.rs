@@ -1,6 +1,6 @@
-use crate::ext::Merge;
 use crate::ext::OidExt;
 use crate::repo::Repo;
+use crate::Error;
 use crate::Result;
 use dag::namedag::MemNameDag;
 use dag::ops::DagAddHeads;
@@ -10,27 +10,65 @@ use gitdag::git2;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
-pub(crate) fn infer_mutation_from_reflog(repo: &Repo) -> Result<MemNameDag> {
-    let refs = repo.dag().git_references();
-    let mut replaces: HashMap<Vertex, Vertex> = Default::default();
-    for name in refs.keys() {
-        if !name.starts_with("refs/remotes/") && name.starts_with("refs/heads/") {
-            replaces.merge(analyse_reflog_name(repo, name).unwrap_or_default());
+/// Notes reference with predecessors of rewritten commits. A note attached
+/// to a commit lists full hashes of its predecessors, one per line.
+const MUTATION_NOTES_REF: &str = "refs/notes/mutation";
+
+/// Where to read rewrite history from. Configured by `revs.mutation-source`.
+#[derive(Clone, Copy, PartialEq)]
+enum MutationSource {
+    Reflog,
+    Notes,
+    Both,
+}
+
+fn mutation_source(repo: &Repo) -> Result<MutationSource> {
+    let config = repo.git_repo().config()?;
+    let value = match config.get_string("revs.mutation-source") {
+        Ok(value) => value,
+        Err(_) => return Ok(MutationSource::Reflog),
+    };
+    match value.as_str() {
+        "reflog" => Ok(MutationSource::Reflog),
+        "notes" => Ok(MutationSource::Notes),
+        "both" => Ok(MutationSource::Both),
+        _ => Err(Error::ParseError(format!(
+            "invalid revs.mutation-source: {:?} (expect reflog, notes, or both)",
+            value
+        ))),
+    }
+}
+
+pub(crate) fn infer_mutation(repo: &Repo) -> Result<MemNameDag> {
+    let source = mutation_source(repo)?;
+    // Successor -> predecessors.
+    let mut replaces: HashMap<Vertex, Vec<Vertex>> = Default::default();
+    if source != MutationSource::Notes {
+        let mut pairs: Vec<_> = infer_mutation_from_reflog(repo).into_iter().collect();
+        pairs.sort_unstable();
+        for (new, old) in pairs {
+            replaces.entry(new).or_default().push(old);
+        }
+    }
+    if source != MutationSource::Reflog {
+        for (new, olds) in read_mutation_notes(repo)? {
+            let entry = replaces.entry(new).or_default();
+            for old in olds {
+                if !entry.contains(&old) {
+                    entry.push(old);
+                }
+            }
         }
     }
 
     let parent_func = |v: Vertex| -> dag::Result<Vec<Vertex>> {
-        match replaces.get(&v) {
-            None => Ok(Vec::new()),
-            Some(old) => Ok(vec![old.clone()]),
-        }
+        Ok(replaces.get(&v).cloned().unwrap_or_default())
     };
     let parent_func = dag::utils::break_parent_func_cycle(parent_func);
+    let predecessors: HashSet<&Vertex> = replaces.values().flatten().collect();
     let mut heads: Vec<Vertex> = replaces
         .keys()
-        .collect::<HashSet<_>>()
-        .difference(&replaces.values().collect::<HashSet<_>>())
-        .cloned()
+        .filter(|v| !predecessors.contains(v))
         .cloned()
         .collect();
     heads.sort_unstable();
@@ -40,20 +78,110 @@ pub(crate) fn infer_mutation_from_reflog(repo: &Repo) -> Result<MemNameDag> {
     Ok(dag)
 }
 
-fn analyse_reflog_name(repo: &Repo, name: &str) -> Result<HashMap<Vertex, Vertex>> {
+/// (Successor, predecessor) pairs, inferred from "amend", "rebase" and
+/// "reset" reflog entries of local branches, and `HEAD` of worktrees.
+fn infer_mutation_from_reflog(repo: &Repo) -> HashSet<(Vertex, Vertex)> {
+    let git_repo = repo.git_repo();
+    let refs = repo.dag().git_references();
+    let mut replaces: HashSet<(Vertex, Vertex)> = Default::default();
+    for name in refs.keys() {
+        if !name.starts_with("refs/remotes/") && name.starts_with("refs/heads/") {
+            replaces.extend(analyse_reflog_name(git_repo, name).unwrap_or_default());
+        }
+    }
+    // Squashes and fixups during a rebase are only recorded in `HEAD`
+    // reflogs. Branch reflogs are shared by worktrees. But each linked
+    // worktree has its own `HEAD` reflog, which is also the only record of
+    // rewrites with a detached `HEAD` there.
+    replaces.extend(analyse_reflog_name(git_repo, "HEAD").unwrap_or_default());
+    for worktree_repo in linked_worktree_repos(git_repo) {
+        replaces.extend(analyse_reflog_name(&worktree_repo, "HEAD").unwrap_or_default());
+    }
+    replaces
+}
+
+/// Open linked worktrees of `git_repo`. Worktrees that cannot be opened,
+/// for example, with their directories deleted, are skipped.
+fn linked_worktree_repos(git_repo: &git2::Repository) -> Vec<git2::Repository> {
+    let names = match git_repo.worktrees() {
+        Ok(names) => names,
+        Err(_) => return Vec::new(),
+    };
+    names
+        .iter()
+        .flatten()
+        .filter_map(|name| git_repo.find_worktree(name).ok())
+        .filter_map(|worktree| git2::Repository::open_from_worktree(&worktree).ok())
+        .collect()
+}
+
+/// Successor -> predecessors, read from notes in `MUTATION_NOTES_REF`.
+/// Lines that are not full commit hashes are ignored.
+fn read_mutation_notes(repo: &Repo) -> Result<HashMap<Vertex, Vec<Vertex>>> {
+    let git_repo = repo.git_repo();
+    let mut result: HashMap<Vertex, Vec<Vertex>> = Default::default();
+    let notes = match git_repo.notes(Some(MUTATION_NOTES_REF)) {
+        Ok(notes) => notes,
+        // No notes.
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(result),
+        Err(e) => return Err(e.into()),
+    };
+    for entry in notes {
+        let (_note_id, commit_id) = entry?;
+        let note = git_repo.find_note(Some(MUTATION_NOTES_REF), commit_id)?;
+        let olds: Vec<Vertex> = note
+            .message()
+            .unwrap_or("")
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| line.len() == 40)
+            .filter_map(|line| git2::Oid::from_str(line).ok())
+            .filter(|&old| old != commit_id)
+            .map(|old| old.to_vertex())
+            .collect();
+        if !olds.is_empty() {
+            result.insert(commit_id.to_vertex(), olds);
+        }
+    }
+    Ok(result)
+}
+
+/// Reflog message prefixes of rewrites that replace the old `HEAD` stack
+/// with the new one. `reset` is included so resetting to a version
+/// rewritten elsewhere, like a force-pushed branch, is also detected.
+const REWRITE_PREFIXES: &[&str] = &[
+    "commit (amend):",
+    "rebase -i (finish):",
+    "rebase (finish):",
+    "reset:",
+];
+
+/// Reflog message prefixes of rewrites that fold the old `HEAD` into the
+/// new one.
+const FOLD_PREFIXES: &[&str] = &[
+    "rebase -i (squash):",
+    "rebase -i (fixup):",
+    "rebase (squash):",
+    "rebase (fixup):",
+];
+
+fn analyse_reflog_name(
+    git_repo: &git2::Repository,
+    name: &str,
+) -> Result<HashSet<(Vertex, Vertex)>> {
     // Check reflog for the given reference name.
-    let reflog = repo.git_repo().reflog(name)?;
-    let mut replaces: HashMap<Vertex, Vertex> = Default::default();
+    let reflog = git_repo.reflog(name)?;
+    let mut replaces: HashSet<(Vertex, Vertex)> = Default::default();
     for entry in reflog.iter() {
         let message: &str = match entry.message() {
             Some(m) => m,
             None => continue,
         };
-        if message.starts_with("commit (amend):") || message.starts_with("rebase -i (finish):") {
-            replaces.merge(
-                analyse_head_rewrite(repo.git_repo(), entry.id_old(), entry.id_new())
-                    .unwrap_or_default(),
-            );
+        let (old, new) = (entry.id_old(), entry.id_new());
+        if REWRITE_PREFIXES.iter().any(|p| message.starts_with(p)) {
+            replaces.extend(analyse_head_rewrite(git_repo, old, new).unwrap_or_default());
+        } else if FOLD_PREFIXES.iter().any(|p| message.starts_with(p)) && old != new {
+            replaces.insert((new.to_vertex(), old.to_vertex()));
         }
     }
     Ok(replaces)
@@ -87,7 +215,7 @@ fn analyse_head_rewrite(
         }
         if seen.insert(new) {
             new_stack.push(new);
-            if let Some(next_new) = git_repo.find_commit(old)?.parent_ids().next() {
+            if let Some(next_new) = git_repo.find_commit(new)?.parent_ids().next() {
                 new = next_new;
             }
         }
@@ -107,7 +235,7 @@ fn analyse_head_rewrite(
     let mut tree_map = HashMap::<git2::Oid, Vec<(Side, git2::Oid)>>::new();
     let mut msg_map = HashMap::<(i64, Vec<u8>), Vec<(Side, git2::Oid)>>::new();
 
-    for (side, stack) in vec![(Side::Old, old_stack), (Side::New, new_stack)] {
+    for (side, stack) in [(Side::Old, old_stack), (Side::New, new_stack)] {
         for oid in stack.into_iter().take(MAX_DEPTH) {
             let commit = match git_repo.find_commit(oid) {
                 Err(_) => continue,
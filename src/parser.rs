@@ -1,11 +1,105 @@
 use crate::ast::Expr;
-use lalrpop_util::lexer::Token;
+use crate::Error;
+use crate::Result;
 use lalrpop_util::ParseError;
 
 #[rustfmt::skip]
+#[allow(clippy::all, mismatched_lifetime_syntaxes)]
 mod grammar;
 
+/// Errors detected by grammar actions. Spans are byte offsets of the
+/// exclusion, like "^2".
+pub(crate) enum GrammarError {
+    /// Exclude a name that is all digits, like "x ^2".
+    DigitExclusion(usize, usize),
+    /// Exclude a name without whitespace before '^', like "x^y".
+    AdjacentExclusion(usize, usize),
+}
+
 /// Parse a string into an AST.
-pub fn parse(s: &str) -> Result<Expr, ParseError<usize, Token, &str>> {
-    grammar::ExprParser::new().parse(s)
+///
+/// On syntax errors, the message includes the offending line of `s`, with
+/// a caret pointing at the error position.
+pub fn parse(s: &str) -> Result<Expr> {
+    grammar::ExprParser::new().parse(s, s).map_err(|e| {
+        // "^2" cannot exclude the commit named "2", since it is the 2nd
+        // parent in "x^2".
+        let digit_exclusion = match &e {
+            ParseError::User {
+                error: GrammarError::DigitExclusion(start, end),
+            } => Some((*start, *end)),
+            ParseError::UnrecognizedToken {
+                token: (start, token, end),
+                ..
+            } if is_nth_parent_token(token.1) => Some((*start, *end)),
+            _ => None,
+        };
+        if let Some((start, end)) = digit_exclusion {
+            let n = &s[start + 1..end];
+            return Error::ParseError(format!(
+                "cannot exclude {:?} using '^' since it is all digits. Use 'x^{}' for the n-th parent, or 'x % {}' to exclude ancestors of {:?}\n{}",
+                n, n, n, n, caret(s, start, end)
+            ));
+        }
+        if let ParseError::User {
+            error: GrammarError::AdjacentExclusion(start, end),
+        } = e
+        {
+            let name = &s[start + 1..end];
+            return Error::ParseError(format!(
+                "cannot exclude {:?} using '^' without whitespace before it. Use 'x ^{}' to exclude ancestors of {:?}\n{}",
+                name, name, name, caret(s, start, end)
+            ));
+        }
+        let span = match &e {
+            ParseError::InvalidToken { location } => Some((*location, *location + 1)),
+            ParseError::UnrecognizedEOF { location, .. } => Some((*location, *location + 1)),
+            ParseError::UnrecognizedToken {
+                token: (start, _, end),
+                ..
+            }
+            | ParseError::ExtraToken {
+                token: (start, _, end),
+            } => Some((*start, *end)),
+            ParseError::User { .. } => None,
+        };
+        // User errors are handled above.
+        let message = e.map_error(|_| "").to_string();
+        let message = match span {
+            // Show the caret right after the first line, before the
+            // "Expected one of" line.
+            Some((start, end)) => match message.split_once('\n') {
+                Some((first, rest)) => format!("{}\n{}\n{}", first, caret(s, start, end), rest),
+                None => format!("{}\n{}", message, caret(s, start, end)),
+            },
+            None => message,
+        };
+        Error::ParseError(message)
+    })
+}
+
+/// Test if `token` is "^<digits>", like "^2".
+fn is_nth_parent_token(token: &str) -> bool {
+    match token.strip_prefix('^') {
+        Some(digits) => !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()),
+        None => false,
+    }
+}
+
+/// Show the line of `s` containing the byte offset `start`, with carets
+/// under `start..end`.
+fn caret(s: &str, start: usize, end: usize) -> String {
+    let start = start.min(s.len());
+    let line_start = s[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line_end = s[start..].find('\n').map(|i| i + start).unwrap_or(s.len());
+    let line = &s[line_start..line_end];
+    let indent = s[line_start..start].chars().count();
+    let width = s[start..end.clamp(start, line_end)].chars().count().max(1);
+    format!(
+        "  {}\n  {}{} (at byte {})",
+        line,
+        " ".repeat(indent),
+        "^".repeat(width),
+        start
+    )
 }
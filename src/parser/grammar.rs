@@ -1,141 +1,187 @@
-// auto-generated: "lalrpop 0.19.0"
-// sha256: 3540bb378dbf388a39df6d0e134ad2ce99d7372b8f086ef5da1d2a4be4f38c9
+// auto-generated: "lalrpop 0.19.12"
+// sha3: 83a41656a99723f4c1aeb9b9b69d262b47bbae1d9a02752cf3e4f784e320b800
 use crate::ast::Expr;
+use crate::ast::rev_list;
+use crate::parser::GrammarError;
+use lalrpop_util::ParseError;
 #[allow(unused_extern_crates)]
 extern crate lalrpop_util as __lalrpop_util;
 #[allow(unused_imports)]
 use self::__lalrpop_util::state_machine as __state_machine;
+extern crate core;
+extern crate alloc;
 
 #[cfg_attr(rustfmt, rustfmt_skip)]
 mod __parse__Expr {
-    #![allow(non_snake_case, non_camel_case_types, unused_mut, unused_variables, unused_imports, unused_parens)]
+    #![allow(non_snake_case, non_camel_case_types, unused_mut, unused_variables, unused_imports, unused_parens, clippy::all)]
 
     use crate::ast::Expr;
+    use crate::ast::rev_list;
+    use crate::parser::GrammarError;
+    use lalrpop_util::ParseError;
     #[allow(unused_extern_crates)]
     extern crate lalrpop_util as __lalrpop_util;
     #[allow(unused_imports)]
     use self::__lalrpop_util::state_machine as __state_machine;
+    extern crate core;
+    extern crate alloc;
     use self::__lalrpop_util::lexer::Token;
     #[allow(dead_code)]
-    pub enum __Symbol<'input>
+    pub(crate) enum __Symbol<'input>
      {
         Variant0(&'input str),
-        Variant1(&'static str),
-        Variant2(::std::vec::Vec<&'static str>),
-        Variant3((Expr, &'input str)),
-        Variant4(::std::vec::Vec<(Expr, &'input str)>),
-        Variant5(Expr),
-        Variant6(::std::option::Option<Expr>),
-        Variant7(String),
+        Variant1((&'static str, Option<String>)),
+        Variant2(alloc::vec::Vec<(&'static str, Option<String>)>),
+        Variant3(&'static str),
+        Variant4(alloc::vec::Vec<&'static str>),
+        Variant5((Expr, &'input str)),
+        Variant6(alloc::vec::Vec<(Expr, &'input str)>),
+        Variant7(usize),
+        Variant8(Expr),
+        Variant9(core::option::Option<Expr>),
+        Variant10(Vec<(bool, Expr)>),
+        Variant11(String),
     }
     const __ACTION: &[i8] = &[
         // State 0
-        22, 0, 0, 7, 0, 0, 0, 0, 0, 0, 23, 0, 0, 24, 0, 0, 25, 26,
+        27, 0, 0, 8, 0, 0, 0, 0, 28, 0, 0, 0, 29, 0, 0, 30, 0, 0, 31, 32, 33, 34, 0, 35, 36, 0,
         // State 1
-        22, 0, 0, 7, 0, 0, 0, 0, 0, 0, 23, 0, 0, 24, 0, 0, 25, 26,
+        27, 0, 0, 8, 0, 0, 0, 0, 28, 0, 0, 0, 29, 0, 0, 30, 0, 0, 31, 32, 33, 34, 0, 0, 36, 0,
         // State 2
-        0, -32, -32, 0, -32, -32, -32, -32, -32, -32, 30, 31, -32, 0, -32, -32, 0, 0,
+        27, 0, 0, 8, -58, 0, -58, 0, 28, 0, 0, 0, 29, 0, 0, 30, 0, 0, 31, 32, 33, 34, 0, 35, 36, 0,
         // State 3
-        0, -39, -39, 0, -39, -39, -39, -39, 32, 33, 0, 0, -39, 0, -39, -39, 0, 0,
+        0, -37, -37, 0, -37, -37, -37, -37, 0, -37, -37, -37, 41, 42, -37, 0, -37, -37, 0, 0, 0, 0, 43, -37, 0, 44,
         // State 4
-        0, 34, 35, 0, -41, -41, -41, 36, 0, 0, 0, 0, 37, 0, -41, -41, 0, 0,
+        0, -46, -46, 0, -46, -46, -46, -46, 0, 12, 13, 45, 0, 0, -46, 0, -46, -46, 0, 0, 0, 0, 0, -46, 0, 0,
         // State 5
-        0, 0, 0, 0, -25, 38, -25, 0, 0, 0, 0, 0, 0, 0, 39, 40, 0, 0,
+        0, 46, 47, 0, -48, -48, -48, 48, 0, 0, 0, 0, 0, 0, 49, 0, -48, -48, 0, 0, 0, 0, 0, -48, 0, 0,
         // State 6
-        22, 0, 0, 7, 0, 0, 0, 0, 0, 0, 23, 0, 0, 24, 0, 0, 25, 26,
+        0, 0, 0, 0, -27, 51, -27, 0, 0, 0, 0, 0, 0, 0, 0, 0, 52, 53, 0, 0, 0, 0, 0, 35, 0, 0,
         // State 7
-        0, -33, -33, 0, -33, -33, -33, -33, -33, -33, 30, 31, -33, 0, -33, -33, 0, 0,
+        27, 0, 0, 8, 0, 0, 0, 0, 28, 0, 0, 0, 29, 0, 0, 30, 0, 0, 31, 32, 33, 34, 0, 35, 36, 0,
         // State 8
-        22, 0, 0, 7, 0, 0, 0, 0, 0, 0, 23, 0, 0, 24, 0, 0, 25, 26,
+        0, 0, 0, 0, -60, 51, -60, 0, 0, 0, 0, 0, 0, 0, 0, 0, 52, 53, 0, 0, 0, 0, 0, 35, 0, 0,
         // State 9
-        22, 0, 0, 7, 0, 0, 0, 0, 0, 0, 23, 0, 0, 24, 0, 0, 25, 26,
+        0, -38, -38, 0, -38, -38, -38, -38, 0, -38, -38, -38, 41, 42, -38, 0, -38, -38, 0, 0, 0, 0, 43, -38, 0, 44,
         // State 10
-        22, 0, 0, 7, 0, 0, 0, 0, 0, 0, 23, 0, 0, 24, 0, 0, 25, 26,
+        27, 0, 0, 8, 0, 0, 0, 0, 28, 0, 0, 0, 29, 0, 0, 30, 0, 0, 31, 32, 33, 34, 0, 0, 36, 0,
         // State 11
-        22, 0, 0, 7, 45, 0, 0, 0, 0, 0, 23, 0, 0, 24, 0, 0, 25, 26,
+        27, 0, 0, 8, 0, 0, 0, 0, 28, 0, 0, 0, 29, 0, 0, 30, 0, 0, 31, 32, 33, 34, 0, 0, 36, 0,
         // State 12
-        0, -38, -38, 0, -38, -38, -38, -38, 32, 33, 0, 0, -38, 0, -38, -38, 0, 0,
+        27, 0, 0, 8, 0, 0, 0, 0, 28, 0, 0, 0, 29, 0, 0, 30, 0, 0, 31, 32, 33, 34, 0, 0, 36, 0,
         // State 13
-        0, 34, 35, 0, -40, -40, -40, 36, 0, 0, 0, 0, 37, 0, -40, -40, 0, 0,
+        27, 0, 0, 8, 0, 0, 0, 0, 28, 0, 0, 0, 29, 0, 0, 30, 0, 0, 31, 32, 33, 34, 0, 0, 36, 0,
         // State 14
-        22, 0, 0, 7, 48, 0, 0, 0, 0, 0, 23, 0, 0, 24, 0, 0, 25, 26,
+        27, 0, 0, 8, 0, 0, 0, 0, 28, 0, 0, 0, 29, 0, 0, 30, 0, 0, 31, 32, 33, 34, 0, 0, 36, 0,
         // State 15
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        27, 0, 0, 8, 61, 0, 0, 0, 28, 0, 0, 0, 29, 0, 0, 30, 0, 0, 31, 32, 33, 34, 0, 35, 36, 0,
         // State 16
-        0, -34, -34, 0, -34, -34, -34, -34, -34, -34, 0, 0, -34, 0, -34, -34, 0, 0,
+        0, -45, -45, 0, -45, -45, -45, -45, 0, 12, 13, 45, 0, 0, -45, 0, -45, -45, 0, 0, 0, 0, 0, -45, 0, 0,
         // State 17
-        0, -37, -37, 0, -37, -37, -37, -37, -37, -37, 0, 0, -37, 0, -37, -37, 0, 0,
+        0, 46, 47, 0, -47, -47, -47, 48, 0, 0, 0, 0, 0, 0, 49, 0, -47, -47, 0, 0, 0, 0, 0, -47, 0, 0,
         // State 18
-        -9, 0, 0, -9, 0, 0, 0, 0, 0, 0, -9, 0, 0, -9, 0, 0, -9, -9,
+        27, 0, 0, 8, 64, 0, 0, 0, 28, 0, 0, 0, 29, 0, 0, 30, 0, 0, 31, 32, 33, 34, 0, 35, 36, 0,
         // State 19
-        0, -50, -50, 12, -50, -50, -50, -50, -50, -50, -50, -50, -50, 0, -50, -50, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 20
-        0, -26, -26, 0, -26, -26, -26, -26, -26, -26, -26, -26, -26, 0, -26, -26, 0, 0,
+        0, -39, -39, 0, -39, -39, -39, -39, 0, -39, -39, -39, 0, 0, -39, 0, -39, -39, 0, 0, 0, 0, 0, -39, 0, 0,
         // State 21
-        -46, 0, 0, -46, 0, 0, 0, 0, 0, 0, -46, 0, 0, -46, 0, 0, -46, -46,
+        0, -44, -44, 0, -44, -44, -44, -44, 0, -44, -44, -44, 0, 0, -44, 0, -44, -44, 0, 0, 0, 0, 0, -44, 0, 0,
         // State 22
-        -48, 0, 0, -48, 0, 0, 0, 0, 0, 0, -48, 0, 0, -48, 0, 0, -48, -48,
+        -9, 0, 0, -9, 0, 0, 0, 0, -9, 0, 0, 0, -9, 0, 0, -9, 0, 0, -9, -9, -9, -9, 0, 0, -9, 0,
         // State 23
-        -47, 0, 0, -47, 0, 0, 0, 0, 0, 0, -47, 0, 0, -47, 0, 0, -47, -47,
+        0, 0, 0, 0, -29, 0, -29, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 24
-        0, -49, -49, -49, -49, -49, -49, -49, -49, -49, -49, -49, -49, 0, -49, -49, 0, 0,
+        0, -64, -64, 16, -64, -64, -64, -64, 0, -64, -64, -64, -64, -64, -64, 0, -64, -64, 0, 0, 0, 0, -64, -64, 0, -64,
         // State 25
-        0, -51, -51, 0, -51, -51, -51, -51, -51, -51, -51, -51, -51, 0, -51, -51, 0, 0,
+        0, -30, -30, 0, -30, -30, -30, -30, 0, -30, -30, -30, -30, -30, -30, 0, -30, -30, 0, 0, 0, 0, -30, -30, 0, -30,
         // State 26
-        0, -35, -35, 0, -35, -35, -35, -35, -35, -35, 0, 0, -35, 0, -35, -35, 0, 0,
+        -55, 0, 0, -55, 0, 0, 0, 0, -55, 0, 0, 0, -55, 0, 0, -55, 0, 0, -55, -55, -55, -55, 0, 0, -55, 0,
         // State 27
-        -10, 0, 0, -10, 0, 0, 0, 0, 0, 0, -10, 0, 0, -10, 0, 0, -10, -10,
+        0, -63, -63, -63, -63, -63, -63, -63, 0, -63, -63, -63, -63, -63, -63, 0, -63, -63, 0, 0, 0, 0, -63, -63, 0, -63,
         // State 28
-        0, -4, -4, 0, -4, -4, -4, -4, -4, -4, -4, -4, -4, 0, -4, -4, 0, 0,
+        -57, 0, 0, -57, 0, 0, 0, 0, -57, 0, 0, 0, -57, 0, 0, -57, 0, 0, -57, -57, -57, -57, 0, 0, -57, 0,
         // State 29
-        0, -44, -44, 0, -44, -44, -44, -44, -44, -44, -44, -44, -44, 0, -44, -44, 0, 0,
+        -56, 0, 0, -56, 0, 0, 0, 0, -56, 0, 0, 0, -56, 0, 0, -56, 0, 0, -56, -56, -56, -56, 0, 0, -56, 0,
         // State 30
-        0, -45, -45, 0, -45, -45, -45, -45, -45, -45, -45, -45, -45, 0, -45, -45, 0, 0,
+        0, -66, -66, 0, -66, -66, -66, -66, 0, -66, -66, -66, -66, -66, -66, 0, -66, -66, 0, 0, 0, 0, -66, -66, 0, -66,
         // State 31
-        -17, 0, 0, -17, 0, 0, 0, 0, 0, 0, -17, 0, 0, -17, 0, 0, -17, -17,
+        0, -31, -31, 0, -31, -31, -31, -31, 0, -31, -31, -31, -31, -31, -31, 0, -31, -31, 0, 0, 0, 0, -31, -31, 0, -31,
         // State 32
-        -16, 0, 0, -16, 0, 0, 0, 0, 0, 0, -16, 0, 0, -16, 0, 0, -16, -16,
+        0, -65, -65, 0, -65, -65, -65, -65, 0, -65, -65, -65, -65, -65, -65, 0, -65, -65, 0, 0, 0, 0, -65, -65, 0, -65,
         // State 33
-        -21, 0, 0, -21, 0, 0, 0, 0, 0, 0, -21, 0, 0, -21, 0, 0, -21, -21,
+        0, -62, -62, -62, -62, -62, -62, -62, 0, -62, -62, -62, -62, -62, -62, 0, -62, -62, 0, 0, 0, 0, -62, -62, 0, -62,
         // State 34
-        -18, 0, 0, -18, 0, 0, 0, 0, 0, 0, -18, 0, 0, -18, 0, 0, -18, -18,
+        -26, 0, 0, -26, -26, 0, -26, 0, -26, 0, 0, 0, -26, 0, 0, -26, 0, 0, -26, -26, -26, -26, 0, -26, -26, 0,
         // State 35
-        -20, 0, 0, -20, 0, 0, 0, 0, 0, 0, -20, 0, 0, -20, 0, 0, -20, -20,
+        0, -67, -67, 0, -67, -67, -67, -67, 0, -67, -67, -67, -67, -67, -67, 0, -67, -67, 0, 0, 0, 0, -67, -67, 0, -67,
         // State 36
-        -19, 0, 0, -19, 0, 0, 0, 0, 0, 0, -19, 0, 0, -19, 0, 0, -19, -19,
+        0, -40, -40, 0, -40, -40, -40, -40, 0, -40, -40, -40, 0, 0, -40, 0, -40, -40, 0, 0, 0, 0, 0, -40, 0, 0,
         // State 37
-        -23, 0, 0, -23, 0, 0, 0, 0, 0, 0, -23, 0, 0, -23, 0, 0, -23, -23,
+        -10, 0, 0, -10, 0, 0, 0, 0, -10, 0, 0, 0, -10, 0, 0, -10, 0, 0, -10, -10, -10, -10, 0, 0, -10, 0,
         // State 38
-        -24, 0, 0, -24, 0, 0, 0, 0, 0, 0, -24, 0, 0, -24, 0, 0, -24, -24,
+        0, 0, 0, 0, -59, 0, -59, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 39
-        -22, 0, 0, -22, 0, 0, 0, 0, 0, 0, -22, 0, 0, -22, 0, 0, -22, -22,
+        0, -4, -4, 0, -4, -4, -4, -4, 0, -4, -4, -4, -4, -4, -4, 0, -4, -4, 0, 0, 0, 0, -4, -4, 0, -4,
         // State 40
-        0, 0, 0, 0, 46, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, -51, -51, 0, -51, -51, -51, -51, 0, -51, -51, -51, -51, -51, -51, 0, -51, -51, 0, 0, 0, 0, -51, -51, 0, -51,
         // State 41
-        0, -5, -5, 0, -5, -5, -5, -5, -5, -5, -5, -5, -5, 0, -5, -5, 0, 0,
+        0, -52, -52, 0, -52, -52, -52, -52, 0, -52, -52, -52, -52, -52, -52, 0, -52, -52, 0, 0, 0, 0, -52, -52, 0, -52,
         // State 42
-        0, -36, -36, 0, -36, -36, -36, -36, -36, -36, 0, 0, -36, 0, -36, -36, 0, 0,
+        0, -53, -53, 0, -53, -53, -53, -53, 0, -53, -53, -53, -53, -53, -53, 0, -53, -53, 0, 0, 0, 0, -53, -53, 0, -53,
         // State 43
-        0, 0, 0, 0, 49, 0, 50, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, -54, -54, 0, -54, -54, -54, -54, 0, -54, -54, -54, -54, -54, -54, 0, -54, -54, 0, 0, 0, 0, -54, -54, 0, -54,
         // State 44
-        0, -28, -28, 0, -28, -28, -28, -28, -28, -28, -28, -28, -28, 0, -28, -28, 0, 0,
+        -18, 0, 0, -18, 0, 0, 0, 0, -18, 0, 0, 0, -18, 0, 0, -18, 0, 0, -18, -18, -18, -18, 0, 0, -18, 0,
         // State 45
-        0, -31, -31, 0, -31, -31, -31, -31, -31, -31, -31, -31, -31, 0, -31, -31, 0, 0,
+        -22, 0, 0, -22, 0, 0, 0, 0, -22, 0, 0, 0, -22, 0, 0, -22, 0, 0, -22, -22, -22, -22, 0, 0, -22, 0,
         // State 46
-        0, 0, 0, 0, 51, 0, 52, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        -19, 0, 0, -19, 0, 0, 0, 0, -19, 0, 0, 0, -19, 0, 0, -19, 0, 0, -19, -19, -19, -19, 0, 0, -19, 0,
         // State 47
-        0, -30, -30, 0, -30, -30, -30, -30, -30, -30, -30, -30, -30, 0, -30, -30, 0, 0,
+        -21, 0, 0, -21, 0, 0, 0, 0, -21, 0, 0, 0, -21, 0, 0, -21, 0, 0, -21, -21, -21, -21, 0, 0, -21, 0,
         // State 48
-        0, -27, -27, 0, -27, -27, -27, -27, -27, -27, -27, -27, -27, 0, -27, -27, 0, 0,
+        -20, 0, 0, -20, 0, 0, 0, 0, -20, 0, 0, 0, -20, 0, 0, -20, 0, 0, -20, -20, -20, -20, 0, 0, -20, 0,
         // State 49
-        -14, 0, 0, -14, -14, 0, 0, 0, 0, 0, -14, 0, 0, -14, 0, 0, -14, -14,
+        0, 0, 0, 0, -28, 0, -28, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 50
-        0, -29, -29, 0, -29, -29, -29, -29, -29, -29, -29, -29, -29, 0, -29, -29, 0, 0,
+        -24, 0, 0, -24, 0, 0, 0, 0, -24, 0, 0, 0, -24, 0, 0, -24, 0, 0, -24, -24, -24, -24, 0, 0, -24, 0,
         // State 51
-        -15, 0, 0, -15, -15, 0, 0, 0, 0, 0, -15, 0, 0, -15, 0, 0, -15, -15,
+        -25, 0, 0, -25, 0, 0, 0, 0, -25, 0, 0, 0, -25, 0, 0, -25, 0, 0, -25, -25, -25, -25, 0, 0, -25, 0,
+        // State 52
+        -23, 0, 0, -23, 0, 0, 0, 0, -23, 0, 0, 0, -23, 0, 0, -23, 0, 0, -23, -23, -23, -23, 0, 0, -23, 0,
+        // State 53
+        0, 0, 0, 0, 62, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        // State 54
+        0, 0, 0, 0, -61, 0, -61, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        // State 55
+        0, -5, -5, 0, -5, -5, -5, -5, 0, -5, -5, -5, -5, -5, -5, 0, -5, -5, 0, 0, 0, 0, -5, -5, 0, -5,
+        // State 56
+        0, -41, -41, 0, -41, -41, -41, -41, 0, -41, -41, -41, 0, 0, -41, 0, -41, -41, 0, 0, 0, 0, 0, -41, 0, 0,
+        // State 57
+        0, -42, -42, 0, -42, -42, -42, -42, 0, -42, -42, -42, 0, 0, -42, 0, -42, -42, 0, 0, 0, 0, 0, -42, 0, 0,
+        // State 58
+        0, -43, -43, 0, -43, -43, -43, -43, 0, -43, -43, -43, 0, 0, -43, 0, -43, -43, 0, 0, 0, 0, 0, -43, 0, 0,
+        // State 59
+        0, 0, 0, 0, 65, 0, 66, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        // State 60
+        0, -33, -33, 0, -33, -33, -33, -33, 0, -33, -33, -33, -33, -33, -33, 0, -33, -33, 0, 0, 0, 0, -33, -33, 0, -33,
+        // State 61
+        0, -36, -36, 0, -36, -36, -36, -36, 0, -36, -36, -36, -36, -36, -36, 0, -36, -36, 0, 0, 0, 0, -36, -36, 0, -36,
+        // State 62
+        0, 0, 0, 0, 67, 0, 68, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        // State 63
+        0, -35, -35, 0, -35, -35, -35, -35, 0, -35, -35, -35, -35, -35, -35, 0, -35, -35, 0, 0, 0, 0, -35, -35, 0, -35,
+        // State 64
+        0, -32, -32, 0, -32, -32, -32, -32, 0, -32, -32, -32, -32, -32, -32, 0, -32, -32, 0, 0, 0, 0, -32, -32, 0, -32,
+        // State 65
+        -14, 0, 0, -14, -14, 0, 0, 0, -14, 0, 0, 0, -14, 0, 0, -14, 0, 0, -14, -14, -14, -14, 0, -14, -14, 0,
+        // State 66
+        0, -34, -34, 0, -34, -34, -34, -34, 0, -34, -34, -34, -34, -34, -34, 0, -34, -34, 0, 0, 0, 0, -34, -34, 0, -34,
+        // State 67
+        -15, 0, 0, -15, -15, 0, 0, 0, -15, 0, 0, 0, -15, 0, 0, -15, 0, 0, -15, -15, -15, -15, 0, -15, -15, 0,
     ];
     fn __action(state: i8, integer: usize) -> i8 {
-        __ACTION[(state as usize) * 18 + integer]
+        __ACTION[(state as usize) * 26 + integer]
     }
     const __EOF_ACTION: &[i8] = &[
         // State 0
@@ -143,152 +189,196 @@ mod __parse__Expr {
         // State 1
         0,
         // State 2
-        -32,
+        -58,
         // State 3
-        -39,
+        -37,
         // State 4
-        -41,
+        -46,
         // State 5
-        -25,
+        -48,
         // State 6
-        0,
+        -27,
         // State 7
-        -33,
-        // State 8
         0,
+        // State 8
+        -60,
         // State 9
-        0,
+        -38,
         // State 10
         0,
         // State 11
         0,
         // State 12
-        -38,
+        0,
         // State 13
-        -40,
+        0,
         // State 14
         0,
         // State 15
-        -52,
+        0,
         // State 16
-        -34,
+        -45,
         // State 17
-        -37,
+        -47,
         // State 18
         0,
         // State 19
-        -50,
+        -68,
         // State 20
-        -26,
+        -39,
         // State 21
-        0,
+        -44,
         // State 22
         0,
         // State 23
-        0,
+        -29,
         // State 24
-        -49,
+        -64,
         // State 25
-        -51,
+        -30,
         // State 26
-        -35,
-        // State 27
         0,
+        // State 27
+        -63,
         // State 28
-        -4,
+        0,
         // State 29
-        -44,
+        0,
         // State 30
-        -45,
+        -66,
         // State 31
-        0,
+        -31,
         // State 32
-        0,
+        -65,
         // State 33
-        0,
+        -62,
         // State 34
-        0,
+        -26,
         // State 35
-        0,
+        -67,
         // State 36
-        0,
+        -40,
         // State 37
         0,
         // State 38
-        0,
+        -59,
         // State 39
-        0,
+        -4,
         // State 40
-        0,
+        -51,
         // State 41
-        -5,
+        -52,
         // State 42
-        -36,
+        -53,
         // State 43
-        0,
+        -54,
         // State 44
-        -28,
+        0,
         // State 45
-        -31,
+        0,
         // State 46
         0,
         // State 47
-        -30,
+        0,
         // State 48
-        -27,
-        // State 49
         0,
+        // State 49
+        -28,
         // State 50
-        -29,
+        0,
         // State 51
         0,
+        // State 52
+        0,
+        // State 53
+        0,
+        // State 54
+        -61,
+        // State 55
+        -5,
+        // State 56
+        -41,
+        // State 57
+        -42,
+        // State 58
+        -43,
+        // State 59
+        0,
+        // State 60
+        -33,
+        // State 61
+        -36,
+        // State 62
+        0,
+        // State 63
+        -35,
+        // State 64
+        -32,
+        // State 65
+        0,
+        // State 66
+        -34,
+        // State 67
+        0,
     ];
     fn __goto(state: i8, nt: usize) -> i8 {
         match nt {
-            2 => 7,
+            2 => 9,
             5 => 1,
-            8 => 14,
-            9 => 8,
-            10 => 9,
+            8 => 18,
             11 => 10,
-            12 => match state {
-                6 => 40,
-                11 => 43,
-                14 => 46,
-                _ => 15,
-            },
-            13 => 2,
-            14 => match state {
-                1 => 26,
-                _ => 16,
-            },
+            12 => 13,
+            13 => 14,
+            14 => 2,
             15 => match state {
-                8 => 42,
-                _ => 17,
-            },
-            16 => match state {
-                9 => 12,
-                _ => 3,
+                7 => 53,
+                15 => 59,
+                18 => 62,
+                _ => 19,
             },
+            16 => 3,
             17 => match state {
-                10 => 13,
+                1 => 36,
+                _ => 20,
+            },
+            18 => match state {
+                10 => 56,
+                11 => 57,
+                12 => 58,
+                _ => 21,
+            },
+            19 => match state {
+                13 => 16,
                 _ => 4,
             },
-            18 => 5,
             20 => match state {
-                7 => 41,
-                _ => 28,
+                14 => 17,
+                _ => 5,
             },
             21 => match state {
-                1 => 27,
-                _ => 18,
+                2 => 8,
+                _ => 6,
             },
-            22 => 19,
-            23 => 20,
+            23 => match state {
+                9 => 55,
+                _ => 39,
+            },
+            24 => match state {
+                1 => 37,
+                _ => 22,
+            },
+            25 => match state {
+                2 => 38,
+                6 => 49,
+                8 => 54,
+                _ => 23,
+            },
+            26 => 24,
+            27 => 25,
             _ => 0,
         }
     }
-    fn __expected_tokens(__state: i8) -> Vec<::std::string::String> {
+    fn __expected_tokens(__state: i8) -> alloc::vec::Vec<alloc::string::String> {
         const __TERMINAL: &[&str] = &[
             r###""!""###,
             r###""%""###,
@@ -298,7 +388,9 @@ mod __parse__Expr {
             r###""+""###,
             r###"",""###,
             r###""-""###,
+            r###"".""###,
             r###""..""###,
+            r###""...""###,
             r###"":""###,
             r###""::""###,
             r###""^""###,
@@ -306,29 +398,36 @@ mod __parse__Expr {
             r###""not ""###,
             r###""or""###,
             r###""|""###,
-            r###"r#"[a-zA-Z0-9/_$@.]+"#"###,
+            r###"r#"([a-zA-Z0-9/_$@]+(\\.[a-zA-Z0-9/_$@]+)*)?@\\{(upstream|u|push)\\}"#"###,
+            r###"r#"/([^/\\x5c]|\\x5c.)*/"#"###,
+            r###"r#"@\\{-[0-9]+\\}"#"###,
+            r###"r#"[a-zA-Z0-9/_$@]+(\\.[a-zA-Z0-9/_$@]+)*"#"###,
+            r###"r#"\\^[0-9]+"#"###,
+            r###"r#"\\^[a-zA-Z0-9/_$@]+(\\.[a-zA-Z0-9/_$@]+)*"#"###,
             r###"r#"\\x22([^\\x22\\x5c]|\\x5c.)*\\x22"#"###,
+            r###"r#"~[0-9]*"#"###,
         ];
         __TERMINAL.iter().enumerate().filter_map(|(index, terminal)| {
             let next_state = __action(__state, index);
             if next_state == 0 {
                 None
             } else {
-                Some(terminal.to_string())
+                Some(alloc::string::ToString::to_string(terminal))
             }
         }).collect()
     }
-    pub struct __StateMachine<'input>
+    pub(crate) struct __StateMachine<'input, '__1>
     where 
     {
+        src: &'__1 str,
         input: &'input str,
-        __phantom: ::std::marker::PhantomData<(&'input ())>,
+        __phantom: core::marker::PhantomData<(&'input ())>,
     }
-    impl<'input> __state_machine::ParserDefinition for __StateMachine<'input>
+    impl<'input, '__1> __state_machine::ParserDefinition for __StateMachine<'input, '__1>
     where 
     {
         type Location = usize;
-        type Error = &'static str;
+        type Error = GrammarError;
         type Token = Token<'input>;
         type TokenIndex = usize;
         type Symbol = __Symbol<'input>;
@@ -350,7 +449,7 @@ mod __parse__Expr {
 
         #[inline]
         fn token_to_index(&self, token: &Self::Token) -> Option<usize> {
-            __token_to_integer(token, ::std::marker::PhantomData::<(&())>)
+            __token_to_integer(token, core::marker::PhantomData::<(&())>)
         }
 
         #[inline]
@@ -360,7 +459,7 @@ mod __parse__Expr {
 
         #[inline]
         fn error_action(&self, state: i8) -> i8 {
-            __action(state, 18 - 1)
+            __action(state, 26 - 1)
         }
 
         #[inline]
@@ -374,10 +473,10 @@ mod __parse__Expr {
         }
 
         fn token_to_symbol(&self, token_index: usize, token: Self::Token) -> Self::Symbol {
-            __token_to_symbol(token_index, token, ::std::marker::PhantomData::<(&())>)
+            __token_to_symbol(token_index, token, core::marker::PhantomData::<(&())>)
         }
 
-        fn expected_tokens(&self, state: i8) -> Vec<String> {
+        fn expected_tokens(&self, state: i8) -> alloc::vec::Vec<alloc::string::String> {
             __expected_tokens(state)
         }
 
@@ -398,16 +497,17 @@ mod __parse__Expr {
             &mut self,
             action: i8,
             start_location: Option<&Self::Location>,
-            states: &mut Vec<i8>,
-            symbols: &mut Vec<__state_machine::SymbolTriple<Self>>,
+            states: &mut alloc::vec::Vec<i8>,
+            symbols: &mut alloc::vec::Vec<__state_machine::SymbolTriple<Self>>,
         ) -> Option<__state_machine::ParseResult<Self>> {
             __reduce(
+                self.src,
                 self.input,
                 action,
                 start_location,
                 states,
                 symbols,
-                ::std::marker::PhantomData::<(&())>,
+                core::marker::PhantomData::<(&())>,
             )
         }
 
@@ -419,28 +519,36 @@ mod __parse__Expr {
         'input,
     >(
         __token: &Token<'input>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> Option<usize>
     {
         match *__token {
-            Token(2, _) if true => Some(0),
-            Token(3, _) if true => Some(1),
-            Token(4, _) if true => Some(2),
-            Token(5, _) if true => Some(3),
-            Token(6, _) if true => Some(4),
-            Token(7, _) if true => Some(5),
-            Token(8, _) if true => Some(6),
-            Token(9, _) if true => Some(7),
-            Token(10, _) if true => Some(8),
-            Token(11, _) if true => Some(9),
-            Token(12, _) if true => Some(10),
-            Token(13, _) if true => Some(11),
-            Token(14, _) if true => Some(12),
-            Token(15, _) if true => Some(13),
-            Token(16, _) if true => Some(14),
-            Token(17, _) if true => Some(15),
-            Token(0, _) if true => Some(16),
-            Token(1, _) if true => Some(17),
+            Token(6, _) if true => Some(0),
+            Token(7, _) if true => Some(1),
+            Token(8, _) if true => Some(2),
+            Token(9, _) if true => Some(3),
+            Token(10, _) if true => Some(4),
+            Token(11, _) if true => Some(5),
+            Token(12, _) if true => Some(6),
+            Token(13, _) if true => Some(7),
+            Token(14, _) if true => Some(8),
+            Token(15, _) if true => Some(9),
+            Token(16, _) if true => Some(10),
+            Token(17, _) if true => Some(11),
+            Token(18, _) if true => Some(12),
+            Token(19, _) if true => Some(13),
+            Token(20, _) if true => Some(14),
+            Token(21, _) if true => Some(15),
+            Token(22, _) if true => Some(16),
+            Token(23, _) if true => Some(17),
+            Token(0, _) if true => Some(18),
+            Token(24, _) if true => Some(19),
+            Token(1, _) if true => Some(20),
+            Token(2, _) if true => Some(21),
+            Token(25, _) if true => Some(22),
+            Token(3, _) if true => Some(23),
+            Token(4, _) if true => Some(24),
+            Token(5, _) if true => Some(25),
             _ => None,
         }
     }
@@ -449,12 +557,12 @@ mod __parse__Expr {
     >(
         __token_index: usize,
         __token: Token<'input>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> __Symbol<'input>
     {
         match __token_index {
-            0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9 | 10 | 11 | 12 | 13 | 14 | 15 | 16 | 17 => match __token {
-                Token(2, __tok0) | Token(3, __tok0) | Token(4, __tok0) | Token(5, __tok0) | Token(6, __tok0) | Token(7, __tok0) | Token(8, __tok0) | Token(9, __tok0) | Token(10, __tok0) | Token(11, __tok0) | Token(12, __tok0) | Token(13, __tok0) | Token(14, __tok0) | Token(15, __tok0) | Token(16, __tok0) | Token(17, __tok0) | Token(0, __tok0) | Token(1, __tok0) if true => __Symbol::Variant0(__tok0),
+            0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9 | 10 | 11 | 12 | 13 | 14 | 15 | 16 | 17 | 18 | 19 | 20 | 21 | 22 | 23 | 24 | 25 => match __token {
+                Token(6, __tok0) | Token(7, __tok0) | Token(8, __tok0) | Token(9, __tok0) | Token(10, __tok0) | Token(11, __tok0) | Token(12, __tok0) | Token(13, __tok0) | Token(14, __tok0) | Token(15, __tok0) | Token(16, __tok0) | Token(17, __tok0) | Token(18, __tok0) | Token(19, __tok0) | Token(20, __tok0) | Token(21, __tok0) | Token(22, __tok0) | Token(23, __tok0) | Token(0, __tok0) | Token(24, __tok0) | Token(1, __tok0) | Token(2, __tok0) | Token(25, __tok0) | Token(3, __tok0) | Token(4, __tok0) | Token(5, __tok0) if true => __Symbol::Variant0(__tok0),
                 _ => unreachable!(),
             },
             _ => unreachable!(),
@@ -479,14 +587,16 @@ mod __parse__Expr {
             'input,
         >(
             &self,
+            src: &str,
             input: &'input str,
-        ) -> Result<Expr, __lalrpop_util::ParseError<usize, Token<'input>, &'static str>>
+        ) -> Result<Expr, __lalrpop_util::ParseError<usize, Token<'input>, GrammarError>>
         {
             let mut __tokens = self.builder.matcher(input);
             __state_machine::Parser::drive(
                 __StateMachine {
+                    src,
                     input,
-                    __phantom: ::std::marker::PhantomData::<(&())>,
+                    __phantom: core::marker::PhantomData::<(&())>,
                 },
                 __tokens,
             )
@@ -495,174 +605,241 @@ mod __parse__Expr {
     pub(crate) fn __reduce<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __action: i8,
         __lookahead_start: Option<&usize>,
-        __states: &mut ::std::vec::Vec<i8>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
-    ) -> Option<Result<Expr,__lalrpop_util::ParseError<usize, Token<'input>, &'static str>>>
+        __states: &mut alloc::vec::Vec<i8>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> Option<Result<Expr,__lalrpop_util::ParseError<usize, Token<'input>, GrammarError>>>
     {
         let (__pop_states, __nonterminal) = match __action {
             0 => {
-                __reduce0(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce0(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             1 => {
-                __reduce1(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce1(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             2 => {
-                __reduce2(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce2(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             3 => {
-                __reduce3(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce3(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             4 => {
-                __reduce4(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce4(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             5 => {
-                __reduce5(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce5(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             6 => {
-                __reduce6(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce6(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             7 => {
-                __reduce7(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce7(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             8 => {
-                __reduce8(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce8(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             9 => {
-                __reduce9(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce9(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             10 => {
-                __reduce10(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce10(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             11 => {
-                __reduce11(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce11(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             12 => {
-                __reduce12(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce12(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             13 => {
-                __reduce13(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce13(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             14 => {
-                __reduce14(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce14(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             15 => {
-                __reduce15(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce15(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             16 => {
-                __reduce16(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce16(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             17 => {
-                __reduce17(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce17(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             18 => {
-                __reduce18(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce18(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             19 => {
-                __reduce19(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce19(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             20 => {
-                __reduce20(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce20(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             21 => {
-                __reduce21(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce21(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             22 => {
-                __reduce22(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce22(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             23 => {
-                __reduce23(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce23(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             24 => {
-                __reduce24(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce24(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             25 => {
-                __reduce25(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                // Exclude = r#"\\^[a-zA-Z0-9/_$@]+(\\.[a-zA-Z0-9/_$@]+)*"# => ActionFn(77);
+                let __sym0 = __pop_Variant0(__symbols);
+                let __start = __sym0.0.clone();
+                let __end = __sym0.2.clone();
+                let __nt = match super::__action77::<>(src, input, __sym0) {
+                    Ok(v) => v,
+                    Err(e) => return Some(Err(e)),
+                };
+                __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+                (1, 14)
             }
             26 => {
-                __reduce26(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce26(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             27 => {
-                __reduce27(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce27(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             28 => {
-                __reduce28(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce28(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             29 => {
-                __reduce29(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce29(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             30 => {
-                __reduce30(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce30(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             31 => {
-                __reduce31(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce31(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             32 => {
-                __reduce32(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce32(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             33 => {
-                __reduce33(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce33(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             34 => {
-                __reduce34(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce34(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             35 => {
-                __reduce35(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce35(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             36 => {
-                __reduce36(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce36(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             37 => {
-                __reduce37(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce37(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             38 => {
-                __reduce38(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce38(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             39 => {
-                __reduce39(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce39(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             40 => {
-                __reduce40(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce40(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             41 => {
-                __reduce41(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce41(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             42 => {
-                __reduce42(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce42(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             43 => {
-                __reduce43(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce43(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             44 => {
-                __reduce44(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce44(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             45 => {
-                __reduce45(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce45(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             46 => {
-                __reduce46(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce46(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             47 => {
-                __reduce47(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce47(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             48 => {
-                __reduce48(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce48(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             49 => {
-                __reduce49(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce49(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             50 => {
-                __reduce50(input, __lookahead_start, __symbols, ::std::marker::PhantomData::<(&())>)
+                __reduce50(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             51 => {
+                __reduce51(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            52 => {
+                // Postfix = r#"\\^[0-9]+"# => ActionFn(78);
+                let __sym0 = __pop_Variant0(__symbols);
+                let __start = __sym0.0.clone();
+                let __end = __sym0.2.clone();
+                let __nt = match super::__action78::<>(src, input, __sym0) {
+                    Ok(v) => v,
+                    Err(e) => return Some(Err(e)),
+                };
+                __symbols.push((__start, __Symbol::Variant1(__nt), __end));
+                (1, 23)
+            }
+            53 => {
+                __reduce53(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            54 => {
+                __reduce54(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            55 => {
+                __reduce55(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            56 => {
+                __reduce56(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            57 => {
+                __reduce57(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            58 => {
+                __reduce58(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            59 => {
+                __reduce59(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            60 => {
+                __reduce60(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            61 => {
+                __reduce61(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            62 => {
+                __reduce62(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            63 => {
+                __reduce63(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            64 => {
+                __reduce64(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            65 => {
+                __reduce65(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            66 => {
+                __reduce66(src, input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            67 => {
                 // __Expr = Expr => ActionFn(0);
-                let __sym0 = __pop_Variant5(__symbols);
+                let __sym0 = __pop_Variant8(__symbols);
                 let __start = __sym0.0.clone();
                 let __end = __sym0.2.clone();
-                let __nt = super::__action0::<>(input, __sym0);
+                let __nt = super::__action0::<>(src, input, __sym0);
                 return Some(Ok(__nt));
             }
             _ => panic!("invalid action code {}", __action)
@@ -678,999 +855,1359 @@ mod __parse__Expr {
     fn __symbol_type_mismatch() -> ! {
         panic!("symbol type mismatch")
     }
-    fn __pop_Variant3<
+    fn __pop_Variant5<
       'input,
     >(
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
     ) -> (usize, (Expr, &'input str), usize)
      {
-        match __symbols.pop().unwrap() {
-            (__l, __Symbol::Variant3(__v), __r) => (__l, __v, __r),
+        match __symbols.pop() {
+            Some((__l, __Symbol::Variant5(__v), __r)) => (__l, __v, __r),
             _ => __symbol_type_mismatch()
         }
     }
-    fn __pop_Variant5<
+    fn __pop_Variant1<
+      'input,
+    >(
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
+    ) -> (usize, (&'static str, Option<String>), usize)
+     {
+        match __symbols.pop() {
+            Some((__l, __Symbol::Variant1(__v), __r)) => (__l, __v, __r),
+            _ => __symbol_type_mismatch()
+        }
+    }
+    fn __pop_Variant8<
       'input,
     >(
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
     ) -> (usize, Expr, usize)
      {
-        match __symbols.pop().unwrap() {
-            (__l, __Symbol::Variant5(__v), __r) => (__l, __v, __r),
+        match __symbols.pop() {
+            Some((__l, __Symbol::Variant8(__v), __r)) => (__l, __v, __r),
             _ => __symbol_type_mismatch()
         }
     }
-    fn __pop_Variant7<
+    fn __pop_Variant11<
       'input,
     >(
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
     ) -> (usize, String, usize)
      {
-        match __symbols.pop().unwrap() {
-            (__l, __Symbol::Variant7(__v), __r) => (__l, __v, __r),
+        match __symbols.pop() {
+            Some((__l, __Symbol::Variant11(__v), __r)) => (__l, __v, __r),
+            _ => __symbol_type_mismatch()
+        }
+    }
+    fn __pop_Variant10<
+      'input,
+    >(
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
+    ) -> (usize, Vec<(bool, Expr)>, usize)
+     {
+        match __symbols.pop() {
+            Some((__l, __Symbol::Variant10(__v), __r)) => (__l, __v, __r),
             _ => __symbol_type_mismatch()
         }
     }
     fn __pop_Variant6<
       'input,
     >(
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>
-    ) -> (usize, ::std::option::Option<Expr>, usize)
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
+    ) -> (usize, alloc::vec::Vec<(Expr, &'input str)>, usize)
+     {
+        match __symbols.pop() {
+            Some((__l, __Symbol::Variant6(__v), __r)) => (__l, __v, __r),
+            _ => __symbol_type_mismatch()
+        }
+    }
+    fn __pop_Variant2<
+      'input,
+    >(
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
+    ) -> (usize, alloc::vec::Vec<(&'static str, Option<String>)>, usize)
      {
-        match __symbols.pop().unwrap() {
-            (__l, __Symbol::Variant6(__v), __r) => (__l, __v, __r),
+        match __symbols.pop() {
+            Some((__l, __Symbol::Variant2(__v), __r)) => (__l, __v, __r),
             _ => __symbol_type_mismatch()
         }
     }
     fn __pop_Variant4<
       'input,
     >(
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>
-    ) -> (usize, ::std::vec::Vec<(Expr, &'input str)>, usize)
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
+    ) -> (usize, alloc::vec::Vec<&'static str>, usize)
      {
-        match __symbols.pop().unwrap() {
-            (__l, __Symbol::Variant4(__v), __r) => (__l, __v, __r),
+        match __symbols.pop() {
+            Some((__l, __Symbol::Variant4(__v), __r)) => (__l, __v, __r),
             _ => __symbol_type_mismatch()
         }
     }
-    fn __pop_Variant2<
+    fn __pop_Variant9<
+      'input,
+    >(
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
+    ) -> (usize, core::option::Option<Expr>, usize)
+     {
+        match __symbols.pop() {
+            Some((__l, __Symbol::Variant9(__v), __r)) => (__l, __v, __r),
+            _ => __symbol_type_mismatch()
+        }
+    }
+    fn __pop_Variant7<
       'input,
     >(
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>
-    ) -> (usize, ::std::vec::Vec<&'static str>, usize)
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
+    ) -> (usize, usize, usize)
      {
-        match __symbols.pop().unwrap() {
-            (__l, __Symbol::Variant2(__v), __r) => (__l, __v, __r),
+        match __symbols.pop() {
+            Some((__l, __Symbol::Variant7(__v), __r)) => (__l, __v, __r),
             _ => __symbol_type_mismatch()
         }
     }
     fn __pop_Variant0<
       'input,
     >(
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
     ) -> (usize, &'input str, usize)
      {
-        match __symbols.pop().unwrap() {
-            (__l, __Symbol::Variant0(__v), __r) => (__l, __v, __r),
+        match __symbols.pop() {
+            Some((__l, __Symbol::Variant0(__v), __r)) => (__l, __v, __r),
             _ => __symbol_type_mismatch()
         }
     }
-    fn __pop_Variant1<
+    fn __pop_Variant3<
       'input,
     >(
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
     ) -> (usize, &'static str, usize)
      {
-        match __symbols.pop().unwrap() {
-            (__l, __Symbol::Variant1(__v), __r) => (__l, __v, __r),
+        match __symbols.pop() {
+            Some((__l, __Symbol::Variant3(__v), __r)) => (__l, __v, __r),
             _ => __symbol_type_mismatch()
         }
     }
     pub(crate) fn __reduce0<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // (<Postfix>) = Postfix => ActionFn(37);
+        // (<Postfix>) = Postfix => ActionFn(51);
         let __sym0 = __pop_Variant1(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action37::<>(input, __sym0);
+        let __nt = super::__action51::<>(src, input, __sym0);
         __symbols.push((__start, __Symbol::Variant1(__nt), __end));
         (1, 0)
     }
     pub(crate) fn __reduce1<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // (<Postfix>)* =  => ActionFn(35);
+        // (<Postfix>)* =  => ActionFn(49);
         let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2.clone())).unwrap_or_default();
         let __end = __start.clone();
-        let __nt = super::__action35::<>(input, &__start, &__end);
+        let __nt = super::__action49::<>(src, input, &__start, &__end);
         __symbols.push((__start, __Symbol::Variant2(__nt), __end));
         (0, 1)
     }
     pub(crate) fn __reduce2<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // (<Postfix>)* = (<Postfix>)+ => ActionFn(36);
+        // (<Postfix>)* = (<Postfix>)+ => ActionFn(50);
         let __sym0 = __pop_Variant2(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action36::<>(input, __sym0);
+        let __nt = super::__action50::<>(src, input, __sym0);
         __symbols.push((__start, __Symbol::Variant2(__nt), __end));
         (1, 1)
     }
     pub(crate) fn __reduce3<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // (<Postfix>)+ = Postfix => ActionFn(47);
+        // (<Postfix>)+ = Postfix => ActionFn(63);
         let __sym0 = __pop_Variant1(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action47::<>(input, __sym0);
+        let __nt = super::__action63::<>(src, input, __sym0);
         __symbols.push((__start, __Symbol::Variant2(__nt), __end));
         (1, 2)
     }
     pub(crate) fn __reduce4<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // (<Postfix>)+ = (<Postfix>)+, Postfix => ActionFn(48);
+        // (<Postfix>)+ = (<Postfix>)+, Postfix => ActionFn(64);
         assert!(__symbols.len() >= 2);
         let __sym1 = __pop_Variant1(__symbols);
         let __sym0 = __pop_Variant2(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym1.2.clone();
-        let __nt = super::__action48::<>(input, __sym0, __sym1);
+        let __nt = super::__action64::<>(src, input, __sym0, __sym1);
         __symbols.push((__start, __Symbol::Variant2(__nt), __end));
         (2, 2)
     }
     pub(crate) fn __reduce5<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // (<Prefix>) = Prefix => ActionFn(40);
-        let __sym0 = __pop_Variant1(__symbols);
+        // (<Prefix>) = Prefix => ActionFn(54);
+        let __sym0 = __pop_Variant3(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action40::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
+        let __nt = super::__action54::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant3(__nt), __end));
         (1, 3)
     }
     pub(crate) fn __reduce6<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // (<Prefix>)* =  => ActionFn(38);
+        // (<Prefix>)* =  => ActionFn(52);
         let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2.clone())).unwrap_or_default();
         let __end = __start.clone();
-        let __nt = super::__action38::<>(input, &__start, &__end);
-        __symbols.push((__start, __Symbol::Variant2(__nt), __end));
+        let __nt = super::__action52::<>(src, input, &__start, &__end);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
         (0, 4)
     }
     pub(crate) fn __reduce7<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // (<Prefix>)* = (<Prefix>)+ => ActionFn(39);
-        let __sym0 = __pop_Variant2(__symbols);
+        // (<Prefix>)* = (<Prefix>)+ => ActionFn(53);
+        let __sym0 = __pop_Variant4(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action39::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant2(__nt), __end));
+        let __nt = super::__action53::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
         (1, 4)
     }
     pub(crate) fn __reduce8<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // (<Prefix>)+ = Prefix => ActionFn(51);
-        let __sym0 = __pop_Variant1(__symbols);
+        // (<Prefix>)+ = Prefix => ActionFn(67);
+        let __sym0 = __pop_Variant3(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action51::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant2(__nt), __end));
+        let __nt = super::__action67::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
         (1, 5)
     }
     pub(crate) fn __reduce9<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // (<Prefix>)+ = (<Prefix>)+, Prefix => ActionFn(52);
+        // (<Prefix>)+ = (<Prefix>)+, Prefix => ActionFn(68);
         assert!(__symbols.len() >= 2);
-        let __sym1 = __pop_Variant1(__symbols);
-        let __sym0 = __pop_Variant2(__symbols);
+        let __sym1 = __pop_Variant3(__symbols);
+        let __sym0 = __pop_Variant4(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym1.2.clone();
-        let __nt = super::__action52::<>(input, __sym0, __sym1);
-        __symbols.push((__start, __Symbol::Variant2(__nt), __end));
+        let __nt = super::__action68::<>(src, input, __sym0, __sym1);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
         (2, 5)
     }
     pub(crate) fn __reduce10<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // (Expr ",") = Expr, "," => ActionFn(34);
+        // (Expr ",") = Expr, "," => ActionFn(48);
         assert!(__symbols.len() >= 2);
         let __sym1 = __pop_Variant0(__symbols);
-        let __sym0 = __pop_Variant5(__symbols);
+        let __sym0 = __pop_Variant8(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym1.2.clone();
-        let __nt = super::__action34::<>(input, __sym0, __sym1);
-        __symbols.push((__start, __Symbol::Variant3(__nt), __end));
+        let __nt = super::__action48::<>(src, input, __sym0, __sym1);
+        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (2, 6)
     }
     pub(crate) fn __reduce11<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // (Expr ",")* =  => ActionFn(32);
+        // (Expr ",")* =  => ActionFn(46);
         let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2.clone())).unwrap_or_default();
         let __end = __start.clone();
-        let __nt = super::__action32::<>(input, &__start, &__end);
-        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        let __nt = super::__action46::<>(src, input, &__start, &__end);
+        __symbols.push((__start, __Symbol::Variant6(__nt), __end));
         (0, 7)
     }
     pub(crate) fn __reduce12<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // (Expr ",")* = (Expr ",")+ => ActionFn(33);
-        let __sym0 = __pop_Variant4(__symbols);
+        // (Expr ",")* = (Expr ",")+ => ActionFn(47);
+        let __sym0 = __pop_Variant6(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action33::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        let __nt = super::__action47::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant6(__nt), __end));
         (1, 7)
     }
     pub(crate) fn __reduce13<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // (Expr ",")+ = Expr, "," => ActionFn(55);
+        // (Expr ",")+ = Expr, "," => ActionFn(71);
         assert!(__symbols.len() >= 2);
         let __sym1 = __pop_Variant0(__symbols);
-        let __sym0 = __pop_Variant5(__symbols);
+        let __sym0 = __pop_Variant8(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym1.2.clone();
-        let __nt = super::__action55::<>(input, __sym0, __sym1);
-        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        let __nt = super::__action71::<>(src, input, __sym0, __sym1);
+        __symbols.push((__start, __Symbol::Variant6(__nt), __end));
         (2, 8)
     }
     pub(crate) fn __reduce14<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // (Expr ",")+ = (Expr ",")+, Expr, "," => ActionFn(56);
+        // (Expr ",")+ = (Expr ",")+, Expr, "," => ActionFn(72);
         assert!(__symbols.len() >= 3);
         let __sym2 = __pop_Variant0(__symbols);
-        let __sym1 = __pop_Variant5(__symbols);
-        let __sym0 = __pop_Variant4(__symbols);
+        let __sym1 = __pop_Variant8(__symbols);
+        let __sym0 = __pop_Variant6(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym2.2.clone();
-        let __nt = super::__action56::<>(input, __sym0, __sym1, __sym2);
-        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        let __nt = super::__action72::<>(src, input, __sym0, __sym1, __sym2);
+        __symbols.push((__start, __Symbol::Variant6(__nt), __end));
         (3, 8)
     }
     pub(crate) fn __reduce15<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // BinOp1 = ":" => ActionFn(15);
-        let __sym0 = __pop_Variant0(__symbols);
-        let __start = __sym0.0.clone();
-        let __end = __sym0.2.clone();
-        let __nt = super::__action15::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 9)
+        // @L =  => ActionFn(56);
+        let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2.clone())).unwrap_or_default();
+        let __end = __start.clone();
+        let __nt = super::__action56::<>(src, input, &__start, &__end);
+        __symbols.push((__start, __Symbol::Variant7(__nt), __end));
+        (0, 9)
     }
     pub(crate) fn __reduce16<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // BinOp1 = ".." => ActionFn(16);
-        let __sym0 = __pop_Variant0(__symbols);
-        let __start = __sym0.0.clone();
-        let __end = __sym0.2.clone();
-        let __nt = super::__action16::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 9)
+        // @R =  => ActionFn(55);
+        let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2.clone())).unwrap_or_default();
+        let __end = __start.clone();
+        let __nt = super::__action55::<>(src, input, &__start, &__end);
+        __symbols.push((__start, __Symbol::Variant7(__nt), __end));
+        (0, 10)
     }
     pub(crate) fn __reduce17<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // BinOp2 = "&" => ActionFn(9);
+        // BinOp1 = ":" => ActionFn(24);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action9::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 10)
+        let __nt = super::__action24::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant3(__nt), __end));
+        (1, 11)
     }
     pub(crate) fn __reduce18<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // BinOp2 = "and" => ActionFn(10);
+        // BinOp2 = "&" => ActionFn(16);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action10::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 10)
+        let __nt = super::__action16::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant3(__nt), __end));
+        (1, 12)
     }
     pub(crate) fn __reduce19<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // BinOp2 = "-" => ActionFn(11);
+        // BinOp2 = "and" => ActionFn(17);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action11::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 10)
+        let __nt = super::__action17::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant3(__nt), __end));
+        (1, 12)
     }
     pub(crate) fn __reduce20<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // BinOp2 = "%" => ActionFn(12);
+        // BinOp2 = "-" => ActionFn(18);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action12::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 10)
+        let __nt = super::__action18::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant3(__nt), __end));
+        (1, 12)
     }
     pub(crate) fn __reduce21<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // BinOp3 = "|" => ActionFn(4);
+        // BinOp2 = "%" => ActionFn(19);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action4::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 11)
+        let __nt = super::__action19::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant3(__nt), __end));
+        (1, 12)
     }
     pub(crate) fn __reduce22<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // BinOp3 = "+" => ActionFn(5);
+        // BinOp3 = "|" => ActionFn(11);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action5::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 11)
+        let __nt = super::__action11::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant3(__nt), __end));
+        (1, 13)
     }
     pub(crate) fn __reduce23<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // BinOp3 = "or" => ActionFn(6);
+        // BinOp3 = "+" => ActionFn(12);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action6::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 11)
+        let __nt = super::__action12::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant3(__nt), __end));
+        (1, 13)
     }
     pub(crate) fn __reduce24<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr = Expr4 => ActionFn(1);
-        let __sym0 = __pop_Variant5(__symbols);
-        let __start = __sym0.0.clone();
-        let __end = __sym0.2.clone();
-        let __nt = super::__action1::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
-        (1, 12)
-    }
-    pub(crate) fn __reduce25<
-        'input,
-    >(
-        input: &'input str,
-        __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
-    ) -> (usize, usize)
-    {
-        // Expr0 = Symbol2 => ActionFn(19);
-        let __sym0 = __pop_Variant7(__symbols);
+        // BinOp3 = "or" => ActionFn(13);
+        let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action19::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
+        let __nt = super::__action13::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant3(__nt), __end));
         (1, 13)
     }
     pub(crate) fn __reduce26<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr0 = Symbol1, "(", Expr, ")" => ActionFn(59);
-        assert!(__symbols.len() >= 4);
-        let __sym3 = __pop_Variant0(__symbols);
-        let __sym2 = __pop_Variant5(__symbols);
-        let __sym1 = __pop_Variant0(__symbols);
-        let __sym0 = __pop_Variant7(__symbols);
+        // Expr = Expr4 => ActionFn(1);
+        let __sym0 = __pop_Variant8(__symbols);
         let __start = __sym0.0.clone();
-        let __end = __sym3.2.clone();
-        let __nt = super::__action59::<>(input, __sym0, __sym1, __sym2, __sym3);
-        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
-        (4, 13)
+        let __end = __sym0.2.clone();
+        let __nt = super::__action1::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (1, 15)
     }
     pub(crate) fn __reduce27<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr0 = Symbol1, "(", ")" => ActionFn(60);
-        assert!(__symbols.len() >= 3);
-        let __sym2 = __pop_Variant0(__symbols);
-        let __sym1 = __pop_Variant0(__symbols);
-        let __sym0 = __pop_Variant7(__symbols);
+        // Expr = Expr4, RevTail => ActionFn(2);
+        assert!(__symbols.len() >= 2);
+        let __sym1 = __pop_Variant10(__symbols);
+        let __sym0 = __pop_Variant8(__symbols);
         let __start = __sym0.0.clone();
-        let __end = __sym2.2.clone();
-        let __nt = super::__action60::<>(input, __sym0, __sym1, __sym2);
-        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
-        (3, 13)
+        let __end = __sym1.2.clone();
+        let __nt = super::__action2::<>(src, input, __sym0, __sym1);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (2, 15)
     }
     pub(crate) fn __reduce28<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr0 = Symbol1, "(", (Expr ",")+, Expr, ")" => ActionFn(61);
-        assert!(__symbols.len() >= 5);
-        let __sym4 = __pop_Variant0(__symbols);
-        let __sym3 = __pop_Variant5(__symbols);
-        let __sym2 = __pop_Variant4(__symbols);
-        let __sym1 = __pop_Variant0(__symbols);
-        let __sym0 = __pop_Variant7(__symbols);
+        // Expr = RevTail => ActionFn(3);
+        let __sym0 = __pop_Variant10(__symbols);
         let __start = __sym0.0.clone();
-        let __end = __sym4.2.clone();
-        let __nt = super::__action61::<>(input, __sym0, __sym1, __sym2, __sym3, __sym4);
-        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
-        (5, 13)
+        let __end = __sym0.2.clone();
+        let __nt = super::__action3::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (1, 15)
     }
     pub(crate) fn __reduce29<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr0 = Symbol1, "(", (Expr ",")+, ")" => ActionFn(62);
-        assert!(__symbols.len() >= 4);
-        let __sym3 = __pop_Variant0(__symbols);
-        let __sym2 = __pop_Variant4(__symbols);
-        let __sym1 = __pop_Variant0(__symbols);
-        let __sym0 = __pop_Variant7(__symbols);
+        // Expr0 = Symbol2 => ActionFn(27);
+        let __sym0 = __pop_Variant11(__symbols);
         let __start = __sym0.0.clone();
-        let __end = __sym3.2.clone();
-        let __nt = super::__action62::<>(input, __sym0, __sym1, __sym2, __sym3);
-        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
-        (4, 13)
+        let __end = __sym0.2.clone();
+        let __nt = super::__action27::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (1, 16)
     }
     pub(crate) fn __reduce30<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr0 = "(", Expr, ")" => ActionFn(21);
-        assert!(__symbols.len() >= 3);
-        let __sym2 = __pop_Variant0(__symbols);
-        let __sym1 = __pop_Variant5(__symbols);
+        // Expr0 = r#"/([^/\\x5c]|\\x5c.)*/"# => ActionFn(28);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
-        let __end = __sym2.2.clone();
-        let __nt = super::__action21::<>(input, __sym0, __sym1, __sym2);
-        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
-        (3, 13)
+        let __end = __sym0.2.clone();
+        let __nt = super::__action28::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (1, 16)
     }
     pub(crate) fn __reduce31<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr1 = Expr0 => ActionFn(49);
-        let __sym0 = __pop_Variant5(__symbols);
+        // Expr0 = Symbol1, "(", Expr, ")" => ActionFn(79);
+        assert!(__symbols.len() >= 4);
+        let __sym3 = __pop_Variant0(__symbols);
+        let __sym2 = __pop_Variant8(__symbols);
+        let __sym1 = __pop_Variant0(__symbols);
+        let __sym0 = __pop_Variant11(__symbols);
         let __start = __sym0.0.clone();
-        let __end = __sym0.2.clone();
-        let __nt = super::__action49::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
-        (1, 14)
+        let __end = __sym3.2.clone();
+        let __nt = super::__action79::<>(src, input, __sym0, __sym1, __sym2, __sym3);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (4, 16)
     }
     pub(crate) fn __reduce32<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr1 = Expr0, (<Postfix>)+ => ActionFn(50);
-        assert!(__symbols.len() >= 2);
-        let __sym1 = __pop_Variant2(__symbols);
-        let __sym0 = __pop_Variant5(__symbols);
+        // Expr0 = Symbol1, "(", ")" => ActionFn(80);
+        assert!(__symbols.len() >= 3);
+        let __sym2 = __pop_Variant0(__symbols);
+        let __sym1 = __pop_Variant0(__symbols);
+        let __sym0 = __pop_Variant11(__symbols);
         let __start = __sym0.0.clone();
-        let __end = __sym1.2.clone();
-        let __nt = super::__action50::<>(input, __sym0, __sym1);
-        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
-        (2, 14)
+        let __end = __sym2.2.clone();
+        let __nt = super::__action80::<>(src, input, __sym0, __sym1, __sym2);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (3, 16)
     }
     pub(crate) fn __reduce33<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr15 = Expr1 => ActionFn(53);
-        let __sym0 = __pop_Variant5(__symbols);
+        // Expr0 = Symbol1, "(", (Expr ",")+, Expr, ")" => ActionFn(81);
+        assert!(__symbols.len() >= 5);
+        let __sym4 = __pop_Variant0(__symbols);
+        let __sym3 = __pop_Variant8(__symbols);
+        let __sym2 = __pop_Variant6(__symbols);
+        let __sym1 = __pop_Variant0(__symbols);
+        let __sym0 = __pop_Variant11(__symbols);
         let __start = __sym0.0.clone();
-        let __end = __sym0.2.clone();
-        let __nt = super::__action53::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
-        (1, 15)
+        let __end = __sym4.2.clone();
+        let __nt = super::__action81::<>(src, input, __sym0, __sym1, __sym2, __sym3, __sym4);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (5, 16)
     }
     pub(crate) fn __reduce34<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr15 = (<Prefix>)+, Expr1 => ActionFn(54);
-        assert!(__symbols.len() >= 2);
-        let __sym1 = __pop_Variant5(__symbols);
-        let __sym0 = __pop_Variant2(__symbols);
+        // Expr0 = Symbol1, "(", (Expr ",")+, ")" => ActionFn(82);
+        assert!(__symbols.len() >= 4);
+        let __sym3 = __pop_Variant0(__symbols);
+        let __sym2 = __pop_Variant6(__symbols);
+        let __sym1 = __pop_Variant0(__symbols);
+        let __sym0 = __pop_Variant11(__symbols);
         let __start = __sym0.0.clone();
-        let __end = __sym1.2.clone();
-        let __nt = super::__action54::<>(input, __sym0, __sym1);
-        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
-        (2, 15)
+        let __end = __sym3.2.clone();
+        let __nt = super::__action82::<>(src, input, __sym0, __sym1, __sym2, __sym3);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (4, 16)
     }
     pub(crate) fn __reduce35<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr2 = Expr2, BinOp1, Expr15 => ActionFn(13);
+        // Expr0 = "(", Expr, ")" => ActionFn(30);
         assert!(__symbols.len() >= 3);
-        let __sym2 = __pop_Variant5(__symbols);
-        let __sym1 = __pop_Variant1(__symbols);
-        let __sym0 = __pop_Variant5(__symbols);
+        let __sym2 = __pop_Variant0(__symbols);
+        let __sym1 = __pop_Variant8(__symbols);
+        let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym2.2.clone();
-        let __nt = super::__action13::<>(input, __sym0, __sym1, __sym2);
-        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
+        let __nt = super::__action30::<>(src, input, __sym0, __sym1, __sym2);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
         (3, 16)
     }
     pub(crate) fn __reduce36<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr2 = Expr15 => ActionFn(14);
-        let __sym0 = __pop_Variant5(__symbols);
+        // Expr1 = Expr0 => ActionFn(65);
+        let __sym0 = __pop_Variant8(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action14::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
-        (1, 16)
+        let __nt = super::__action65::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (1, 17)
     }
     pub(crate) fn __reduce37<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr3 = Expr3, BinOp2, Expr2 => ActionFn(7);
-        assert!(__symbols.len() >= 3);
-        let __sym2 = __pop_Variant5(__symbols);
-        let __sym1 = __pop_Variant1(__symbols);
-        let __sym0 = __pop_Variant5(__symbols);
+        // Expr1 = Expr0, (<Postfix>)+ => ActionFn(66);
+        assert!(__symbols.len() >= 2);
+        let __sym1 = __pop_Variant2(__symbols);
+        let __sym0 = __pop_Variant8(__symbols);
         let __start = __sym0.0.clone();
-        let __end = __sym2.2.clone();
-        let __nt = super::__action7::<>(input, __sym0, __sym1, __sym2);
-        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
-        (3, 17)
+        let __end = __sym1.2.clone();
+        let __nt = super::__action66::<>(src, input, __sym0, __sym1);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (2, 17)
     }
     pub(crate) fn __reduce38<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr3 = Expr2 => ActionFn(8);
-        let __sym0 = __pop_Variant5(__symbols);
+        // Expr15 = Expr1 => ActionFn(69);
+        let __sym0 = __pop_Variant8(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action8::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
-        (1, 17)
+        let __nt = super::__action69::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (1, 18)
     }
     pub(crate) fn __reduce39<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr4 = Expr4, BinOp3, Expr3 => ActionFn(2);
-        assert!(__symbols.len() >= 3);
-        let __sym2 = __pop_Variant5(__symbols);
-        let __sym1 = __pop_Variant1(__symbols);
-        let __sym0 = __pop_Variant5(__symbols);
+        // Expr15 = (<Prefix>)+, Expr1 => ActionFn(70);
+        assert!(__symbols.len() >= 2);
+        let __sym1 = __pop_Variant8(__symbols);
+        let __sym0 = __pop_Variant4(__symbols);
         let __start = __sym0.0.clone();
-        let __end = __sym2.2.clone();
-        let __nt = super::__action2::<>(input, __sym0, __sym1, __sym2);
-        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
-        (3, 18)
+        let __end = __sym1.2.clone();
+        let __nt = super::__action70::<>(src, input, __sym0, __sym1);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (2, 18)
     }
     pub(crate) fn __reduce40<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr4 = Expr3 => ActionFn(3);
-        let __sym0 = __pop_Variant5(__symbols);
+        // Expr2 = Expr2, BinOp1, Expr15 => ActionFn(20);
+        assert!(__symbols.len() >= 3);
+        let __sym2 = __pop_Variant8(__symbols);
+        let __sym1 = __pop_Variant3(__symbols);
+        let __sym0 = __pop_Variant8(__symbols);
         let __start = __sym0.0.clone();
-        let __end = __sym0.2.clone();
-        let __nt = super::__action3::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
-        (1, 18)
+        let __end = __sym2.2.clone();
+        let __nt = super::__action20::<>(src, input, __sym0, __sym1, __sym2);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (3, 19)
     }
     pub(crate) fn __reduce41<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr? = Expr => ActionFn(30);
-        let __sym0 = __pop_Variant5(__symbols);
+        // Expr2 = Expr2, "..", Expr15 => ActionFn(21);
+        assert!(__symbols.len() >= 3);
+        let __sym2 = __pop_Variant8(__symbols);
+        let __sym1 = __pop_Variant0(__symbols);
+        let __sym0 = __pop_Variant8(__symbols);
+        let __start = __sym0.0.clone();
+        let __end = __sym2.2.clone();
+        let __nt = super::__action21::<>(src, input, __sym0, __sym1, __sym2);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (3, 19)
+    }
+    pub(crate) fn __reduce42<
+        'input,
+    >(
+        src: &str,
+        input: &'input str,
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Expr2 = Expr2, "...", Expr15 => ActionFn(22);
+        assert!(__symbols.len() >= 3);
+        let __sym2 = __pop_Variant8(__symbols);
+        let __sym1 = __pop_Variant0(__symbols);
+        let __sym0 = __pop_Variant8(__symbols);
+        let __start = __sym0.0.clone();
+        let __end = __sym2.2.clone();
+        let __nt = super::__action22::<>(src, input, __sym0, __sym1, __sym2);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (3, 19)
+    }
+    pub(crate) fn __reduce43<
+        'input,
+    >(
+        src: &str,
+        input: &'input str,
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Expr2 = Expr15 => ActionFn(23);
+        let __sym0 = __pop_Variant8(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action30::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant6(__nt), __end));
+        let __nt = super::__action23::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
         (1, 19)
     }
-    pub(crate) fn __reduce42<
+    pub(crate) fn __reduce44<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr? =  => ActionFn(31);
+        // Expr3 = Expr3, BinOp2, Expr2 => ActionFn(14);
+        assert!(__symbols.len() >= 3);
+        let __sym2 = __pop_Variant8(__symbols);
+        let __sym1 = __pop_Variant3(__symbols);
+        let __sym0 = __pop_Variant8(__symbols);
+        let __start = __sym0.0.clone();
+        let __end = __sym2.2.clone();
+        let __nt = super::__action14::<>(src, input, __sym0, __sym1, __sym2);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (3, 20)
+    }
+    pub(crate) fn __reduce45<
+        'input,
+    >(
+        src: &str,
+        input: &'input str,
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Expr3 = Expr2 => ActionFn(15);
+        let __sym0 = __pop_Variant8(__symbols);
+        let __start = __sym0.0.clone();
+        let __end = __sym0.2.clone();
+        let __nt = super::__action15::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (1, 20)
+    }
+    pub(crate) fn __reduce46<
+        'input,
+    >(
+        src: &str,
+        input: &'input str,
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Expr4 = Expr4, BinOp3, Expr3 => ActionFn(9);
+        assert!(__symbols.len() >= 3);
+        let __sym2 = __pop_Variant8(__symbols);
+        let __sym1 = __pop_Variant3(__symbols);
+        let __sym0 = __pop_Variant8(__symbols);
+        let __start = __sym0.0.clone();
+        let __end = __sym2.2.clone();
+        let __nt = super::__action9::<>(src, input, __sym0, __sym1, __sym2);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (3, 21)
+    }
+    pub(crate) fn __reduce47<
+        'input,
+    >(
+        src: &str,
+        input: &'input str,
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Expr4 = Expr3 => ActionFn(10);
+        let __sym0 = __pop_Variant8(__symbols);
+        let __start = __sym0.0.clone();
+        let __end = __sym0.2.clone();
+        let __nt = super::__action10::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (1, 21)
+    }
+    pub(crate) fn __reduce48<
+        'input,
+    >(
+        src: &str,
+        input: &'input str,
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Expr? = Expr => ActionFn(44);
+        let __sym0 = __pop_Variant8(__symbols);
+        let __start = __sym0.0.clone();
+        let __end = __sym0.2.clone();
+        let __nt = super::__action44::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant9(__nt), __end));
+        (1, 22)
+    }
+    pub(crate) fn __reduce49<
+        'input,
+    >(
+        src: &str,
+        input: &'input str,
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Expr? =  => ActionFn(45);
         let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2.clone())).unwrap_or_default();
         let __end = __start.clone();
-        let __nt = super::__action31::<>(input, &__start, &__end);
-        __symbols.push((__start, __Symbol::Variant6(__nt), __end));
-        (0, 19)
+        let __nt = super::__action45::<>(src, input, &__start, &__end);
+        __symbols.push((__start, __Symbol::Variant9(__nt), __end));
+        (0, 22)
     }
-    pub(crate) fn __reduce43<
+    pub(crate) fn __reduce50<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Postfix = "::" => ActionFn(25);
+        // Postfix = "::" => ActionFn(34);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action25::<>(input, __sym0);
+        let __nt = super::__action34::<>(src, input, __sym0);
         __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 20)
+        (1, 23)
     }
-    pub(crate) fn __reduce44<
+    pub(crate) fn __reduce51<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Postfix = "^" => ActionFn(26);
+        // Postfix = "^" => ActionFn(35);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action26::<>(input, __sym0);
+        let __nt = super::__action35::<>(src, input, __sym0);
         __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 20)
+        (1, 23)
     }
-    pub(crate) fn __reduce45<
+    pub(crate) fn __reduce53<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Prefix = "!" => ActionFn(22);
+        // Postfix = r#"~[0-9]*"# => ActionFn(37);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action22::<>(input, __sym0);
+        let __nt = super::__action37::<>(src, input, __sym0);
         __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 21)
+        (1, 23)
     }
-    pub(crate) fn __reduce46<
+    pub(crate) fn __reduce54<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Prefix = "not " => ActionFn(23);
+        // Prefix = "!" => ActionFn(31);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action23::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 21)
+        let __nt = super::__action31::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant3(__nt), __end));
+        (1, 24)
     }
-    pub(crate) fn __reduce47<
+    pub(crate) fn __reduce55<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Prefix = "::" => ActionFn(24);
+        // Prefix = "not " => ActionFn(32);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action24::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 21)
+        let __nt = super::__action32::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant3(__nt), __end));
+        (1, 24)
     }
-    pub(crate) fn __reduce48<
+    pub(crate) fn __reduce56<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Symbol1 = r#"[a-zA-Z0-9/_$@.]+"# => ActionFn(29);
+        // Prefix = "::" => ActionFn(33);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action29::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant7(__nt), __end));
-        (1, 22)
+        let __nt = super::__action33::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant3(__nt), __end));
+        (1, 24)
     }
-    pub(crate) fn __reduce49<
+    pub(crate) fn __reduce57<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Symbol2 = Symbol1 => ActionFn(27);
-        let __sym0 = __pop_Variant7(__symbols);
+        // RevTail = Exclude => ActionFn(4);
+        let __sym0 = __pop_Variant8(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action27::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant7(__nt), __end));
-        (1, 23)
+        let __nt = super::__action4::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant10(__nt), __end));
+        (1, 25)
     }
-    pub(crate) fn __reduce50<
+    pub(crate) fn __reduce58<
+        'input,
+    >(
+        src: &str,
+        input: &'input str,
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // RevTail = Exclude, RevTail => ActionFn(5);
+        assert!(__symbols.len() >= 2);
+        let __sym1 = __pop_Variant10(__symbols);
+        let __sym0 = __pop_Variant8(__symbols);
+        let __start = __sym0.0.clone();
+        let __end = __sym1.2.clone();
+        let __nt = super::__action5::<>(src, input, __sym0, __sym1);
+        __symbols.push((__start, __Symbol::Variant10(__nt), __end));
+        (2, 25)
+    }
+    pub(crate) fn __reduce59<
+        'input,
+    >(
+        src: &str,
+        input: &'input str,
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // RevTail = Exclude, Expr4 => ActionFn(6);
+        assert!(__symbols.len() >= 2);
+        let __sym1 = __pop_Variant8(__symbols);
+        let __sym0 = __pop_Variant8(__symbols);
+        let __start = __sym0.0.clone();
+        let __end = __sym1.2.clone();
+        let __nt = super::__action6::<>(src, input, __sym0, __sym1);
+        __symbols.push((__start, __Symbol::Variant10(__nt), __end));
+        (2, 25)
+    }
+    pub(crate) fn __reduce60<
+        'input,
+    >(
+        src: &str,
+        input: &'input str,
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // RevTail = Exclude, Expr4, RevTail => ActionFn(7);
+        assert!(__symbols.len() >= 3);
+        let __sym2 = __pop_Variant10(__symbols);
+        let __sym1 = __pop_Variant8(__symbols);
+        let __sym0 = __pop_Variant8(__symbols);
+        let __start = __sym0.0.clone();
+        let __end = __sym2.2.clone();
+        let __nt = super::__action7::<>(src, input, __sym0, __sym1, __sym2);
+        __symbols.push((__start, __Symbol::Variant10(__nt), __end));
+        (3, 25)
+    }
+    pub(crate) fn __reduce61<
         'input,
     >(
+        src: &str,
         input: &'input str,
         __lookahead_start: Option<&usize>,
-        __symbols: &mut ::std::vec::Vec<(usize,__Symbol<'input>,usize)>,
-        _: ::std::marker::PhantomData<(&'input ())>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Symbol2 = r#"\\x22([^\\x22\\x5c]|\\x5c.)*\\x22"# => ActionFn(28);
+        // Symbol1 = r#"[a-zA-Z0-9/_$@]+(\\.[a-zA-Z0-9/_$@]+)*"# => ActionFn(42);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0.clone();
         let __end = __sym0.2.clone();
-        let __nt = super::__action28::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant7(__nt), __end));
-        (1, 23)
+        let __nt = super::__action42::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant11(__nt), __end));
+        (1, 26)
+    }
+    pub(crate) fn __reduce62<
+        'input,
+    >(
+        src: &str,
+        input: &'input str,
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Symbol1 = "." => ActionFn(43);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0.clone();
+        let __end = __sym0.2.clone();
+        let __nt = super::__action43::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant11(__nt), __end));
+        (1, 26)
+    }
+    pub(crate) fn __reduce63<
+        'input,
+    >(
+        src: &str,
+        input: &'input str,
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Symbol2 = Symbol1 => ActionFn(38);
+        let __sym0 = __pop_Variant11(__symbols);
+        let __start = __sym0.0.clone();
+        let __end = __sym0.2.clone();
+        let __nt = super::__action38::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant11(__nt), __end));
+        (1, 27)
+    }
+    pub(crate) fn __reduce64<
+        'input,
+    >(
+        src: &str,
+        input: &'input str,
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Symbol2 = r#"@\\{-[0-9]+\\}"# => ActionFn(39);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0.clone();
+        let __end = __sym0.2.clone();
+        let __nt = super::__action39::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant11(__nt), __end));
+        (1, 27)
+    }
+    pub(crate) fn __reduce65<
+        'input,
+    >(
+        src: &str,
+        input: &'input str,
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Symbol2 = r#"([a-zA-Z0-9/_$@]+(\\.[a-zA-Z0-9/_$@]+)*)?@\\{(upstream|u|push)\\}"# => ActionFn(40);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0.clone();
+        let __end = __sym0.2.clone();
+        let __nt = super::__action40::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant11(__nt), __end));
+        (1, 27)
+    }
+    pub(crate) fn __reduce66<
+        'input,
+    >(
+        src: &str,
+        input: &'input str,
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Symbol2 = r#"\\x22([^\\x22\\x5c]|\\x5c.)*\\x22"# => ActionFn(41);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0.clone();
+        let __end = __sym0.2.clone();
+        let __nt = super::__action41::<>(src, input, __sym0);
+        __symbols.push((__start, __Symbol::Variant11(__nt), __end));
+        (1, 27)
     }
 }
 pub use self::__parse__Expr::ExprParser;
@@ -1678,14 +2215,23 @@ pub use self::__parse__Expr::ExprParser;
 mod __intern_token {
     #![allow(unused_imports)]
     use crate::ast::Expr;
+    use crate::ast::rev_list;
+    use crate::parser::GrammarError;
+    use lalrpop_util::ParseError;
     #[allow(unused_extern_crates)]
     extern crate lalrpop_util as __lalrpop_util;
     #[allow(unused_imports)]
     use self::__lalrpop_util::state_machine as __state_machine;
+    extern crate core;
+    extern crate alloc;
     pub fn new_builder() -> __lalrpop_util::lexer::MatcherBuilder {
         let __strs: &[(&str, bool)] = &[
-            ("^([\\$\\.-9@-Z_a-z]+)", false),
-            ("^(\"([\u{0}-!\\#-\\[\\]-\u{10ffff}]|\\\\[\u{0}-\t\u{b}-\u{10ffff}])*\")", false),
+            ("^(([\\$/-9@-Z_a-z]+(\\.[\\$/-9@-Z_a-z]+)*)?@\\{(upstream|u|push)\\})", false),
+            ("^(@\\{\\-[0-9]+\\})", false),
+            ("^([\\$/-9@-Z_a-z]+(\\.[\\$/-9@-Z_a-z]+)*)", false),
+            ("^(\\^[\\$/-9@-Z_a-z]+(\\.[\\$/-9@-Z_a-z]+)*)", false),
+            ("^(\"([\0-!\\#-\\[\\]-\u{10ffff}]|\\\\[\0-\t\u{b}-\u{10ffff}])*\")", false),
+            ("^(\\~[0-9]*)", false),
             ("^(!)", false),
             ("^(%)", false),
             ("^(\\&)", false),
@@ -1694,7 +2240,9 @@ mod __intern_token {
             ("^(\\+)", false),
             ("^(,)", false),
             ("^(\\-)", false),
+            ("^(\\.)", false),
             ("^(\\.\\.)", false),
+            ("^(\\.\\.\\.)", false),
             ("^(:)", false),
             ("^(::)", false),
             ("^(\\^)", false),
@@ -1702,17 +2250,20 @@ mod __intern_token {
             ("^(not )", false),
             ("^(or)", false),
             ("^(\\|)", false),
+            ("^(/([\0-\\.0-\\[\\]-\u{10ffff}]|\\\\[\0-\t\u{b}-\u{10ffff}])*/)", false),
+            ("^(\\^[0-9]+)", false),
             (r"^(\s*)", true),
         ];
         __lalrpop_util::lexer::MatcherBuilder::new(__strs.iter().copied()).unwrap()
     }
 }
-pub use self::__lalrpop_util::lexer::Token;
+pub(crate) use self::__lalrpop_util::lexer::Token;
 
 #[allow(unused_variables)]
 fn __action0<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, Expr, usize),
 ) -> Expr
@@ -1724,6 +2275,7 @@ fn __action0<
 fn __action1<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, Expr, usize),
 ) -> Expr
@@ -1735,6 +2287,117 @@ fn __action1<
 fn __action2<
     'input,
 >(
+    src: &str,
+    input: &'input str,
+    (_, a, _): (usize, Expr, usize),
+    (_, t, _): (usize, Vec<(bool, Expr)>, usize),
+) -> Expr
+{
+    {
+        let mut t = t;
+        t.insert(0, (true, a));
+        rev_list(t)
+    }
+}
+
+#[allow(unused_variables)]
+fn __action3<
+    'input,
+>(
+    src: &str,
+    input: &'input str,
+    (_, __0, _): (usize, Vec<(bool, Expr)>, usize),
+) -> Expr
+{
+    rev_list(__0)
+}
+
+#[allow(unused_variables)]
+fn __action4<
+    'input,
+>(
+    src: &str,
+    input: &'input str,
+    (_, n, _): (usize, Expr, usize),
+) -> Vec<(bool, Expr)>
+{
+    vec![(false, n)]
+}
+
+#[allow(unused_variables)]
+fn __action5<
+    'input,
+>(
+    src: &str,
+    input: &'input str,
+    (_, n, _): (usize, Expr, usize),
+    (_, t, _): (usize, Vec<(bool, Expr)>, usize),
+) -> Vec<(bool, Expr)>
+{
+    {
+        let mut t = t;
+        t.insert(0, (false, n));
+        t
+    }
+}
+
+#[allow(unused_variables)]
+fn __action6<
+    'input,
+>(
+    src: &str,
+    input: &'input str,
+    (_, n, _): (usize, Expr, usize),
+    (_, e, _): (usize, Expr, usize),
+) -> Vec<(bool, Expr)>
+{
+    vec![(false, n), (true, e)]
+}
+
+#[allow(unused_variables)]
+fn __action7<
+    'input,
+>(
+    src: &str,
+    input: &'input str,
+    (_, n, _): (usize, Expr, usize),
+    (_, e, _): (usize, Expr, usize),
+    (_, t, _): (usize, Vec<(bool, Expr)>, usize),
+) -> Vec<(bool, Expr)>
+{
+    {
+        let mut t = t;
+        t.insert(0, (true, e));
+        t.insert(0, (false, n));
+        t
+    }
+}
+
+#[allow(unused_variables)]
+fn __action8<
+    'input,
+>(
+    src: &str,
+    input: &'input str,
+    (_, l, _): (usize, usize, usize),
+    (_, n, _): (usize, &'input str, usize),
+    (_, r, _): (usize, usize, usize),
+) -> Result<Expr,__lalrpop_util::ParseError<usize,Token<'input>,GrammarError>>
+{
+    {
+        let separated = |c: char| c.is_whitespace() || c == '(' || c == ',';
+        if !src[..l].is_empty() && !src[..l].ends_with(separated) {
+            return Err(ParseError::User { error: GrammarError::AdjacentExclusion(l, r) });
+        }
+        Ok(Expr::Name(n[1..].to_string()))
+    }
+}
+
+#[allow(unused_variables)]
+fn __action9<
+    'input,
+>(
+    src: &str,
     input: &'input str,
     (_, a, _): (usize, Expr, usize),
     (_, op, _): (usize, &'static str, usize),
@@ -1745,9 +2408,10 @@ fn __action2<
 }
 
 #[allow(unused_variables)]
-fn __action3<
+fn __action10<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, Expr, usize),
 ) -> Expr
@@ -1756,9 +2420,10 @@ fn __action3<
 }
 
 #[allow(unused_variables)]
-fn __action4<
+fn __action11<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, &'input str, usize),
 ) -> &'static str
@@ -1767,9 +2432,10 @@ fn __action4<
 }
 
 #[allow(unused_variables)]
-fn __action5<
+fn __action12<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, &'input str, usize),
 ) -> &'static str
@@ -1778,9 +2444,10 @@ fn __action5<
 }
 
 #[allow(unused_variables)]
-fn __action6<
+fn __action13<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, &'input str, usize),
 ) -> &'static str
@@ -1789,9 +2456,10 @@ fn __action6<
 }
 
 #[allow(unused_variables)]
-fn __action7<
+fn __action14<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, a, _): (usize, Expr, usize),
     (_, op, _): (usize, &'static str, usize),
@@ -1802,9 +2470,10 @@ fn __action7<
 }
 
 #[allow(unused_variables)]
-fn __action8<
+fn __action15<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, Expr, usize),
 ) -> Expr
@@ -1813,9 +2482,10 @@ fn __action8<
 }
 
 #[allow(unused_variables)]
-fn __action9<
+fn __action16<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, &'input str, usize),
 ) -> &'static str
@@ -1824,9 +2494,10 @@ fn __action9<
 }
 
 #[allow(unused_variables)]
-fn __action10<
+fn __action17<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, &'input str, usize),
 ) -> &'static str
@@ -1835,9 +2506,10 @@ fn __action10<
 }
 
 #[allow(unused_variables)]
-fn __action11<
+fn __action18<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, &'input str, usize),
 ) -> &'static str
@@ -1846,9 +2518,10 @@ fn __action11<
 }
 
 #[allow(unused_variables)]
-fn __action12<
+fn __action19<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, &'input str, usize),
 ) -> &'static str
@@ -1857,9 +2530,10 @@ fn __action12<
 }
 
 #[allow(unused_variables)]
-fn __action13<
+fn __action20<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, a, _): (usize, Expr, usize),
     (_, op, _): (usize, &'static str, usize),
@@ -1870,31 +2544,54 @@ fn __action13<
 }
 
 #[allow(unused_variables)]
-fn __action14<
+fn __action21<
     'input,
 >(
+    src: &str,
     input: &'input str,
-    (_, __0, _): (usize, Expr, usize),
+    (_, a, _): (usize, Expr, usize),
+    (_, _, _): (usize, &'input str, usize),
+    (_, b, _): (usize, Expr, usize),
 ) -> Expr
 {
-    __0
+    Expr::Fn("only".into(), vec![b, a])
 }
 
 #[allow(unused_variables)]
-fn __action15<
+fn __action22<
     'input,
 >(
+    src: &str,
     input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
-) -> &'static str
+    (_, a, _): (usize, Expr, usize),
+    (_, _, _): (usize, &'input str, usize),
+    (_, b, _): (usize, Expr, usize),
+) -> Expr
 {
-    "range"
+    {
+        let left = Expr::Fn("only".into(), vec![a.clone(), b.clone()]);
+        let right = Expr::Fn("only".into(), vec![b, a]);
+        Expr::Fn("union".into(), vec![left, right])
+    }
+}
+
+#[allow(unused_variables)]
+fn __action23<
+    'input,
+>(
+    src: &str,
+    input: &'input str,
+    (_, __0, _): (usize, Expr, usize),
+) -> Expr
+{
+    __0
 }
 
 #[allow(unused_variables)]
-fn __action16<
+fn __action24<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, &'input str, usize),
 ) -> &'static str
@@ -1903,11 +2600,12 @@ fn __action16<
 }
 
 #[allow(unused_variables)]
-fn __action17<
+fn __action25<
     'input,
 >(
+    src: &str,
     input: &'input str,
-    (_, pre, _): (usize, ::std::vec::Vec<&'static str>, usize),
+    (_, pre, _): (usize, alloc::vec::Vec<&'static str>, usize),
     (_, e, _): (usize, Expr, usize),
 ) -> Expr
 {
@@ -1919,25 +2617,31 @@ fn __action17<
 }
 
 #[allow(unused_variables)]
-fn __action18<
+fn __action26<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, e, _): (usize, Expr, usize),
-    (_, post, _): (usize, ::std::vec::Vec<&'static str>, usize),
+    (_, post, _): (usize, alloc::vec::Vec<(&'static str, Option<String>)>, usize),
 ) -> Expr
 {
     {
         let mut e = e;
-        for v in post { e = Expr::Fn(v.into(), vec![e]); }
+        for (v, arg) in post {
+            let mut args = vec![e];
+            args.extend(arg.map(Expr::Name));
+            e = Expr::Fn(v.into(), args);
+        }
         e
     }
 }
 
 #[allow(unused_variables)]
-fn __action19<
+fn __action27<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, String, usize),
 ) -> Expr
@@ -1946,14 +2650,30 @@ fn __action19<
 }
 
 #[allow(unused_variables)]
-fn __action20<
+fn __action28<
+    'input,
+>(
+    src: &str,
+    input: &'input str,
+    (_, escaped, _): (usize, &'input str, usize),
+) -> Expr
+{
+    {
+        // Regex literal. "\/" is "/". Other escapes are kept for the regex.
+        Expr::Regex(escaped[1..escaped.len()-1].replace("\\/", "/"))
+    }
+}
+
+#[allow(unused_variables)]
+fn __action29<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, f, _): (usize, String, usize),
     (_, _, _): (usize, &'input str, usize),
-    (_, args, _): (usize, ::std::vec::Vec<(Expr, &'input str)>, usize),
-    (_, last, _): (usize, ::std::option::Option<Expr>, usize),
+    (_, args, _): (usize, alloc::vec::Vec<(Expr, &'input str)>, usize),
+    (_, last, _): (usize, core::option::Option<Expr>, usize),
     (_, _, _): (usize, &'input str, usize),
 ) -> Expr
 {
@@ -1966,9 +2686,10 @@ fn __action20<
 }
 
 #[allow(unused_variables)]
-fn __action21<
+fn __action30<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, _, _): (usize, &'input str, usize),
     (_, __0, _): (usize, Expr, usize),
@@ -1979,9 +2700,10 @@ fn __action21<
 }
 
 #[allow(unused_variables)]
-fn __action22<
+fn __action31<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, &'input str, usize),
 ) -> &'static str
@@ -1990,9 +2712,10 @@ fn __action22<
 }
 
 #[allow(unused_variables)]
-fn __action23<
+fn __action32<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, &'input str, usize),
 ) -> &'static str
@@ -2001,9 +2724,10 @@ fn __action23<
 }
 
 #[allow(unused_variables)]
-fn __action24<
+fn __action33<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, &'input str, usize),
 ) -> &'static str
@@ -2012,31 +2736,68 @@ fn __action24<
 }
 
 #[allow(unused_variables)]
-fn __action25<
+fn __action34<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, &'input str, usize),
-) -> &'static str
+) -> (&'static str, Option<String>)
 {
-    "descendants"
+    ("descendants", None)
 }
 
 #[allow(unused_variables)]
-fn __action26<
+fn __action35<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, &'input str, usize),
-) -> &'static str
+) -> (&'static str, Option<String>)
 {
-    "parents"
+    ("parents", None)
 }
 
 #[allow(unused_variables)]
-fn __action27<
+fn __action36<
+    'input,
+>(
+    src: &str,
+    input: &'input str,
+    (_, l, _): (usize, usize, usize),
+    (_, n, _): (usize, &'input str, usize),
+    (_, r, _): (usize, usize, usize),
+) -> Result<(&'static str, Option<String>),__lalrpop_util::ParseError<usize,Token<'input>,GrammarError>>
+{
+    {
+        if src[..l].ends_with(char::is_whitespace) {
+            return Err(ParseError::User { error: GrammarError::DigitExclusion(l, r) });
+        }
+        Ok(("nthparent", Some(n[1..].to_string())))
+    }
+}
+
+#[allow(unused_variables)]
+fn __action37<
+    'input,
+>(
+    src: &str,
+    input: &'input str,
+    (_, n, _): (usize, &'input str, usize),
+) -> (&'static str, Option<String>)
+{
+    {
+        let n = if n.len() > 1 { &n[1..] } else { "1" };
+        ("nthancestor", Some(n.to_string()))
+    }
+}
+
+#[allow(unused_variables)]
+fn __action38<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, String, usize),
 ) -> String
@@ -2045,9 +2806,34 @@ fn __action27<
 }
 
 #[allow(unused_variables)]
-fn __action28<
+fn __action39<
+    'input,
+>(
+    src: &str,
+    input: &'input str,
+    (_, __0, _): (usize, &'input str, usize),
+) -> String
+{
+    __0.to_string()
+}
+
+#[allow(unused_variables)]
+fn __action40<
+    'input,
+>(
+    src: &str,
+    input: &'input str,
+    (_, __0, _): (usize, &'input str, usize),
+) -> String
+{
+    __0.to_string()
+}
+
+#[allow(unused_variables)]
+fn __action41<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, escaped, _): (usize, &'input str, usize),
 ) -> String
@@ -2070,9 +2856,10 @@ fn __action28<
 }
 
 #[allow(unused_variables)]
-fn __action29<
+fn __action42<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, &'input str, usize),
 ) -> String
@@ -2081,55 +2868,72 @@ fn __action29<
 }
 
 #[allow(unused_variables)]
-fn __action30<
+fn __action43<
+    'input,
+>(
+    src: &str,
+    input: &'input str,
+    (_, __0, _): (usize, &'input str, usize),
+) -> String
+{
+    __0.to_string()
+}
+
+#[allow(unused_variables)]
+fn __action44<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, Expr, usize),
-) -> ::std::option::Option<Expr>
+) -> core::option::Option<Expr>
 {
     Some(__0)
 }
 
 #[allow(unused_variables)]
-fn __action31<
+fn __action45<
     'input,
 >(
+    src: &str,
     input: &'input str,
     __lookbehind: &usize,
     __lookahead: &usize,
-) -> ::std::option::Option<Expr>
+) -> core::option::Option<Expr>
 {
     None
 }
 
 #[allow(unused_variables)]
-fn __action32<
+fn __action46<
     'input,
 >(
+    src: &str,
     input: &'input str,
     __lookbehind: &usize,
     __lookahead: &usize,
-) -> ::std::vec::Vec<(Expr, &'input str)>
+) -> alloc::vec::Vec<(Expr, &'input str)>
 {
-    vec![]
+    alloc::vec![]
 }
 
 #[allow(unused_variables)]
-fn __action33<
+fn __action47<
     'input,
 >(
+    src: &str,
     input: &'input str,
-    (_, v, _): (usize, ::std::vec::Vec<(Expr, &'input str)>, usize),
-) -> ::std::vec::Vec<(Expr, &'input str)>
+    (_, v, _): (usize, alloc::vec::Vec<(Expr, &'input str)>, usize),
+) -> alloc::vec::Vec<(Expr, &'input str)>
 {
     v
 }
 
 #[allow(unused_variables)]
-fn __action34<
+fn __action48<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, Expr, usize),
     (_, __1, _): (usize, &'input str, usize),
@@ -2139,66 +2943,72 @@ fn __action34<
 }
 
 #[allow(unused_variables)]
-fn __action35<
+fn __action49<
     'input,
 >(
+    src: &str,
     input: &'input str,
     __lookbehind: &usize,
     __lookahead: &usize,
-) -> ::std::vec::Vec<&'static str>
+) -> alloc::vec::Vec<(&'static str, Option<String>)>
 {
-    vec![]
+    alloc::vec![]
 }
 
 #[allow(unused_variables)]
-fn __action36<
+fn __action50<
     'input,
 >(
+    src: &str,
     input: &'input str,
-    (_, v, _): (usize, ::std::vec::Vec<&'static str>, usize),
-) -> ::std::vec::Vec<&'static str>
+    (_, v, _): (usize, alloc::vec::Vec<(&'static str, Option<String>)>, usize),
+) -> alloc::vec::Vec<(&'static str, Option<String>)>
 {
     v
 }
 
 #[allow(unused_variables)]
-fn __action37<
+fn __action51<
     'input,
 >(
+    src: &str,
     input: &'input str,
-    (_, __0, _): (usize, &'static str, usize),
-) -> &'static str
+    (_, __0, _): (usize, (&'static str, Option<String>), usize),
+) -> (&'static str, Option<String>)
 {
     __0
 }
 
 #[allow(unused_variables)]
-fn __action38<
+fn __action52<
     'input,
 >(
+    src: &str,
     input: &'input str,
     __lookbehind: &usize,
     __lookahead: &usize,
-) -> ::std::vec::Vec<&'static str>
+) -> alloc::vec::Vec<&'static str>
 {
-    vec![]
+    alloc::vec![]
 }
 
 #[allow(unused_variables)]
-fn __action39<
+fn __action53<
     'input,
 >(
+    src: &str,
     input: &'input str,
-    (_, v, _): (usize, ::std::vec::Vec<&'static str>, usize),
-) -> ::std::vec::Vec<&'static str>
+    (_, v, _): (usize, alloc::vec::Vec<&'static str>, usize),
+) -> alloc::vec::Vec<&'static str>
 {
     v
 }
 
 #[allow(unused_variables)]
-fn __action40<
+fn __action54<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, &'static str, usize),
 ) -> &'static str
@@ -2207,112 +3017,150 @@ fn __action40<
 }
 
 #[allow(unused_variables)]
-fn __action41<
+fn __action55<
+    'input,
+>(
+    src: &str,
+    input: &'input str,
+    __lookbehind: &usize,
+    __lookahead: &usize,
+) -> usize
+{
+    __lookbehind.clone()
+}
+
+#[allow(unused_variables)]
+fn __action56<
+    'input,
+>(
+    src: &str,
+    input: &'input str,
+    __lookbehind: &usize,
+    __lookahead: &usize,
+) -> usize
+{
+    __lookahead.clone()
+}
+
+#[allow(unused_variables)]
+fn __action57<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, &'static str, usize),
-) -> ::std::vec::Vec<&'static str>
+) -> alloc::vec::Vec<&'static str>
 {
-    vec![__0]
+    alloc::vec![__0]
 }
 
 #[allow(unused_variables)]
-fn __action42<
+fn __action58<
     'input,
 >(
+    src: &str,
     input: &'input str,
-    (_, v, _): (usize, ::std::vec::Vec<&'static str>, usize),
+    (_, v, _): (usize, alloc::vec::Vec<&'static str>, usize),
     (_, e, _): (usize, &'static str, usize),
-) -> ::std::vec::Vec<&'static str>
+) -> alloc::vec::Vec<&'static str>
 {
     { let mut v = v; v.push(e); v }
 }
 
 #[allow(unused_variables)]
-fn __action43<
+fn __action59<
     'input,
 >(
+    src: &str,
     input: &'input str,
-    (_, __0, _): (usize, &'static str, usize),
-) -> ::std::vec::Vec<&'static str>
+    (_, __0, _): (usize, (&'static str, Option<String>), usize),
+) -> alloc::vec::Vec<(&'static str, Option<String>)>
 {
-    vec![__0]
+    alloc::vec![__0]
 }
 
 #[allow(unused_variables)]
-fn __action44<
+fn __action60<
     'input,
 >(
+    src: &str,
     input: &'input str,
-    (_, v, _): (usize, ::std::vec::Vec<&'static str>, usize),
-    (_, e, _): (usize, &'static str, usize),
-) -> ::std::vec::Vec<&'static str>
+    (_, v, _): (usize, alloc::vec::Vec<(&'static str, Option<String>)>, usize),
+    (_, e, _): (usize, (&'static str, Option<String>), usize),
+) -> alloc::vec::Vec<(&'static str, Option<String>)>
 {
     { let mut v = v; v.push(e); v }
 }
 
 #[allow(unused_variables)]
-fn __action45<
+fn __action61<
     'input,
 >(
+    src: &str,
     input: &'input str,
     (_, __0, _): (usize, (Expr, &'input str), usize),
-) -> ::std::vec::Vec<(Expr, &'input str)>
+) -> alloc::vec::Vec<(Expr, &'input str)>
 {
-    vec![__0]
+    alloc::vec![__0]
 }
 
 #[allow(unused_variables)]
-fn __action46<
+fn __action62<
     'input,
 >(
+    src: &str,
     input: &'input str,
-    (_, v, _): (usize, ::std::vec::Vec<(Expr, &'input str)>, usize),
+    (_, v, _): (usize, alloc::vec::Vec<(Expr, &'input str)>, usize),
     (_, e, _): (usize, (Expr, &'input str), usize),
-) -> ::std::vec::Vec<(Expr, &'input str)>
+) -> alloc::vec::Vec<(Expr, &'input str)>
 {
     { let mut v = v; v.push(e); v }
 }
 
 #[allow(unused_variables)]
-fn __action47<
+fn __action63<
     'input,
 >(
+    src: &str,
     input: &'input str,
-    __0: (usize, &'static str, usize),
-) -> ::std::vec::Vec<&'static str>
+    __0: (usize, (&'static str, Option<String>), usize),
+) -> alloc::vec::Vec<(&'static str, Option<String>)>
 {
     let __start0 = __0.0.clone();
     let __end0 = __0.2.clone();
-    let __temp0 = __action37(
+    let __temp0 = __action51(
+        src,
         input,
         __0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action43(
+    __action59(
+        src,
         input,
         __temp0,
     )
 }
 
 #[allow(unused_variables)]
-fn __action48<
+fn __action64<
     'input,
 >(
+    src: &str,
     input: &'input str,
-    __0: (usize, ::std::vec::Vec<&'static str>, usize),
-    __1: (usize, &'static str, usize),
-) -> ::std::vec::Vec<&'static str>
+    __0: (usize, alloc::vec::Vec<(&'static str, Option<String>)>, usize),
+    __1: (usize, (&'static str, Option<String>), usize),
+) -> alloc::vec::Vec<(&'static str, Option<String>)>
 {
     let __start0 = __1.0.clone();
     let __end0 = __1.2.clone();
-    let __temp0 = __action37(
+    let __temp0 = __action51(
+        src,
         input,
         __1,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action44(
+    __action60(
+        src,
         input,
         __0,
         __temp0,
@@ -2320,22 +3168,25 @@ fn __action48<
 }
 
 #[allow(unused_variables)]
-fn __action49<
+fn __action65<
     'input,
 >(
+    src: &str,
     input: &'input str,
     __0: (usize, Expr, usize),
 ) -> Expr
 {
     let __start0 = __0.2.clone();
     let __end0 = __0.2.clone();
-    let __temp0 = __action35(
+    let __temp0 = __action49(
+        src,
         input,
         &__start0,
         &__end0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action18(
+    __action26(
+        src,
         input,
         __0,
         __temp0,
@@ -2343,22 +3194,25 @@ fn __action49<
 }
 
 #[allow(unused_variables)]
-fn __action50<
+fn __action66<
     'input,
 >(
+    src: &str,
     input: &'input str,
     __0: (usize, Expr, usize),
-    __1: (usize, ::std::vec::Vec<&'static str>, usize),
+    __1: (usize, alloc::vec::Vec<(&'static str, Option<String>)>, usize),
 ) -> Expr
 {
     let __start0 = __1.0.clone();
     let __end0 = __1.2.clone();
-    let __temp0 = __action36(
+    let __temp0 = __action50(
+        src,
         input,
         __1,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action18(
+    __action26(
+        src,
         input,
         __0,
         __temp0,
@@ -2366,43 +3220,49 @@ fn __action50<
 }
 
 #[allow(unused_variables)]
-fn __action51<
+fn __action67<
     'input,
 >(
+    src: &str,
     input: &'input str,
     __0: (usize, &'static str, usize),
-) -> ::std::vec::Vec<&'static str>
+) -> alloc::vec::Vec<&'static str>
 {
     let __start0 = __0.0.clone();
     let __end0 = __0.2.clone();
-    let __temp0 = __action40(
+    let __temp0 = __action54(
+        src,
         input,
         __0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action41(
+    __action57(
+        src,
         input,
         __temp0,
     )
 }
 
 #[allow(unused_variables)]
-fn __action52<
+fn __action68<
     'input,
 >(
+    src: &str,
     input: &'input str,
-    __0: (usize, ::std::vec::Vec<&'static str>, usize),
+    __0: (usize, alloc::vec::Vec<&'static str>, usize),
     __1: (usize, &'static str, usize),
-) -> ::std::vec::Vec<&'static str>
+) -> alloc::vec::Vec<&'static str>
 {
     let __start0 = __1.0.clone();
     let __end0 = __1.2.clone();
-    let __temp0 = __action40(
+    let __temp0 = __action54(
+        src,
         input,
         __1,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action42(
+    __action58(
+        src,
         input,
         __0,
         __temp0,
@@ -2410,22 +3270,25 @@ fn __action52<
 }
 
 #[allow(unused_variables)]
-fn __action53<
+fn __action69<
     'input,
 >(
+    src: &str,
     input: &'input str,
     __0: (usize, Expr, usize),
 ) -> Expr
 {
     let __start0 = __0.0.clone();
     let __end0 = __0.0.clone();
-    let __temp0 = __action38(
+    let __temp0 = __action52(
+        src,
         input,
         &__start0,
         &__end0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action17(
+    __action25(
+        src,
         input,
         __temp0,
         __0,
@@ -2433,22 +3296,25 @@ fn __action53<
 }
 
 #[allow(unused_variables)]
-fn __action54<
+fn __action70<
     'input,
 >(
+    src: &str,
     input: &'input str,
-    __0: (usize, ::std::vec::Vec<&'static str>, usize),
+    __0: (usize, alloc::vec::Vec<&'static str>, usize),
     __1: (usize, Expr, usize),
 ) -> Expr
 {
     let __start0 = __0.0.clone();
     let __end0 = __0.2.clone();
-    let __temp0 = __action39(
+    let __temp0 = __action53(
+        src,
         input,
         __0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action17(
+    __action25(
+        src,
         input,
         __temp0,
         __1,
@@ -2456,47 +3322,53 @@ fn __action54<
 }
 
 #[allow(unused_variables)]
-fn __action55<
+fn __action71<
     'input,
 >(
+    src: &str,
     input: &'input str,
     __0: (usize, Expr, usize),
     __1: (usize, &'input str, usize),
-) -> ::std::vec::Vec<(Expr, &'input str)>
+) -> alloc::vec::Vec<(Expr, &'input str)>
 {
     let __start0 = __0.0.clone();
     let __end0 = __1.2.clone();
-    let __temp0 = __action34(
+    let __temp0 = __action48(
+        src,
         input,
         __0,
         __1,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action45(
+    __action61(
+        src,
         input,
         __temp0,
     )
 }
 
 #[allow(unused_variables)]
-fn __action56<
+fn __action72<
     'input,
 >(
+    src: &str,
     input: &'input str,
-    __0: (usize, ::std::vec::Vec<(Expr, &'input str)>, usize),
+    __0: (usize, alloc::vec::Vec<(Expr, &'input str)>, usize),
     __1: (usize, Expr, usize),
     __2: (usize, &'input str, usize),
-) -> ::std::vec::Vec<(Expr, &'input str)>
+) -> alloc::vec::Vec<(Expr, &'input str)>
 {
     let __start0 = __1.0.clone();
     let __end0 = __2.2.clone();
-    let __temp0 = __action34(
+    let __temp0 = __action48(
+        src,
         input,
         __1,
         __2,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action46(
+    __action62(
+        src,
         input,
         __0,
         __temp0,
@@ -2504,25 +3376,28 @@ fn __action56<
 }
 
 #[allow(unused_variables)]
-fn __action57<
+fn __action73<
     'input,
 >(
+    src: &str,
     input: &'input str,
     __0: (usize, String, usize),
     __1: (usize, &'input str, usize),
-    __2: (usize, ::std::option::Option<Expr>, usize),
+    __2: (usize, core::option::Option<Expr>, usize),
     __3: (usize, &'input str, usize),
 ) -> Expr
 {
     let __start0 = __1.2.clone();
     let __end0 = __2.0.clone();
-    let __temp0 = __action32(
+    let __temp0 = __action46(
+        src,
         input,
         &__start0,
         &__end0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action20(
+    __action29(
+        src,
         input,
         __0,
         __1,
@@ -2533,25 +3408,28 @@ fn __action57<
 }
 
 #[allow(unused_variables)]
-fn __action58<
+fn __action74<
     'input,
 >(
+    src: &str,
     input: &'input str,
     __0: (usize, String, usize),
     __1: (usize, &'input str, usize),
-    __2: (usize, ::std::vec::Vec<(Expr, &'input str)>, usize),
-    __3: (usize, ::std::option::Option<Expr>, usize),
+    __2: (usize, alloc::vec::Vec<(Expr, &'input str)>, usize),
+    __3: (usize, core::option::Option<Expr>, usize),
     __4: (usize, &'input str, usize),
 ) -> Expr
 {
     let __start0 = __2.0.clone();
     let __end0 = __2.2.clone();
-    let __temp0 = __action33(
+    let __temp0 = __action47(
+        src,
         input,
         __2,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action20(
+    __action29(
+        src,
         input,
         __0,
         __1,
@@ -2562,9 +3440,118 @@ fn __action58<
 }
 
 #[allow(unused_variables)]
-fn __action59<
+fn __action75<
+    'input,
+>(
+    src: &str,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+    __1: (usize, usize, usize),
+) -> Result<Expr,__lalrpop_util::ParseError<usize,Token<'input>,GrammarError>>
+{
+    let __start0 = __0.0.clone();
+    let __end0 = __0.0.clone();
+    let __temp0 = __action56(
+        src,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action8(
+        src,
+        input,
+        __temp0,
+        __0,
+        __1,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action76<
+    'input,
+>(
+    src: &str,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+    __1: (usize, usize, usize),
+) -> Result<(&'static str, Option<String>),__lalrpop_util::ParseError<usize,Token<'input>,GrammarError>>
+{
+    let __start0 = __0.0.clone();
+    let __end0 = __0.0.clone();
+    let __temp0 = __action56(
+        src,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action36(
+        src,
+        input,
+        __temp0,
+        __0,
+        __1,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action77<
+    'input,
+>(
+    src: &str,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+) -> Result<Expr,__lalrpop_util::ParseError<usize,Token<'input>,GrammarError>>
+{
+    let __start0 = __0.2.clone();
+    let __end0 = __0.2.clone();
+    let __temp0 = __action55(
+        src,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action75(
+        src,
+        input,
+        __0,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action78<
+    'input,
+>(
+    src: &str,
+    input: &'input str,
+    __0: (usize, &'input str, usize),
+) -> Result<(&'static str, Option<String>),__lalrpop_util::ParseError<usize,Token<'input>,GrammarError>>
+{
+    let __start0 = __0.2.clone();
+    let __end0 = __0.2.clone();
+    let __temp0 = __action55(
+        src,
+        input,
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action76(
+        src,
+        input,
+        __0,
+        __temp0,
+    )
+}
+
+#[allow(unused_variables)]
+fn __action79<
     'input,
 >(
+    src: &str,
     input: &'input str,
     __0: (usize, String, usize),
     __1: (usize, &'input str, usize),
@@ -2574,12 +3561,14 @@ fn __action59<
 {
     let __start0 = __2.0.clone();
     let __end0 = __2.2.clone();
-    let __temp0 = __action30(
+    let __temp0 = __action44(
+        src,
         input,
         __2,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action57(
+    __action73(
+        src,
         input,
         __0,
         __1,
@@ -2589,9 +3578,10 @@ fn __action59<
 }
 
 #[allow(unused_variables)]
-fn __action60<
+fn __action80<
     'input,
 >(
+    src: &str,
     input: &'input str,
     __0: (usize, String, usize),
     __1: (usize, &'input str, usize),
@@ -2600,13 +3590,15 @@ fn __action60<
 {
     let __start0 = __1.2.clone();
     let __end0 = __2.0.clone();
-    let __temp0 = __action31(
+    let __temp0 = __action45(
+        src,
         input,
         &__start0,
         &__end0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action57(
+    __action73(
+        src,
         input,
         __0,
         __1,
@@ -2616,25 +3608,28 @@ fn __action60<
 }
 
 #[allow(unused_variables)]
-fn __action61<
+fn __action81<
     'input,
 >(
+    src: &str,
     input: &'input str,
     __0: (usize, String, usize),
     __1: (usize, &'input str, usize),
-    __2: (usize, ::std::vec::Vec<(Expr, &'input str)>, usize),
+    __2: (usize, alloc::vec::Vec<(Expr, &'input str)>, usize),
     __3: (usize, Expr, usize),
     __4: (usize, &'input str, usize),
 ) -> Expr
 {
     let __start0 = __3.0.clone();
     let __end0 = __3.2.clone();
-    let __temp0 = __action30(
+    let __temp0 = __action44(
+        src,
         input,
         __3,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action58(
+    __action74(
+        src,
         input,
         __0,
         __1,
@@ -2645,25 +3640,28 @@ fn __action61<
 }
 
 #[allow(unused_variables)]
-fn __action62<
+fn __action82<
     'input,
 >(
+    src: &str,
     input: &'input str,
     __0: (usize, String, usize),
     __1: (usize, &'input str, usize),
-    __2: (usize, ::std::vec::Vec<(Expr, &'input str)>, usize),
+    __2: (usize, alloc::vec::Vec<(Expr, &'input str)>, usize),
     __3: (usize, &'input str, usize),
 ) -> Expr
 {
     let __start0 = __2.2.clone();
     let __end0 = __3.0.clone();
-    let __temp0 = __action31(
+    let __temp0 = __action45(
+        src,
         input,
         &__start0,
         &__end0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action58(
+    __action74(
+        src,
         input,
         __0,
         __1,
@@ -2673,17 +3671,20 @@ fn __action62<
     )
 }
 
-pub trait __ToTriple<'input, > {
-    fn to_triple(value: Self) -> Result<(usize,Token<'input>,usize), __lalrpop_util::ParseError<usize, Token<'input>, &'static str>>;
+pub trait __ToTriple<'input, >
+{
+    fn to_triple(value: Self) -> Result<(usize,Token<'input>,usize), __lalrpop_util::ParseError<usize, Token<'input>, GrammarError>>;
 }
 
-impl<'input, > __ToTriple<'input, > for (usize, Token<'input>, usize) {
-    fn to_triple(value: Self) -> Result<(usize,Token<'input>,usize), __lalrpop_util::ParseError<usize, Token<'input>, &'static str>> {
+impl<'input, > __ToTriple<'input, > for (usize, Token<'input>, usize)
+{
+    fn to_triple(value: Self) -> Result<(usize,Token<'input>,usize), __lalrpop_util::ParseError<usize, Token<'input>, GrammarError>> {
         Ok(value)
     }
 }
-impl<'input, > __ToTriple<'input, > for Result<(usize, Token<'input>, usize), &'static str> {
-    fn to_triple(value: Self) -> Result<(usize,Token<'input>,usize), __lalrpop_util::ParseError<usize, Token<'input>, &'static str>> {
+impl<'input, > __ToTriple<'input, > for Result<(usize, Token<'input>, usize), GrammarError>
+{
+    fn to_triple(value: Self) -> Result<(usize,Token<'input>,usize), __lalrpop_util::ParseError<usize, Token<'input>, GrammarError>> {
         match value {
             Ok(v) => Ok(v),
             Err(error) => Err(__lalrpop_util::ParseError::User { error }),
@@ -0,0 +1,176 @@
+use crate::ast::Expr;
+use crate::eval::Context;
+use crate::Result;
+use std::fmt;
+
+/// How a node in an evaluation plan is evaluated, from cheap to expensive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlanKind {
+    /// A pre-calculated set, or a name defined by the context.
+    Inlined,
+
+    /// A reference name or a commit hash.
+    Name,
+
+    /// A commit graph operation, like `ancestors(x)`. Usually cheap.
+    Dag,
+
+    /// Calculated once and cached by the repo, like `public()`.
+    Cached,
+
+    /// Tests commits one by one by reading commit objects, like
+    /// `author(name)`. The cost grows with the number of tested commits.
+    Filter,
+
+    /// Similar to `Filter`, but also calculates diffs, like `modifies(path)`.
+    /// Expensive.
+    DiffFilter,
+
+    /// A user-defined function. The cost is unknown.
+    Custom,
+}
+
+/// A node in an evaluation plan. See
+/// [`Repo::plan`](struct.Repo.html#method.plan).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlanNode {
+    /// The name, or the function name with non-set arguments, like
+    /// `author(alice)`.
+    pub label: String,
+
+    /// How the node is evaluated.
+    pub kind: PlanKind,
+
+    /// Whether only commits from the other side of an intersection or a
+    /// difference are tested. This applies to filters intersected with, or
+    /// subtracted from other sets, like `author(alice)` in
+    /// `draft() & author(alice)`.
+    pub scoped: bool,
+
+    /// Set arguments.
+    pub children: Vec<PlanNode>,
+}
+
+impl PlanNode {
+    fn is_filter(&self) -> bool {
+        matches!(self.kind, PlanKind::Filter | PlanKind::DiffFilter)
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter, indent: usize) -> fmt::Result {
+        let kind = match self.kind {
+            PlanKind::Inlined => "inlined",
+            PlanKind::Name => "name",
+            PlanKind::Dag => "dag",
+            PlanKind::Cached => "cached",
+            PlanKind::Filter => "filter",
+            PlanKind::DiffFilter => "diff filter",
+            PlanKind::Custom => "custom",
+        };
+        let scoped = if self.scoped { ", scoped" } else { "" };
+        writeln!(
+            f,
+            "{:indent$}{} [{}{}]",
+            "",
+            self.label,
+            kind,
+            scoped,
+            indent = indent
+        )?;
+        for child in &self.children {
+            child.fmt_indented(f, indent + 2)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PlanNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+/// All arguments are sets.
+const ALL: usize = usize::MAX;
+
+/// Classify a builtin function. Return the kind and the number of leading
+/// set arguments.
+pub(crate) fn classify(name: &str) -> (PlanKind, usize) {
+    match name {
+        "head" | "all" | "publichead" | "drafthead" | "public" | "draft" => (PlanKind::Cached, 0),
+        "id" | "main" | "ref" | "tag" => (PlanKind::Name, 0),
+        "limit" | "sort" | "nthparent" | "nthancestor" => (PlanKind::Dag, 1),
+        // `follow` walks the history to track renames. It is not a filter.
+        "recent" | "unreachable" | "secret" | "orphan" | "divergent" | "none" | "follow" => {
+            (PlanKind::Dag, 0)
+        }
+        "author" | "committer" | "date" | "committerdate" | "workdate" | "desc" | "descexact"
+        | "grep" | "summary" | "fixes" | "emptycommit" | "merge" | "nonmerge" | "revertcommits"
+        | "cherrypicks" => (PlanKind::Filter, 0),
+        "reverts" | "cherrypickedfrom" => (PlanKind::Filter, 1),
+        "modifies" | "file" | "bigdiff" | "touched" | "changedsince" => (PlanKind::DiffFilter, 0),
+        "duplicates" => (PlanKind::DiffFilter, ALL),
+        _ => (PlanKind::Dag, ALL),
+    }
+}
+
+/// Describe how `expr` will be evaluated, without evaluating it.
+pub(crate) fn plan(expr: &Expr, context: &Context) -> Result<PlanNode> {
+    let node = match expr {
+        Expr::Name(name) => {
+            let kind = if context.names.contains_key(name) {
+                PlanKind::Inlined
+            } else if context.fns.contains_key(name) {
+                PlanKind::Custom
+            } else {
+                PlanKind::Name
+            };
+            leaf(name.clone(), kind)
+        }
+        Expr::Inlined(set) => leaf(format!("{:?}", set), PlanKind::Inlined),
+        Expr::Regex(_) => leaf(expr.to_string(), PlanKind::Name),
+        Expr::Fn(name, args) => {
+            // Report unknown functions like `eval`.
+            crate::eval::get_function(name, context)?;
+            let (kind, set_args) = if context.fns.contains_key(name.as_ref()) {
+                (PlanKind::Custom, 0)
+            } else {
+                classify(name)
+            };
+            let set_args = set_args.min(args.len());
+            let mut children = args[..set_args]
+                .iter()
+                .map(|arg| plan(arg, context))
+                .collect::<Result<Vec<_>>>()?;
+            // Filters are only tested against commits in the non-filter side.
+            if let [a, b] = &mut children[..] {
+                match (name.as_ref(), a.is_filter(), b.is_filter()) {
+                    ("intersection", true, false) => a.scoped = true,
+                    ("intersection" | "difference", false, true) => b.scoped = true,
+                    _ => (),
+                }
+            }
+            let literals: Vec<String> = args[set_args..].iter().map(|a| a.to_string()).collect();
+            let label = if children.is_empty() || !literals.is_empty() {
+                format!("{}({})", name, literals.join(", "))
+            } else {
+                name.to_string()
+            };
+            PlanNode {
+                label,
+                kind,
+                scoped: false,
+                children,
+            }
+        }
+    };
+    Ok(node)
+}
+
+fn leaf(label: String, kind: PlanKind) -> PlanNode {
+    PlanNode {
+        label,
+        kind,
+        scoped: false,
+        children: Vec::new(),
+    }
+}
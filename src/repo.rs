@@ -1,53 +1,157 @@
+use crate::ast;
 use crate::ast::Expr;
 use crate::ast::ParseToExpr;
+use crate::cache::ExprCache;
+use crate::cache::ExprKey;
+use crate::ext::OidExt;
+use crate::ext::VertexExt;
+use crate::ext::VertexIterExt;
+use crate::plan::PlanNode;
+use crate::version::compare_tag_names;
+use crate::Error;
 use crate::EvalContext;
 use crate::Result;
+use crate::RevsFuture;
+use crate::SetExt;
 use dag::namedag::MemNameDag;
+use dag::ops::DagPersistent;
+use dag::ops::IdConvert;
 use dag::DagAlgorithm;
 use dag::Set;
 use dag::Vertex;
 use gitdag::dag;
+use gitdag::errors::MapDagError;
 use gitdag::git2;
+use gitdag::git2::Oid;
 use gitdag::GitDag;
+use globset::Glob;
+use hgtime::HgTime;
 use once_cell::sync::OnceCell;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::sync::Mutex;
+use std::thread;
+use std::thread::JoinHandle;
+
+/// Public and draft sets.
+type Phases = (Set, Set);
 
 /// Repo with extra states to support revset queries.
 pub struct Repo {
     git_repo: Box<dyn AsRef<git2::Repository>>,
-    dag: GitDag,
+    /// Shared with repos used by `revs_async`.
+    dag: Arc<GitDag>,
+    dag_path: PathBuf,
+    main_branch_name: String,
     cached_sets: Mutex<HashMap<&'static str, Set>>,
     cached_mutation_dag: OnceCell<MemNameDag>,
     cached_eval_context: OnceCell<EvalContext>,
+    cached_patch_ids: Mutex<HashMap<Oid, Option<Oid>>>,
+    cached_exprs: Mutex<ExprCache>,
+    /// Idle git repo handles that can be sent to other threads.
+    git_repo_pool: Arc<Mutex<Vec<git2::Repository>>>,
+    /// Public and draft sets being computed in a background thread.
+    pending_phases: Mutex<Option<JoinHandle<Result<Phases>>>>,
+    precompute_phases: bool,
+    index_reflogs: bool,
+    rebuild_if_stale: bool,
+    /// Fixed "now" in seconds since epoch. `None` uses the system time.
+    now: Option<i64>,
+    /// Changes when the commit graph index is reloaded.
+    dag_version: u64,
 }
 
 impl Repo {
     /// Open an existing repo. Build indexes on demand.
     pub fn open_from_env() -> Result<Self> {
-        let repo = git2::Repository::open_from_env()?;
-        Self::open_from_repo(Box::new(repo))
+        Self::builder().build()
     }
 
     /// Open an existing repo previously opened by libgit2.
     /// Build commit graph indexes on demand.
+    ///
+    /// The index is stored in the `dag` directory inside the `.git`
+    /// directory. To store it elsewhere, for example, if `.git` is
+    /// read-only, use
+    /// [`open_from_repo_with_options`](struct.Repo.html#method.open_from_repo_with_options).
     pub fn open_from_repo(git_repo: impl AsRef<git2::Repository> + 'static) -> Result<Self> {
-        let git_repo_ref = git_repo.as_ref();
-        let dag_path = git_repo_ref.path().join("dag");
-        let main_branch_name = guess_main_branch_name(git_repo_ref);
-        let dag = GitDag::open_git_repo(git_repo_ref, &dag_path, &main_branch_name)?;
-        let cached_sets = Default::default();
-        let cached_mutation_dag = Default::default();
-        let cached_eval_context = Default::default();
-        let result = Repo {
-            git_repo: Box::new(git_repo),
-            dag,
-            cached_sets,
-            cached_mutation_dag,
-            cached_eval_context,
-        };
+        Self::open_from_repo_with_options(git_repo, OpenOptions::default())
+    }
 
-        Ok(result)
+    /// Open an existing repo previously opened by libgit2, with `options`.
+    /// Build commit graph indexes on demand.
+    pub fn open_from_repo_with_options(
+        git_repo: impl AsRef<git2::Repository> + 'static,
+        options: OpenOptions,
+    ) -> Result<Self> {
+        let mut builder = Self::builder().git_repo(git_repo);
+        if let Some(path) = options.dag_path {
+            builder = builder.dag_path(path);
+        }
+        builder.build()
+    }
+
+    /// Open an existing repo containing `path`. Search parent directories
+    /// like `git -C path`. Build commit graph indexes on demand.
+    pub fn open_from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let git_repo = git2::Repository::discover(path)?;
+        Self::open_from_repo(Box::new(git_repo))
+    }
+
+    /// Returns a [`RepoBuilder`](struct.RepoBuilder.html) to open a repo
+    /// with customized options.
+    pub fn builder() -> RepoBuilder {
+        RepoBuilder::default()
+    }
+
+    /// Test if references were changed after the commit graph index was
+    /// built. If so, queries might miss new commits. Use
+    /// [`reload`](struct.Repo.html#method.reload) to pick up the changes.
+    pub fn index_is_stale(&self) -> Result<bool> {
+        references_changed(self.git_repo(), &self.dag)
+    }
+
+    /// Read references and update the commit graph index. Clear caches that
+    /// depend on references or configs.
+    pub fn reload(&mut self) -> Result<()> {
+        let git_repo = self.git_repo.as_ref().as_ref();
+        if self.index_reflogs {
+            index_reflog_commits(git_repo, &self.dag_path, &self.main_branch_name)?;
+        }
+        self.dag = Arc::new(open_dag(
+            git_repo,
+            &self.dag_path,
+            &self.main_branch_name,
+            self.rebuild_if_stale,
+        )?);
+        self.cached_sets.lock().unwrap().clear();
+        self.cached_mutation_dag = Default::default();
+        self.cached_eval_context = Default::default();
+        self.git_repo_pool.lock().unwrap().clear();
+        self.dag_version += 1;
+        *self.pending_phases.lock().unwrap() = None;
+        if self.precompute_phases {
+            self.spawn_phase_computation()?;
+        }
+        Ok(())
+    }
+
+    /// Reload if the commit graph index is stale. Return `true` if reloaded.
+    ///
+    /// Long-running processes can call this before queries to pick up
+    /// changes made by other processes.
+    pub fn reload_if_stale(&mut self) -> Result<bool> {
+        let stale = self.index_is_stale()?;
+        if stale {
+            self.reload()?;
+        }
+        Ok(stale)
     }
 
     /// Evaluate the expression. Return the resulting set.
@@ -77,11 +181,555 @@ impl Repo {
         crate::eval::eval(self, &ast, ctx)
     }
 
+    /// Evaluate the expression in a background thread. User-defined aliases
+    /// are ignored, like `revs`.
+    ///
+    /// The returned future does not block the async executor while heavy
+    /// queries, like filters scanning many commits, are evaluated. The
+    /// evaluation shares the commit graph index, cached sets and options
+    /// with this repo, so it sees the same references. Evaluations run in a
+    /// bounded pool of background threads.
+    pub fn revs_async(&self, ast: impl ParseToExpr) -> Result<RevsFuture> {
+        let ast = ast.parse_to_expr()?;
+        let open_repo = self.share();
+        Ok(RevsFuture::spawn(move || open_repo()?.revs(ast)))
+    }
+
+    /// Return a function that opens this repo in another thread. The opened
+    /// repo shares the commit graph index without syncing it again.
+    fn share(&self) -> impl FnOnce() -> Result<Repo> + Send + 'static {
+        let git_dir = self.git_repo().path().to_path_buf();
+        let dag = self.dag.clone();
+        let dag_path = self.dag_path.clone();
+        let main_branch_name = self.main_branch_name.clone();
+        let cached_sets = self.cached_sets.lock().unwrap().clone();
+        let expr_cache_size = self.cached_exprs.lock().unwrap().capacity();
+        let precompute_phases = self.precompute_phases;
+        let index_reflogs = self.index_reflogs;
+        let rebuild_if_stale = self.rebuild_if_stale;
+        let now = self.now;
+        let dag_version = self.dag_version;
+        move || {
+            let git_repo = git2::Repository::open(git_dir)?;
+            Ok(Repo {
+                git_repo: Box::new(Box::new(git_repo)),
+                dag,
+                dag_path,
+                main_branch_name,
+                cached_sets: Mutex::new(cached_sets),
+                cached_mutation_dag: Default::default(),
+                cached_eval_context: Default::default(),
+                cached_patch_ids: Default::default(),
+                cached_exprs: Mutex::new(ExprCache::new(expr_cache_size)),
+                git_repo_pool: Default::default(),
+                // Phases being computed are not shared. They are calculated
+                // on demand if needed.
+                pending_phases: Default::default(),
+                precompute_phases,
+                index_reflogs,
+                rebuild_if_stale,
+                now,
+                dag_version,
+            })
+        }
+    }
+
+    /// Describe how the expression will be evaluated, without evaluating it.
+    /// User-defined aliases are ignored, like `revs`.
+    ///
+    /// The plan is a tree of set arguments. Each node is classified as a
+    /// cheap commit graph operation, a cached set, or a filter testing
+    /// commits one by one. Filters intersected with, or subtracted from
+    /// other sets only test commits in the other sets.
+    pub fn plan(&self, ast: impl ParseToExpr) -> Result<PlanNode> {
+        let ast = ast.parse_to_expr()?;
+        crate::plan::plan(&ast, &Default::default())
+    }
+
+    /// Build a set from commit hashes, for example, provided by other tools.
+    /// The set can be used in expressions via `Expr::Inlined`. It is sorted
+    /// in the commit graph order, like other sets.
+    ///
+    /// Return `UnresolvedName` error if a commit is not in the commit graph
+    /// index.
+    pub fn set_from_oids(&self, oids: impl IntoIterator<Item = Oid>) -> Result<Set> {
+        let mut vertexes = Vec::new();
+        for oid in oids {
+            let v = oid.to_vertex();
+            if !self.dag.contains_vertex_name(&v)? {
+                return Err(Error::UnresolvedName(oid.to_string()));
+            }
+            vertexes.push(v);
+        }
+        self.to_set(vertexes)
+    }
+
+    /// Test if `oid` is in `set`.
+    ///
+    /// This is usually faster than iterating the set. For example, `draft()`
+    /// and `desc(text)` only need to check `oid` instead of scanning commits.
+    pub fn set_contains(&self, set: &Set, oid: Oid) -> Result<bool> {
+        Ok(set.contains(&oid.to_vertex())?)
+    }
+
+    /// Narrow `base` by the expression. Return the commits in both.
+    ///
+    /// The result is lazy. Only commits in `base` are tested against the
+    /// expression, so filters like `author(name)` do not scan all commits.
+    /// This is useful for pickers narrowing a small set, like `draft()`, as
+    /// the user types. The result can be refined again.
+    pub fn refine(&self, base: &Set, ast: impl ParseToExpr) -> Result<Set> {
+        let ast = ast.parse_to_expr()?;
+        crate::eval::eval_in(self, base, &ast, &Default::default())
+    }
+
+    /// Evaluate the expression. Return the number of commits in the result.
+    /// User-defined aliases are ignored, like `revs`.
+    ///
+    /// Sets backed by the commit graph index, like `::.`, are counted without
+    /// iterating commits. Filters still need to test commits one by one.
+    pub fn count(&self, ast: impl ParseToExpr) -> Result<usize> {
+        let set = self.revs(ast)?;
+        Ok(set.count()?)
+    }
+
+    /// Evaluate the expression. Take at most `max` commits from the result.
+    /// Return the commits and whether the result was truncated.
+    ///
+    /// This is a safety valve for huge repos. Expensive filters like
+    /// `desc(text)` stop scanning once enough commits are found.
+    pub fn revs_capped(&self, ast: impl ParseToExpr, max: usize) -> Result<(Set, bool)> {
+        let set = self.revs(ast)?;
+        let mut iter = set.iter()?;
+        let mut names = Vec::new();
+        for name in iter.by_ref().take(max) {
+            names.push(name?);
+        }
+        let truncated = match iter.next() {
+            Some(name) => name.map(|_| true)?,
+            None => false,
+        };
+        Ok((self.to_set(names)?, truncated))
+    }
+
+    /// Evaluate multiple expressions. Merge the results into a single stream,
+    /// newest first, similar to `git log x y`.
+    ///
+    /// Each result set is iterated lazily in its DAG order, without sorting.
+    /// Among the next commits of all sets, the one with the newest author
+    /// date is emitted first. So the stream is ordered by author date only if
+    /// dates in each set follow the DAG order. Commits appearing in multiple
+    /// sets are only emitted once. Commits with the same author date are
+    /// emitted in the order of `asts`.
+    pub fn merge_streams(
+        &self,
+        asts: impl IntoIterator<Item = impl ParseToExpr>,
+    ) -> Result<impl Iterator<Item = Result<Oid>> + '_> {
+        let mut streams = Vec::new();
+        for ast in asts {
+            streams.push(self.revs(ast)?.to_oids()?);
+        }
+        let mut heads: Vec<Option<(i64, Oid)>> = vec![None; streams.len()];
+        let mut seen = HashSet::new();
+        let iter = std::iter::from_fn(move || -> Option<Result<Oid>> {
+            // Fill in heads of each stream.
+            for (stream, head) in streams.iter_mut().zip(heads.iter_mut()) {
+                if head.is_some() {
+                    continue;
+                }
+                for oid in stream.by_ref() {
+                    let oid = match oid {
+                        Ok(oid) => oid,
+                        Err(e) => return Some(Err(e)),
+                    };
+                    if seen.contains(&oid) {
+                        continue;
+                    }
+                    match self.git_repo().find_commit(oid) {
+                        Ok(commit) => *head = Some((commit.author().when().seconds(), oid)),
+                        Err(e) => return Some(Err(e.into())),
+                    }
+                    break;
+                }
+            }
+            // Pick the newest head.
+            let mut picked: Option<(i64, Oid)> = None;
+            for head in heads.iter().flatten() {
+                picked = match picked {
+                    Some((time, _)) if time >= head.0 => picked,
+                    _ => Some(*head),
+                };
+            }
+            let (_, oid) = picked?;
+            seen.insert(oid);
+            for head in heads.iter_mut() {
+                if head.map(|(_, o)| o) == Some(oid) {
+                    *head = None;
+                }
+            }
+            Some(Ok(oid))
+        });
+        Ok(iter)
+    }
+
+    /// Calculate flags like `obsolete`, `public` for commits in `set`.
+    pub fn flags_for(&self, set: &Set) -> Result<HashMap<Oid, CommitFlags>> {
+        let obsolete = self.revs("obsolete()")? & set.clone();
+        let public = self.revs("public()")? & set.clone();
+        let head = self.revs("head()")? & set.clone();
+        let mut result = HashMap::new();
+        for v in set.iter()? {
+            let v = v?;
+            let flags = CommitFlags {
+                obsolete: obsolete.contains(&v)?,
+                public: public.contains(&v)?,
+                head: head.contains(&v)?,
+            };
+            result.insert(v.to_oid()?, flags);
+        }
+        Ok(result)
+    }
+
+    /// Parent-child edges among commits in `set`, as `(parent, child)`.
+    ///
+    /// Only edges with both ends in `set` are included. This is useful for
+    /// drawing the subgraph without querying parents of each commit. Edges
+    /// are ordered by the child in `set` order, then by parent order.
+    pub fn edges(&self, set: &Set) -> Result<Vec<(Oid, Oid)>> {
+        self.collect_edges(set, true)
+    }
+
+    /// Edges from commits in `set` to their parents outside `set`, as
+    /// `(parent, child)`. Together with `edges`, a frontend can mark commits
+    /// that have hidden ancestors.
+    pub fn dangling_edges(&self, set: &Set) -> Result<Vec<(Oid, Oid)>> {
+        self.collect_edges(set, false)
+    }
+
+    fn collect_edges(&self, set: &Set, inside: bool) -> Result<Vec<(Oid, Oid)>> {
+        let mut result = Vec::new();
+        for v in set.iter()? {
+            let v = v?;
+            let child = v.to_oid()?;
+            let mut parents = Vec::new();
+            for parent in self.dag.parent_names(v)? {
+                if set.contains(&parent)? == inside {
+                    parents.push(parent);
+                }
+            }
+            result.extend(parents.to_oid_vec()?.into_iter().map(|p| (p, child)));
+        }
+        Ok(result)
+    }
+
+    /// Heads of `set`, with reference names. Heads with references are
+    /// listed first, ordered by name. Other heads are ordered by committer
+    /// date, newest first. This is a friendly order for pickers.
+    ///
+    /// If a head has multiple references, local branches are preferred over
+    /// remote branches and tags. Prefixes like `refs/heads/` are stripped.
+    pub fn heads_annotated(&self, set: &Set) -> Result<Vec<(Oid, Option<String>)>> {
+        let heads = self.dag.heads(set.clone())?;
+        let mut names: HashMap<Vertex, String> = HashMap::new();
+        // References are sorted. "refs/heads/" sorts first.
+        for (name, v) in self.dag.git_references() {
+            if !name.starts_with("refs/") || !heads.contains(v)? {
+                continue;
+            }
+            let short_name = ["refs/heads/", "refs/remotes/", "refs/tags/"]
+                .iter()
+                .find_map(|prefix| name.strip_prefix(prefix))
+                .unwrap_or(name);
+            names
+                .entry(v.clone())
+                .or_insert_with(|| short_name.to_string());
+        }
+        let mut result = Vec::new();
+        for v in heads.iter()? {
+            let v = v?;
+            let oid = v.to_oid()?;
+            let time = self
+                .git_repo()
+                .find_commit(oid)?
+                .committer()
+                .when()
+                .seconds();
+            result.push((names.remove(&v), Reverse(time), oid));
+        }
+        result.sort_by(|a, b| (a.0.is_none(), &a.0, a.1).cmp(&(b.0.is_none(), &b.0, b.1)));
+        Ok(result
+            .into_iter()
+            .map(|(name, _time, oid)| (oid, name))
+            .collect())
+    }
+
+    /// Calculate ahead and behind counts of all local branches against their
+    /// upstreams, like "ahead 1, behind 2" in `git status`.
+    ///
+    /// The upstream is the configured one (`branch.<name>.merge`), or the
+    /// remote branch matching `upstream_pattern`, where `{}` is replaced by
+    /// the local branch name. For example, `"origin/{}"`. Use an empty
+    /// pattern to only use configured upstreams.
+    pub fn branch_status(&self, upstream_pattern: &str) -> Result<Vec<BranchStatus>> {
+        let refs = self.dag.git_references();
+        let mut result = Vec::new();
+        for (ref_name, v) in refs {
+            let name = match ref_name.strip_prefix("refs/heads/") {
+                Some(name) => name,
+                None => continue,
+            };
+            let configured = self
+                .git_repo()
+                .branch_upstream_name(ref_name)
+                .ok()
+                .and_then(|buf| buf.as_str().map(|s| s.to_string()));
+            let matched = match upstream_pattern {
+                "" => None,
+                pattern => Some(format!("refs/remotes/{}", pattern.replace("{}", name))),
+            };
+            let upstream = configured
+                .into_iter()
+                .chain(matched)
+                .find_map(|name| refs.get(&name).map(|upstream_v| (name, upstream_v)));
+            let status = match upstream {
+                Some((upstream_name, upstream_v)) => {
+                    let branch: Set = v.clone().into();
+                    let upstream: Set = upstream_v.clone().into();
+                    let short_name = ["refs/remotes/", "refs/heads/"]
+                        .iter()
+                        .find_map(|prefix| upstream_name.strip_prefix(prefix))
+                        .unwrap_or(&upstream_name);
+                    BranchStatus {
+                        name: name.to_string(),
+                        upstream: Some(short_name.to_string()),
+                        ahead: self.dag.only(branch.clone(), upstream.clone())?.count()?,
+                        behind: self.dag.only(upstream, branch)?.count()?,
+                    }
+                }
+                None => BranchStatus {
+                    name: name.to_string(),
+                    upstream: None,
+                    ahead: 0,
+                    behind: 0,
+                },
+            };
+            result.push(status);
+        }
+        Ok(result)
+    }
+
+    /// Count authors of commits in `set`. Return `("name <email>", count)`
+    /// sorted by count in descending order. `.mailmap` is respected.
+    pub fn authors(&self, set: &Set) -> Result<Vec<(String, usize)>> {
+        self.count_identities(set, |commit| commit.author().to_owned())
+    }
+
+    /// Count committers of commits in `set`. Similar to `authors`.
+    pub fn committers(&self, set: &Set) -> Result<Vec<(String, usize)>> {
+        self.count_identities(set, |commit| commit.committer().to_owned())
+    }
+
+    fn count_identities(
+        &self,
+        set: &Set,
+        signature: impl Fn(&git2::Commit) -> git2::Signature<'static>,
+    ) -> Result<Vec<(String, usize)>> {
+        let git_repo = self.git_repo();
+        let mailmap = git_repo.mailmap()?;
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for oid in set.to_oids()? {
+            let commit = git_repo.find_commit(oid?)?;
+            let sig = mailmap.resolve_signature(&signature(&commit))?;
+            let identity = format!(
+                "{} <{}>",
+                sig.name().unwrap_or(""),
+                sig.email().unwrap_or("")
+            );
+            *counts.entry(identity).or_default() += 1;
+        }
+        let mut result: Vec<_> = counts.into_iter().collect();
+        result.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(result)
+    }
+
+    /// Find tags matching the glob `pattern`, like `v1.*`. Return tag names
+    /// and commits, sorted by semantic versions in ascending order.
+    ///
+    /// Tags that are not semantic versions are sorted lexicographically,
+    /// after the ones that are.
+    pub fn tags_sorted(&self, pattern: &str) -> Result<Vec<(String, Oid)>> {
+        let matcher = match Glob::new(pattern) {
+            Ok(glob) => glob.compile_matcher(),
+            Err(e) => return Err(Error::ParseError(format!("invalid pattern: {}", e))),
+        };
+        let mut result = Vec::new();
+        for (name, v) in self.dag().git_references() {
+            if let Some(tag) = name.strip_prefix("refs/tags/") {
+                if matcher.is_match(tag) {
+                    result.push((tag.to_string(), v.to_oid()?));
+                }
+            }
+        }
+        result.sort_by(|a, b| compare_tag_names(&a.0, &b.0));
+        Ok(result)
+    }
+
+    /// Find the most recent tag reachable from `HEAD`. Return the tag name
+    /// and its commit, or `None` if there are no such tags.
+    ///
+    /// If a commit has multiple tags, the highest version is used.
+    pub fn last_tag(&self) -> Result<Option<(String, Oid)>> {
+        let tagged = self.revs(ast!(heads(intersection(ancestors("."), tag()))))?;
+        let vertex = match tagged.first()? {
+            Some(vertex) => vertex,
+            None => return Ok(None),
+        };
+        let name = self
+            .dag()
+            .git_references()
+            .iter()
+            .filter(|(_, v)| **v == vertex)
+            .filter_map(|(name, _)| name.strip_prefix("refs/tags/"))
+            .max_by(|a, b| compare_tag_names(a, b));
+        match name {
+            Some(name) => Ok(Some((name.to_string(), vertex.to_oid()?))),
+            None => Ok(None),
+        }
+    }
+
+    /// Find commits that last changed lines in `lines` (1-based, inclusive)
+    /// of the file at `path` in `HEAD`, using `git blame`.
+    pub fn blame_commits(
+        &self,
+        path: impl AsRef<Path>,
+        lines: RangeInclusive<usize>,
+    ) -> Result<Set> {
+        let mut options = git2::BlameOptions::new();
+        options.min_line(*lines.start()).max_line(*lines.end());
+        let blame = self
+            .git_repo()
+            .blame_file(path.as_ref(), Some(&mut options))?;
+        let vertexes: HashSet<Vertex> = blame
+            .iter()
+            .map(|hunk| hunk.final_commit_id().to_vertex())
+            .collect();
+        self.to_set(vertexes)
+    }
+
+    /// Create a reference for each commit in `set`, named `<prefix>/<n>`,
+    /// where `n` starts from 1 and skips existing names. Return the created
+    /// reference names, in the order of `set`.
+    ///
+    /// This keeps the commits alive, and makes them visible to git tools.
+    /// Use [`reload`](struct.Repo.html#method.reload) to use the new
+    /// references in queries.
+    pub fn create_refs(&self, set: &Set, prefix: &str) -> Result<Vec<String>> {
+        let prefix = normalize_ref_prefix(prefix)?;
+        let git_repo = self.git_repo();
+        let mut result = Vec::new();
+        let mut n = 0;
+        for oid in set.to_oids()? {
+            let oid = oid?;
+            let name = loop {
+                n += 1;
+                let name = format!("{}/{}", prefix, n);
+                if git_repo.find_reference(&name).is_err() {
+                    break name;
+                }
+            };
+            git_repo.reference(&name, oid, false, "gitrevset: create_refs")?;
+            result.push(name);
+        }
+        Ok(result)
+    }
+
+    /// Delete references named `<prefix>/*`, for example, created by
+    /// [`create_refs`](struct.Repo.html#method.create_refs). Return the
+    /// deleted reference names.
+    pub fn delete_refs(&self, prefix: &str) -> Result<Vec<String>> {
+        let prefix = normalize_ref_prefix(prefix)?;
+        let git_repo = self.git_repo();
+        let mut result = Vec::new();
+        for reference in git_repo.references_glob(&format!("{}/*", prefix))? {
+            let mut reference = reference?;
+            if let Some(name) = reference.name() {
+                result.push(name.to_string());
+            }
+            reference.delete()?;
+        }
+        Ok(result)
+    }
+
+    /// The remote preferred when a name matches branches of multiple remotes.
+    /// Read from the `revs.default-remote` config, or the remote of the main
+    /// branch.
+    pub(crate) fn default_remote(&self) -> Option<String> {
+        if let Ok(config) = self.git_repo().config() {
+            if let Ok(remote) = config.get_string("revs.default-remote") {
+                return Some(remote);
+            }
+        }
+        let rest = self.main_branch_name.strip_prefix("refs/remotes/")?;
+        rest.split('/').next().map(|s| s.to_string())
+    }
+
+    /// Use `now` (seconds since epoch) as the current time for relative
+    /// dates, like `date("today")` or `date("since 2 days ago")`. `None`
+    /// restores the system time. Useful for deterministic tests.
+    pub fn set_now(&mut self, now: Option<i64>) {
+        self.now = now;
+        self.cached_exprs.lock().unwrap().clear();
+    }
+
+    /// The current time used for relative dates.
+    pub(crate) fn now(&self) -> Option<HgTime> {
+        match self.now {
+            Some(unixtime) => Some(HgTime {
+                unixtime,
+                offset: 0,
+            }),
+            None => HgTime::now(),
+        }
+    }
+
+    /// The reference name of the main branch used by the commit graph index.
+    pub fn main_branch_name(&self) -> &str {
+        &self.main_branch_name
+    }
+
     /// Obtains read-only `dag` reference.
     pub fn dag(&self) -> &GitDag {
         &self.dag
     }
 
+    /// Obtains the graph of rewritten commits. Parents are previous versions,
+    /// children are newer versions. Commits that were never rewritten are not
+    /// included. See `revs.mutation-source` for where versions are read from.
+    pub fn mutation_dag(&self) -> Result<&MemNameDag> {
+        self.cached_mutation_dag
+            .get_or_try_init(|| crate::mutation::infer_mutation(self))
+    }
+
+    /// Direct previous versions of a commit.
+    pub fn predecessors_of(&self, oid: Oid) -> Result<Vec<Oid>> {
+        let mutdag = self.mutation_dag()?;
+        let set = self.mutation_set(oid)?;
+        mutdag.parents(set)?.to_oid_vec()
+    }
+
+    /// Direct newer versions of a commit.
+    pub fn successors_of(&self, oid: Oid) -> Result<Vec<Oid>> {
+        let mutdag = self.mutation_dag()?;
+        let set = self.mutation_set(oid)?;
+        mutdag.children(set)?.to_oid_vec()
+    }
+
+    /// `oid` as a set, if it is in the mutation graph.
+    fn mutation_set(&self, oid: Oid) -> Result<Set> {
+        let set = Set::from_static_names(std::iter::once(oid.to_vertex()));
+        Ok(set & self.mutation_dag()?.all()?)
+    }
+
     /// Obtains read-only `git2::Repository` reference.
     pub fn git_repo(&self) -> &git2::Repository {
         self.git_repo.as_ref().as_ref()
@@ -94,6 +742,72 @@ impl Repo {
             .get_or_try_init(|| parse_eval_context(self.git_repo()))
     }
 
+    /// Diff a commit against its first parent, or the empty tree for roots.
+    pub fn diff_first_parent(&self, oid: Oid) -> Result<git2::Diff<'_>> {
+        let git_repo = self.git_repo();
+        let commit = git_repo.find_commit(oid)?;
+        crate::eval::diff_first_parent(git_repo, &commit)
+    }
+
+    /// Calculate the patch-id of a commit, compared to its first parent.
+    /// Return `None` if the commit does not change anything.
+    ///
+    /// Commits with the same patch-id are likely cherry-picked from each
+    /// other. Results are cached.
+    pub fn patch_id(&self, oid: Oid) -> Result<Option<Oid>> {
+        if let Some(id) = self.cached_patch_ids.lock().unwrap().get(&oid) {
+            return Ok(*id);
+        }
+        let diff = self.diff_first_parent(oid)?;
+        let id = if diff.deltas().len() == 0 {
+            None
+        } else {
+            Some(diff.patchid(None)?)
+        };
+        self.cached_patch_ids.lock().unwrap().insert(oid, id);
+        Ok(id)
+    }
+
+    /// Start computing `public()` and `draft()` in a background thread.
+    fn spawn_phase_computation(&self) -> Result<()> {
+        // Resolving heads only reads references. It is cheap.
+        let public_heads = self.revs(ast!(publichead()))?;
+        let heads = self.revs(ast!(head()))?;
+        let dag = self.dag.dag_snapshot()?;
+        let handle = thread::spawn(move || -> Result<Phases> {
+            let public = dag.ancestors(public_heads)?;
+            let draft = dag.ancestors(heads)? - public.clone();
+            Ok((public, draft))
+        });
+        *self.pending_phases.lock().unwrap() = Some(handle);
+        Ok(())
+    }
+
+    /// Wait for the background phase computation, if any. Store the results
+    /// in the set cache.
+    pub(crate) fn wait_for_phases(&self) -> Result<()> {
+        let handle = self.pending_phases.lock().unwrap().take();
+        // If the thread panicked, phases are calculated lazily.
+        if let Some(Ok(result)) = handle.map(|h| h.join()) {
+            let (public, draft) = result?;
+            let mut cached_sets = self.cached_sets.lock().unwrap();
+            cached_sets.insert("public", public);
+            cached_sets.insert("draft", draft);
+        }
+        Ok(())
+    }
+
+    /// Idle git repo handles that can be sent to other threads, like the
+    /// ones testing commits for filters. Take a handle, and put it back
+    /// after use. The pool has at least one handle when returned.
+    pub(crate) fn git_repo_pool(&self) -> Result<Arc<Mutex<Vec<git2::Repository>>>> {
+        let mut pool = self.git_repo_pool.lock().unwrap();
+        if pool.is_empty() {
+            pool.push(git2::Repository::open(self.git_repo().path())?);
+        }
+        Ok(self.git_repo_pool.clone())
+    }
+
     pub(crate) fn cached_set(
         &self,
         name: &'static str,
@@ -112,19 +826,336 @@ impl Repo {
     }
 
     pub(crate) fn to_set(&self, iter: impl IntoIterator<Item = Vertex>) -> Result<Set> {
-        Ok(self.dag.sort(&Set::from_static_names(iter.into_iter()))?)
+        Ok(self.dag.sort(&Set::from_static_names(iter))?)
     }
 
-    pub(crate) fn mutation_dag(&self) -> Result<&MemNameDag> {
-        self.cached_mutation_dag
-            .get_or_try_init(|| crate::mutation::infer_mutation_from_reflog(self))
+    /// Key to look up the expression cache. `None` if the cache is disabled,
+    /// or the expression or the context cannot be cached.
+    pub(crate) fn expr_cache_key(
+        &self,
+        expr: &Expr,
+        context: &EvalContext,
+    ) -> Option<(ExprKey, bool)> {
+        if !self.cached_exprs.lock().unwrap().is_enabled() {
+            return None;
+        }
+        // Only the default context and the context from the git config are
+        // known to not change.
+        let with_config = match self.cached_eval_context.get() {
+            Some(config_context) if std::ptr::eq(config_context, context) => true,
+            _ if context.names.is_empty() && context.fns.is_empty() => false,
+            _ => return None,
+        };
+        Some((ExprKey::from_expr(expr)?, with_config))
+    }
+
+    pub(crate) fn cached_expr(&self, key: &(ExprKey, bool)) -> Option<Set> {
+        self.cached_exprs.lock().unwrap().get(key, self.dag_version)
+    }
+
+    pub(crate) fn cache_expr(&self, key: (ExprKey, bool), set: Set) {
+        let mut cache = self.cached_exprs.lock().unwrap();
+        cache.insert(key, set, self.dag_version);
+    }
+
+    #[cfg(test)]
+    pub(crate) fn expr_cache_stats(&self) -> (usize, usize) {
+        let cache = self.cached_exprs.lock().unwrap();
+        (cache.len(), cache.hits())
+    }
+}
+
+/// Flags of a commit. See [`Repo::flags_for`](struct.Repo.html#method.flags_for).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CommitFlags {
+    /// The commit is in `obsolete()`.
+    pub obsolete: bool,
+
+    /// The commit is in `public()`.
+    pub public: bool,
+
+    /// The commit is in `head()`.
+    pub head: bool,
+}
+
+/// Options for
+/// [`Repo::open_from_repo_with_options`](struct.Repo.html#method.open_from_repo_with_options).
+/// For more options, use [`RepoBuilder`](struct.RepoBuilder.html).
+#[derive(Clone, Debug, Default)]
+pub struct OpenOptions {
+    /// Directory to store the commit graph index. `None` uses the `dag`
+    /// directory inside the `.git` directory.
+    pub dag_path: Option<PathBuf>,
+}
+
+/// Ahead and behind counts of a local branch. See
+/// [`Repo::branch_status`](struct.Repo.html#method.branch_status).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BranchStatus {
+    /// Name of the local branch, like `main`.
+    pub name: String,
+
+    /// Name of the upstream branch, like `origin/main`. `None` if there is
+    /// no upstream.
+    pub upstream: Option<String>,
+
+    /// Number of commits in the branch but not in the upstream.
+    pub ahead: usize,
+
+    /// Number of commits in the upstream but not in the branch.
+    pub behind: usize,
+}
+
+/// Options to open a [`Repo`](struct.Repo.html).
+///
+/// ```
+/// # fn main() -> gitrevset::Result<()> {
+/// # #[cfg(feature = "testutil")]
+/// # {
+/// # let repo = gitrevset::TestRepo::new();
+/// # repo.set_env();
+/// let repo = gitrevset::Repo::builder()
+///     .main_branch("refs/remotes/origin/main")
+///     .build()?;
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct RepoBuilder {
+    git_repo: Option<Box<dyn AsRef<git2::Repository>>>,
+    dag_path: Option<PathBuf>,
+    main_branch: Option<String>,
+    expr_cache_size: usize,
+    precompute_phases: bool,
+    index_reflogs: bool,
+    rebuild_if_stale: bool,
+}
+
+impl RepoBuilder {
+    /// Use a repo previously opened by libgit2.
+    /// By default, the repo is opened from the environment.
+    pub fn git_repo(mut self, git_repo: impl AsRef<git2::Repository> + 'static) -> Self {
+        self.git_repo = Some(Box::new(git_repo));
+        self
+    }
+
+    /// Directory to store the commit graph index.
+    /// By default, it is the `dag` directory inside the `.git` directory.
+    pub fn dag_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.dag_path = Some(path.into());
+        self
+    }
+
+    /// Full reference name of the main branch, for example,
+    /// `refs/remotes/origin/master`. The commit graph index is optimized for
+    /// commits in the main branch.
+    /// By default, it is read from the `revs.main-branch` config, or guessed
+    /// from common remote branch names.
+    pub fn main_branch(mut self, name: impl Into<String>) -> Self {
+        self.main_branch = Some(name.into());
+        self
+    }
+
+    /// Cache evaluated sets of up to `size` expressions, including
+    /// sub-expressions. Useful for processes evaluating similar expressions
+    /// repeatedly.
+    ///
+    /// Cached results are reused until the repo is
+    /// [reloaded](struct.Repo.html#method.reload). Expressions depending on
+    /// `HEAD`, the clock, reflogs, or configs, like `.`, `date("today")`, or
+    /// `fixes(x)`, are not cached. By default, the cache is disabled.
+    pub fn expr_cache_size(mut self, size: usize) -> Self {
+        self.expr_cache_size = size;
+        self
+    }
+
+    /// Compute `public()` and `draft()` in a background thread when the
+    /// repo is opened, so the first query using them does not wait as long.
+    ///
+    /// This costs a thread and some CPU time at startup even if phases are
+    /// never used. By default, phases are computed on first use.
+    pub fn precompute_phases(mut self, enabled: bool) -> Self {
+        self.precompute_phases = enabled;
+        self
+    }
+
+    /// Add commits referred by reflogs to the commit graph index, so
+    /// commits no longer referred by references, like the ones before
+    /// `git reset`, can be found by `unreachable()`.
+    ///
+    /// This reads reflogs of all references each time the repo is opened or
+    /// reloaded, which can be slow if reflogs are long.
+    /// By default, reflogs are not read.
+    pub fn index_reflogs(mut self, enabled: bool) -> Self {
+        self.index_reflogs = enabled;
+        self
+    }
+
+    /// Check references again after building the commit graph index. If
+    /// they were changed during indexing, for example, by a concurrent
+    /// `git fetch`, rebuild the index so it starts up to date. This also
+    /// applies to [`Repo::reload`](struct.Repo.html#method.reload).
+    ///
+    /// This costs an extra scan of references. Useful for long-running
+    /// processes opening busy repos.
+    /// By default, the index uses references read at the start of indexing.
+    pub fn rebuild_if_stale(mut self, enabled: bool) -> Self {
+        self.rebuild_if_stale = enabled;
+        self
+    }
+
+    /// Open the repo. Build commit graph indexes on demand.
+    pub fn build(self) -> Result<Repo> {
+        let git_repo = match self.git_repo {
+            Some(git_repo) => git_repo,
+            None => Box::new(Box::new(git2::Repository::open_from_env()?)),
+        };
+        let git_repo_ref = git_repo.as_ref().as_ref();
+        let dag_path = match self.dag_path {
+            Some(path) => path,
+            None => git_repo_ref.path().join("dag"),
+        };
+        let main_branch_name = match self.main_branch {
+            Some(name) => name,
+            None => guess_main_branch_name(git_repo_ref),
+        };
+        if self.index_reflogs {
+            index_reflog_commits(git_repo_ref, &dag_path, &main_branch_name)?;
+        }
+        let dag = Arc::new(open_dag(
+            git_repo_ref,
+            &dag_path,
+            &main_branch_name,
+            self.rebuild_if_stale,
+        )?);
+        let cached_sets = Default::default();
+        let cached_mutation_dag = Default::default();
+        let cached_eval_context = Default::default();
+        let cached_patch_ids = Default::default();
+        let cached_exprs = Mutex::new(ExprCache::new(self.expr_cache_size));
+        let result = Repo {
+            git_repo,
+            dag,
+            dag_path,
+            main_branch_name,
+            cached_sets,
+            cached_mutation_dag,
+            cached_eval_context,
+            cached_patch_ids,
+            cached_exprs,
+            git_repo_pool: Default::default(),
+            pending_phases: Default::default(),
+            precompute_phases: self.precompute_phases,
+            index_reflogs: self.index_reflogs,
+            rebuild_if_stale: self.rebuild_if_stale,
+            now: None,
+            dag_version: 0,
+        };
+        if result.precompute_phases {
+            result.spawn_phase_computation()?;
+        }
+
+        Ok(result)
+    }
+}
+
+/// Build or update the commit graph index at `dag_path`. If
+/// `rebuild_if_stale` is set, retry a few times until the index matches
+/// references.
+fn open_dag(
+    git_repo: &git2::Repository,
+    dag_path: &Path,
+    main_branch: &str,
+    rebuild_if_stale: bool,
+) -> Result<GitDag> {
+    const MAX_REBUILDS: usize = 3;
+    let mut dag = GitDag::open_git_repo(git_repo, dag_path, main_branch)?;
+    if rebuild_if_stale {
+        for _ in 0..MAX_REBUILDS {
+            if !references_changed(git_repo, &dag)? {
+                break;
+            }
+            dag = GitDag::open_git_repo(git_repo, dag_path, main_branch)?;
+        }
+    }
+    Ok(dag)
+}
+
+/// Test if references differ from the ones used by `dag`.
+fn references_changed(git_repo: &git2::Repository, dag: &GitDag) -> Result<bool> {
+    let mut references = BTreeMap::new();
+    for reference in git_repo.references()? {
+        let reference = reference?;
+        // Match the commit graph index. References to non-commits are
+        // ignored.
+        if let (Some(name), Ok(commit)) = (reference.name(), reference.peel_to_commit()) {
+            references.insert(name.to_string(), commit.id().to_vertex());
+        }
+    }
+    Ok(&references != dag.git_references())
+}
+
+/// Add commits in reflogs of references and `HEAD` to the commit graph
+/// index at `dag_path`.
+fn index_reflog_commits(
+    git_repo: &git2::Repository,
+    dag_path: &Path,
+    main_branch: &str,
+) -> Result<()> {
+    let mut names = vec!["HEAD".to_string()];
+    for name in git_repo.references()?.names() {
+        names.push(name?.to_string());
+    }
+    let mut heads = HashSet::new();
+    for name in names {
+        let reflog = match git_repo.reflog(&name) {
+            Ok(reflog) => reflog,
+            Err(_) => continue,
+        };
+        for entry in reflog.iter() {
+            for oid in [entry.id_old(), entry.id_new()] {
+                if !oid.is_zero() && git_repo.find_commit(oid).is_ok() {
+                    heads.insert(oid.to_vertex());
+                }
+            }
+        }
+    }
+    let mut heads: Vec<Vertex> = heads.into_iter().collect();
+    heads.sort_unstable();
+    // Index the main branch first so it stays in the optimized group.
+    let main_heads = match git_repo.refname_to_id(main_branch) {
+        Ok(oid) => vec![oid.to_vertex()],
+        Err(_) => Vec::new(),
+    };
+    let parent_func = |v: Vertex| -> dag::Result<Vec<Vertex>> {
+        let oid = Oid::from_bytes(v.as_ref()).context("converting to git oid")?;
+        let commit = git_repo
+            .find_commit(oid)
+            .context("resolving reflog commit")?;
+        Ok(commit.parent_ids().map(|id| id.to_vertex()).collect())
+    };
+    let mut dag = dag::Dag::open(dag_path)?;
+    dag.add_heads_and_flush(parent_func, &main_heads, &heads)?;
+    Ok(())
+}
+
+/// Strip the trailing `/`. Check that `prefix` is under `refs/`.
+fn normalize_ref_prefix(prefix: &str) -> Result<&str> {
+    let prefix = prefix.trim_end_matches('/');
+    match prefix.strip_prefix("refs/") {
+        Some(rest) if !rest.is_empty() => Ok(prefix),
+        _ => Err(Error::ParseError(format!(
+            "reference prefix {:?} is not under refs/",
+            prefix
+        ))),
     }
 }
 
 fn guess_main_branch_name(repo: &git2::Repository) -> String {
     if let Ok(config) = repo.config() {
-        if let Ok(s) = config.get_str("revs.main-branch") {
-            return s.to_string();
+        if let Ok(s) = config.get_string("revs.main-branch") {
+            return s;
         }
     }
     let candidates = [
@@ -143,25 +1174,27 @@ fn guess_main_branch_name(repo: &git2::Repository) -> String {
 
 fn parse_eval_context(repo: &git2::Repository) -> Result<EvalContext> {
     let mut result = EvalContext::default();
+    for (name, ast) in read_aliases(repo)? {
+        let func =
+            move |_name: &str, repo: &Repo, args: &[Expr], ctx: &EvalContext| -> Result<Set> {
+                repo.revs_with_context(ast::substitute_alias_args(&ast, args), ctx)
+            };
+        result.fns.insert(name, Box::new(func));
+    }
+    Ok(result)
+}
+
+/// Read user-defined aliases in the `[revsetalias]` config section.
+/// Aliases that cannot be parsed are ignored.
+pub(crate) fn read_aliases(repo: &git2::Repository) -> Result<HashMap<String, Expr>> {
+    let mut result = HashMap::new();
     let config = repo.config()?;
     for entry in &config.entries(Some("revsetalias.*"))? {
         let entry = entry?;
         if let (Some(name), Some(value)) = (entry.name(), entry.value()) {
             if let Some(name) = name.get("revsetalias.".len()..) {
                 if let Ok(ast) = value.parse_to_expr() {
-                    let func = move |_name: &str,
-                                     repo: &Repo,
-                                     args: &[Expr],
-                                     ctx: &EvalContext|
-                          -> Result<Set> {
-                        // Replace arguments in ast, ex. $1 -> args[0], ...
-                        let mut ast = ast.clone();
-                        for (i, arg) in args.iter().enumerate() {
-                            ast.replace(&format!("${}", i + 1), arg);
-                        }
-                        repo.revs_with_context(ast, ctx)
-                    };
-                    result.fns.insert(name.to_string(), Box::new(func));
+                    result.insert(name.to_string(), ast);
                 }
             }
         }
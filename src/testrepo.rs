@@ -4,6 +4,8 @@ use crate::SetExt;
 use git2::Oid;
 use gitdag::dag::Set;
 use std::ops::Deref;
+use std::ops::DerefMut;
+use std::path::Path;
 
 /// Repo for testing purpose.
 pub struct TestRepo {
@@ -15,7 +17,7 @@ impl TestRepo {
     /// Create a test repo.
     pub fn new() -> Self {
         let dir = tempfile::tempdir().unwrap();
-        let git_repo = git2::Repository::init(&dir.path()).unwrap();
+        let git_repo = git2::Repository::init(dir.path()).unwrap();
         let repo = Repo::open_from_repo(Box::new(git_repo)).unwrap();
         Self { dir, repo }
     }
@@ -54,6 +56,62 @@ impl TestRepo {
         self.reload();
     }
 
+    /// Add a commit on top of `parents` with file changes. `None` content
+    /// removes the file. The commit message and the branch name are `name`.
+    pub fn commit(&mut self, name: &str, parents: &[&str], files: &[(&str, Option<&str>)]) -> Oid {
+        let commit_id = {
+            let repo = self.repo.git_repo();
+            let parents: Vec<_> = parents
+                .iter()
+                .map(|p| repo.find_commit(self.query_single_oid(p)).unwrap())
+                .collect();
+            let mut index = git2::Index::new().unwrap();
+            if let Some(parent) = parents.first() {
+                index.read_tree(&parent.tree().unwrap()).unwrap();
+            }
+            for &(path, content) in files {
+                match content {
+                    Some(content) => {
+                        let entry = git2::IndexEntry {
+                            ctime: git2::IndexTime::new(0, 0),
+                            mtime: git2::IndexTime::new(0, 0),
+                            dev: 0,
+                            ino: 0,
+                            mode: 0o100644,
+                            uid: 0,
+                            gid: 0,
+                            file_size: content.len() as u32,
+                            id: repo.blob(content.as_bytes()).unwrap(),
+                            flags: path.len() as u16,
+                            flags_extended: 0,
+                            path: path.as_bytes().to_vec(),
+                        };
+                        index.add(&entry).unwrap();
+                    }
+                    None => index.remove_path(Path::new(path)).unwrap(),
+                }
+            }
+            let tree_id = index.write_tree_to(repo).unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            let epoch = parents
+                .iter()
+                .map(|p| p.time().seconds() + 1)
+                .max()
+                .unwrap_or(0);
+            let time = git2::Time::new(epoch, 0);
+            let sig = git2::Signature::new(name, "test@example.com", &time).unwrap();
+            let parent_refs: Vec<_> = parents.iter().collect();
+            let commit_id = repo
+                .commit(None, &sig, &sig, name, &tree, &parent_refs)
+                .unwrap();
+            repo.reference(&format!("refs/heads/{}", name), commit_id, true, "commit")
+                .unwrap();
+            commit_id
+        };
+        self.reload();
+        commit_id
+    }
+
     /// Run revset query. Return commit messages.
     pub fn query(&self, code: &str) -> Vec<String> {
         self.desc_set(&self.revs(code).unwrap())
@@ -67,8 +125,7 @@ impl TestRepo {
     /// Set -> Commit messages.
     pub fn desc_set(&self, set: &Set) -> Vec<String> {
         let mut result = Vec::new();
-        for oid in set.to_oids().unwrap() {
-            let oid = oid.unwrap();
+        for oid in set.to_oid_vec().unwrap() {
             let commit = self.git_repo().find_commit(oid).unwrap();
             let message = commit.message().unwrap();
             result.push(message.to_string());
@@ -97,14 +154,23 @@ impl TestRepo {
 
     /// Make "commit (amend)" change to a reference.
     pub fn amend(&mut self, ref_name: &str) {
+        let msg = {
+            let oid = self.git_repo().refname_to_id(ref_name).unwrap();
+            let commit = self.git_repo().find_commit(oid).unwrap();
+            commit.message().unwrap().to_string()
+        };
+        self.amend_with_message(ref_name, &format!("{}_new", msg));
+    }
+
+    /// Make "commit (amend)" change to a reference with the given commit
+    /// message.
+    pub fn amend_with_message(&mut self, ref_name: &str, new_msg: &str) {
         let dir = self.repo.git_repo().path();
         let git_repo = git2::Repository::init(dir).unwrap();
         let oid = git_repo.refname_to_id(ref_name).unwrap();
         let commit = git_repo.find_commit(oid).unwrap();
-        let msg = commit.message().unwrap();
-        let new_msg = format!("{}_new", msg);
         let new_oid = commit
-            .amend(None, None, None, None, Some(&new_msg), None)
+            .amend(None, None, None, None, Some(new_msg), None)
             .unwrap();
         git_repo
             .reference(ref_name, new_oid, true, "commit (amend): amend")
@@ -116,6 +182,22 @@ impl TestRepo {
         self.reload();
     }
 
+    /// Change the commit message of the branch `name`. The branch is moved to
+    /// the new commit. Children of the old commit are not rewritten.
+    pub fn reword(&mut self, name: &str, message: &str) -> Oid {
+        let ref_name = format!("refs/heads/{}", name);
+        let new_oid = {
+            let git_repo = self.repo.git_repo();
+            let commit = git_repo.find_reference(&ref_name).unwrap();
+            let commit = commit.peel_to_commit().unwrap();
+            commit
+                .amend(Some(&ref_name), None, None, None, Some(message), None)
+                .unwrap()
+        };
+        self.reload();
+        new_oid
+    }
+
     /// Set git configs.
     pub fn set_config(&mut self, name: &str, value: &str) {
         let git_repo = self.git_repo();
@@ -143,3 +225,9 @@ impl Deref for TestRepo {
         &self.repo
     }
 }
+
+impl DerefMut for TestRepo {
+    fn deref_mut(&mut self) -> &mut Repo {
+        &mut self.repo
+    }
+}
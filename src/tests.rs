@@ -1,6 +1,7 @@
 use crate::ext::OidExt;
 use crate::testrepo::TestRepo;
 use gitdag::dag::Set;
+use hgtime::HgTime;
 
 #[test]
 fn test_revset_functions() {
@@ -23,27 +24,84 @@ fn test_revset_functions() {
     assert_eq!(repo.query("roots(all())"), ["A"]);
     assert_eq!(repo.query("roots(A:C + G:H)"), ["G", "A"]);
     assert_eq!(repo.query("B:D"), ["D", "G", "F", "C", "B"]);
+    assert_eq!(repo.query("paths(B, D)"), ["D", "G", "F", "C", "B"]);
+    assert_eq!(repo.query("allpaths(B, D)"), ["D", "G", "F", "C", "B"]);
+    assert_eq!(repo.query("shortestpath(B, D)"), ["D", "C", "B"]);
+    assert_eq!(repo.query("shortestpath(F, E)"), ["E", "D", "G", "F"]);
+    assert_eq!(
+        repo.query("shortestpath(A, I)"),
+        ["I", "H", "G", "F", "B", "A"]
+    );
+    assert_eq!(repo.query("shortestpath(A + C, E)"), ["E", "D", "C"]);
+    assert!(repo.query("shortestpath(C, I)").is_empty());
     assert_eq!(repo.query("A:E - G - (C^ + C)^"), ["E", "D", "F", "C"]);
     assert_eq!(repo.query("!!!(B:D)"), ["I", "H", "E", "A"]);
     assert_eq!(repo.query("::B + G::"), ["I", "H", "E", "D", "G", "B", "A"]);
     assert_eq!(repo.query("H % E"), ["H"]);
     assert_eq!(repo.query("H % C"), ["H", "G", "F"]);
+    assert_eq!(repo.query("C..H"), ["H", "G", "F"]);
+    assert_eq!(repo.query("C .. H"), repo.query("H % C"));
+    assert_eq!(repo.query("sort(E...H, topo)"), ["H", "E", "D", "C"]);
+    assert_eq!(repo.query("sort(H...E, topo)"), ["H", "E", "D", "C"]);
+    assert!(repo.query("B...B").is_empty());
+    assert_eq!(repo.query("H ^C"), ["H", "G", "F"]);
+    assert_eq!(repo.query("^C H"), ["H", "G", "F"]);
+    assert_eq!(repo.query("E ^D I ^H"), ["I", "E"]);
+    assert_eq!(repo.query("E + I ^D ^H"), ["I", "E"]);
+    assert_eq!(repo.query("heads(H ^C)"), ["H"]);
+    assert!(repo.query("^C").is_empty());
+    assert_eq!(repo.query(r#""E~3""#), ["B"]);
+    assert_eq!(repo.query(r#""D^2" + "D^1""#), ["G", "C"]);
+    assert_eq!(repo.query(r#""H^^""#), ["F"]);
+    assert_eq!(repo.query(r#""I~2^""#), ["F"]);
+    assert!(repo.revs(r#""E~100""#).is_err());
+    assert!(repo.revs("E I").is_err());
     assert_eq!(repo.query("gca(E+H)"), ["G"]);
     assert_eq!(repo.query("gca(E,H)"), ["G"]);
     assert_eq!(repo.query("first(A:D)"), ["D"]);
     assert_eq!(repo.query("first(B-B,C,D)"), ["C"]);
     assert_eq!(repo.query("first(B-B,C+D)"), ["D"]);
     assert_eq!(repo.query("last(A:D)"), ["A"]);
+    assert_eq!(repo.query("limit(A:D, 2)"), ["D", "G"]);
+    assert_eq!(repo.query("limit(A:D, 10)"), repo.query("A:D"));
+    assert!(repo.query("limit(A:D, 0)").is_empty());
+    assert!(repo.revs("limit(A:D, x)").is_err());
+    assert_eq!(repo.query("reverse(A:D)"), ["A", "B", "C", "F", "G", "D"]);
+    assert_eq!(repo.query("first(reverse(A:D))"), ["A"]);
+    assert_eq!(repo.query("last(reverse(A:D))"), ["D"]);
+    assert_eq!(repo.query("limit(reverse(A:D), 2)"), ["B", "A"]);
+    assert!(repo.query("reverse(A - A)").is_empty());
+    assert_eq!(repo.query("linear(A)"), ["B", "A"]);
+    assert_eq!(repo.query("linear(C)"), ["C"]);
+    assert_eq!(repo.query("linear(E)"), ["E", "D"]);
+    assert_eq!(repo.query("linear(F)"), ["G", "F"]);
+    assert_eq!(repo.query("linear(C + I)"), ["I", "H", "C"]);
+    assert_eq!(repo.query("branchpoint(all())"), ["G", "B"]);
+    assert_eq!(repo.query("branchpoint(F:E)"), ["G"]);
+    assert_eq!(repo.query("mergepoint(all())"), ["D"]);
+    assert!(repo.query("mergepoint(E + I)").is_empty());
     assert_eq!(
         repo.query("children(G) | children(A:B)"),
         ["H", "D", "F", "C", "B"]
     );
+    assert_eq!(repo.query("children(B, C:E)"), ["C"]);
+    assert_eq!(repo.query("children(B + G, all())"), ["H", "D", "F", "C"]);
+    for (x, bound) in [("B", "C:E"), ("G", "D + I"), ("A:I", "F:I"), ("E", "all()")] {
+        assert_eq!(
+            repo.query(&format!("children({}, {})", x, bound)),
+            repo.query(&format!("children({}) & ({})", x, bound)),
+        );
+    }
     assert_eq!(repo.query("head()"), ["I", "E"]);
     assert_eq!(repo.query("desc(C)"), ["C"]);
     assert_eq!(repo.query("author(D)"), ["D"]);
     assert_eq!(repo.query("heads(author(test))"), ["I", "E"]);
     assert_eq!(repo.query("committer(E)"), ["E"]);
     assert_eq!(repo.query("heads(committer(test))"), ["I", "E"]);
+    assert_eq!(repo.query(r#"author("re:^[DE] <")"#), ["E", "D"]);
+    assert_eq!(repo.query(r#"committer("re:^E <test@")"#), ["E"]);
+    assert!(repo.query(r#"author("re:^test")"#).is_empty());
+    assert!(repo.revs(r#"author("re:(")"#).is_err());
     assert_eq!(repo.query("modifies(B)"), ["B"]);
 
     // date(), committerdate()
@@ -71,7 +129,7 @@ fn test_revset_functions() {
     // id(), ref(), tag(), "."
     for name in repo.query("all()") {
         let rev_code = format!("id({})", repo.query_single_oid(&name).to_vertex().to_hex());
-        assert_eq!(repo.query(&rev_code), [name.clone()]);
+        assert_eq!(repo.query(&rev_code), [name]);
     }
     assert_eq!(
         repo.query("ref()"),
@@ -96,6 +154,7 @@ fn test_revset_functions() {
     assert_eq!(repo.query("predecessors(H)"), ["H_new", "H"]);
     assert_eq!(repo.query("successors(H_old)"), ["H_new", "H"]);
     assert_eq!(repo.query("obsolete()"), ["H"]);
+    assert_eq!(repo.query("orphan()"), ["I"]);
 
     // apply
     assert_eq!(repo.query("apply($1, .)"), ["E"]);
@@ -104,68 +163,2427 @@ fn test_revset_functions() {
 }
 
 #[test]
-fn test_revset_alias_config() {
+fn test_content_functions() {
+    let mut repo = TestRepo::new();
+    repo.commit("A", &[], &[]);
+    repo.commit("B", &["A"], &[("a", Some("1"))]);
+    repo.commit("C", &["B"], &[]);
+    repo.commit("D", &["C"], &[("a", Some("2")), ("b/c", Some("3"))]);
+    repo.commit("E", &["D"], &[("b/c", None)]);
+    repo.commit("F", &["B", "E"], &[]);
+    repo.commit("G", &["F"], &[("d", Some("1\n2\n3\n4\n5\n"))]);
+
+    assert_eq!(repo.query("emptycommit()"), ["F", "C", "A"]);
+    assert_eq!(repo.query("bigdiff(0)"), ["G", "E", "D", "B"]);
+    assert_eq!(repo.query("bigdiff(2)"), ["G", "D"]);
+    assert_eq!(repo.query("bigdiff(3)"), ["G"]);
+    assert!(repo.query("bigdiff(5)").is_empty());
+    assert_eq!(repo.query("touched(2)"), ["D"]);
+    assert_eq!(repo.query(r#"touched(">1")"#), ["D"]);
+    assert_eq!(repo.query(r#"touched(">=1")"#), ["G", "E", "D", "B"]);
+    assert_eq!(repo.query(r#"touched("<1")"#), ["F", "C", "A"]);
+    assert_eq!(
+        repo.query(r#"touched("<= 1") & B::"#),
+        ["G", "F", "E", "C", "B"]
+    );
+    assert!(repo.revs(r#"touched(">x")"#).is_err());
+    assert_eq!(repo.query(r#"changedsince(a, "since 2 0")"#), ["D"]);
+    assert_eq!(repo.query(r#"changedsince(a, "0 0 to 3 0")"#), ["D", "B"]);
+    assert_eq!(
+        repo.query(r#"changedsince("b/*", "since 0 0")"#),
+        ["E", "D"]
+    );
+    assert_eq!(repo.query(r#"changedsince("*", "since 5 0")"#), ["G"]);
+    assert_eq!(repo.query("file(a)"), ["D", "B"]);
+    assert_eq!(repo.query(r#"file("b/**")"#), ["E", "D"]);
+    assert_eq!(repo.query(r#"file("{a,d}")"#), ["G", "D", "B"]);
+    assert_eq!(repo.query(r#"file("*") & ::D"#), ["D", "B"]);
+    assert!(repo.query("file(c)").is_empty());
+    assert!(repo.revs(r#"file("[")"#).is_err());
+
+    // changedsince(p, d) is file(p) & date(d).
+    for path in ["a", "b/*", "b/**", "{a,d}", "*", "c"] {
+        for date in [
+            "since 0 0",
+            "since 2 0",
+            "0 0 to 3 0",
+            "before 4 0",
+            "since 9 0",
+        ] {
+            let changed = format!(r#"changedsince("{}", "{}")"#, path, date);
+            let file_date = format!(r#"file("{}") & date("{}")"#, path, date);
+            assert_eq!(repo.query(&changed), repo.query(&file_date), "{}", changed);
+        }
+    }
+
+    // H is a cherry-pick of B.
+    repo.commit("H", &["A"], &[("a", Some("1"))]);
+    assert_eq!(repo.query("duplicates(H, all() - H)"), ["H"]);
+    assert_eq!(repo.query("duplicates(all(), H)"), ["H", "B"]);
+    assert_eq!(repo.query("duplicates(D + C, all())"), ["D"]);
+    assert!(repo.query("duplicates(G, B)").is_empty());
+    let patch_id = |name| repo.patch_id(repo.query_single_oid(name)).unwrap();
+    assert_eq!(patch_id("H"), patch_id("B"));
+    assert_ne!(patch_id("D"), patch_id("B"));
+    assert_eq!(patch_id("C"), None);
+    let changed_paths = |name| {
+        let diff = repo.diff_first_parent(repo.query_single_oid(name)).unwrap();
+        let paths: Vec<_> = diff
+            .deltas()
+            .map(|d| d.new_file().path().unwrap().to_owned())
+            .collect();
+        paths
+    };
+    assert_eq!(changed_paths("H"), changed_paths("B"));
+    assert!(changed_paths("C").is_empty());
+}
+
+#[test]
+fn test_duplicated_operands() -> crate::Result<()> {
     let mut repo = TestRepo::new();
     repo.drawdag("A--B--C");
-    repo.set_config("revsetalias.t", "B");
-    repo.set_config("revsetalias.f", "$1^^ + $1");
-    repo.set_config("revsetalias.g", "apply(f($1), children($1))");
+    repo.set_config("revsetalias.twice", "$1 + $1");
+    repo.set_config("revsetalias.overlap", "::$1 + $1^::");
 
-    assert_eq!(repo.query_with_alias_config("t"), ["B"]);
-    assert_eq!(repo.query_with_alias_config("t()"), ["B"]);
-    assert_eq!(repo.query_with_alias_config("f(C)"), ["C", "A"]);
-    assert_eq!(repo.query_with_alias_config("f(t())"), ["B"]);
-    assert_eq!(repo.query_with_alias_config("g(t)"), ["C", "A"]);
+    assert_eq!(repo.query("A + A"), ["A"]);
+    assert_eq!(repo.query("A + A + A"), ["A"]);
+    assert_eq!(repo.query("A:C + B:C"), ["C", "B", "A"]);
+    assert_eq!(repo.query("(B + B) & (B + C)"), ["B"]);
+    assert_eq!(repo.query("first(A + A)"), ["A"]);
+    assert_eq!(repo.query("first(B + B + A)"), ["B"]);
+    assert_eq!(repo.query("last(C + C)"), ["C"]);
+    assert_eq!(repo.query("last(A + C + A)"), ["A"]);
+    assert_eq!(repo.query("gca(B, B + B)"), ["B"]);
+    assert_eq!(repo.query_with_alias_config("twice(B)"), ["B"]);
+    assert_eq!(repo.query_with_alias_config("overlap(B)"), ["C", "B", "A"]);
+
+    assert_eq!(repo.revs("A + A")?.count()?, 1);
+    assert_eq!(repo.count("A + A")?, 1);
+    assert_eq!(repo.count("A:C + desc(B)")?, 3);
+    assert_eq!(repo.count("none()")?, 0);
+    assert_eq!(repo.revs("A:C + B:C + C")?.count()?, 3);
+    assert_eq!(repo.anyrevs("twice(A + B)")?.count()?, 2);
+    Ok(())
 }
 
 #[test]
-fn test_ext() {
-    use crate::ext::OidExt;
+fn test_merge_streams() -> crate::Result<()> {
     use crate::ext::OidIterExt;
-    use crate::ext::SetExt;
-    use crate::ext::VertexExt;
-    use gitdag::git2::Oid;
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C--D--E--F");
+    repo.commit("P", &["A"], &[]);
+    repo.commit("Q", &["A"], &[]);
+    // Y is a child of X, but has an older author date.
+    {
+        let git_repo = repo.git_repo();
+        let tree = git_repo.find_tree(git_repo.treebuilder(None)?.write()?)?;
+        let mut parents = Vec::new();
+        for &(name, epoch) in [("X", 100), ("Y", 50), ("Z", 75)].iter() {
+            let sig = crate::git2::Signature::new(
+                name,
+                "test@example.com",
+                &crate::git2::Time::new(epoch, 0),
+            )?;
+            let parent_refs: Vec<_> = parents.iter().collect();
+            let oid = git_repo.commit(None, &sig, &sig, name, &tree, &parent_refs)?;
+            git_repo.reference(&format!("refs/heads/{}", name), oid, true, "commit")?;
+            parents = match name {
+                "X" => vec![git_repo.find_commit(oid)?],
+                _ => Vec::new(),
+            };
+        }
+    }
+    repo.reload();
 
-    let oid = Oid::zero();
-    let v = oid.to_vertex();
-    assert_eq!(oid, v.to_oid().unwrap());
+    let merged = |asts: &[&str]| -> Vec<String> {
+        let oids = repo
+            .merge_streams(asts.iter().cloned())
+            .unwrap()
+            .collect::<crate::Result<Vec<_>>>()
+            .unwrap();
+        repo.desc_set(&oids.to_set())
+    };
+    assert_eq!(
+        merged(&["A + C + E", "B + D + F"]),
+        ["F", "E", "D", "C", "B", "A"]
+    );
+    assert_eq!(merged(&["A + C + E", "C + D"]), ["E", "D", "C", "A"]);
+    assert_eq!(merged(&["none()", "B"]), ["B"]);
+    assert!(merged(&[]).is_empty());
 
-    let oid2 = {
-        let mut bytes = oid.as_bytes().to_vec();
-        bytes[0] ^= 1;
-        Oid::from_bytes(&bytes).unwrap()
+    // Same author date. Follow the order of the input.
+    assert_eq!(merged(&["P", "Q"]), ["P", "Q"]);
+    assert_eq!(merged(&["Q", "P"]), ["Q", "P"]);
+
+    // Author dates disagree with the DAG order. Each set keeps its DAG order.
+    assert_eq!(repo.query("X + Y"), ["Y", "X"]);
+    assert_eq!(merged(&["X + Y", "Z"]), ["Z", "Y", "X"]);
+    assert_eq!(merged(&["Z", "X + Y"]), ["Z", "Y", "X"]);
+    Ok(())
+}
+
+#[test]
+fn test_repo_builder() -> crate::Result<()> {
+    use crate::git2;
+    use crate::Repo;
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C");
+    let git_dir = repo.git_repo().path().to_path_buf();
+
+    // Default options.
+    let built = Repo::builder()
+        .git_repo(Box::new(git2::Repository::open(&git_dir)?))
+        .build()?;
+    assert_eq!(built.main_branch_name(), "refs/remotes/origin/master");
+    assert_eq!(built.revs("all()")?.count()?, 3);
+
+    // Custom dag path and main branch.
+    let dag_dir = tempfile::tempdir().unwrap();
+    let built = Repo::builder()
+        .git_repo(Box::new(git2::Repository::open(&git_dir)?))
+        .dag_path(dag_dir.path())
+        .main_branch("refs/heads/C")
+        .build()?;
+    assert_eq!(built.main_branch_name(), "refs/heads/C");
+    assert_eq!(built.revs("all()")?.count()?, 3);
+    assert!(std::fs::read_dir(dag_dir.path()).unwrap().count() > 0);
+
+    // Main branch from config.
+    repo.set_config("revs.main-branch", "refs/heads/B");
+    let built = Repo::builder()
+        .git_repo(Box::new(git2::Repository::open(&git_dir)?))
+        .build()?;
+    assert_eq!(built.main_branch_name(), "refs/heads/B");
+    Ok(())
+}
+
+#[test]
+fn test_open_with_options() -> crate::Result<()> {
+    use crate::git2;
+    use crate::OpenOptions;
+    use crate::Repo;
+    let mut test_repo = TestRepo::new();
+    test_repo.drawdag("A--B");
+    let b = test_repo.query_single_oid("B");
+
+    // Fetch into a repo without the default index.
+    let dir = tempfile::tempdir().unwrap();
+    let git_repo = git2::Repository::init(dir.path())?;
+    let git_dir = git_repo.path().to_path_buf();
+    let url = test_repo.git_repo().path().to_str().unwrap();
+    let mut remote = git_repo.remote_anonymous(url)?;
+    remote.fetch(&["refs/heads/*:refs/heads/*"], None, None)?;
+    drop(remote);
+
+    let dag_dir = tempfile::tempdir().unwrap();
+    let options = OpenOptions {
+        dag_path: Some(dag_dir.path().to_path_buf()),
     };
-    let oid_list = [oid, oid2];
-    let set = oid_list.to_vec().to_set();
+    let repo = Repo::open_from_repo_with_options(Box::new(git_repo), options)?;
+    assert_eq!(repo.revs("all()")?.count()?, 2);
+    assert!(repo.set_contains(&repo.revs("B")?, b)?);
+    assert!(std::fs::read_dir(dag_dir.path()).unwrap().count() > 0);
+    assert!(!git_dir.join("dag").exists());
+    Ok(())
+}
+
+#[test]
+fn test_bare_repo_head() -> crate::Result<()> {
+    use crate::git2;
+    use crate::Error;
+    use crate::Repo;
+    use crate::SetExt;
+    let dir = tempfile::tempdir().unwrap();
+    let git_repo = git2::Repository::init_bare(dir.path())?;
+    let sig = git2::Signature::new("A", "test@example.com", &git2::Time::new(0, 0))?;
+    let tree_id = git_repo.treebuilder(None)?.write()?;
+    let tree = git_repo.find_tree(tree_id)?;
+    let oid = git_repo.commit(Some("refs/heads/main"), &sig, &sig, "A", &tree, &[])?;
+    git_repo.set_head("refs/heads/nonexistent")?;
+
+    // HEAD points to a missing branch.
+    let repo = Repo::open_from_repo(Box::new(git2::Repository::open(dir.path())?))?;
+    assert!(repo.git_repo().is_bare());
+    match repo.revs(".") {
+        Err(Error::UnbornHead(name)) => assert_eq!(name, "refs/heads/nonexistent"),
+        _ => panic!("expect UnbornHead error"),
+    }
+    assert_eq!(repo.revs("main")?.to_oids()?.next().unwrap()?, oid);
+
+    // HEAD points to an existing branch.
+    git_repo.set_head("refs/heads/main")?;
+    let repo = Repo::open_from_repo(Box::new(git2::Repository::open(dir.path())?))?;
+    for name in [".", "@", "HEAD"] {
+        assert_eq!(repo.revs(name)?.to_oids()?.next().unwrap()?, oid);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_revs_capped() -> crate::Result<()> {
+    use crate::Expr;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    let mut repo = TestRepo::new();
+    repo.drawdag("A1--B2--C1--D2--E1--F2");
+
+    let (set, truncated) = repo.revs_capped("desc(1)", 2)?;
+    assert_eq!(repo.desc_set(&set), ["E1", "C1"]);
+    assert!(truncated);
+    let (set, truncated) = repo.revs_capped("desc(1)", 3)?;
+    assert_eq!(repo.desc_set(&set), ["E1", "C1", "A1"]);
+    assert!(!truncated);
+    let (set, truncated) = repo.revs_capped("desc(2)", 10)?;
+    assert_eq!(set.count()?, 3);
+    assert!(!truncated);
+    let (set, truncated) = repo.revs_capped("all()", 0)?;
+    assert!(set.is_empty()?);
+    assert!(truncated);
+
+    // Stop iterating a lazy set early.
+    let iterated = Arc::new(AtomicUsize::new(0));
+    let lazy = {
+        let iterated = iterated.clone();
+        let names: Vec<_> = repo.revs("all()")?.iter()?.collect();
+        Set::from_iter(names.into_iter().inspect(move |_| {
+            iterated.fetch_add(1, Ordering::SeqCst);
+        }))
+    };
+    let (set, truncated) = repo.revs_capped(Expr::Inlined(lazy), 2)?;
+    assert_eq!(set.count()?, 2);
+    assert!(truncated);
+    assert_eq!(iterated.load(Ordering::SeqCst), 3);
+    Ok(())
+}
+
+#[test]
+fn test_revs_async() -> crate::Result<()> {
+    use std::future::Future;
+    use std::sync::Arc;
+    use std::task::Context;
+    use std::task::Poll;
+    use std::task::Wake;
+    use std::thread::Thread;
+
+    // A minimal executor.
+    struct ThreadWaker(Thread);
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = Box::pin(future);
+        let waker = Arc::new(ThreadWaker(std::thread::current())).into();
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => std::thread::park(),
+            }
+        }
+    }
+
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C");
+    let future = repo.revs_async("desc(B)::")?;
+    assert_eq!(repo.desc_set(&block_on(future)?), ["C", "B"]);
+    let future = repo.revs_async("C^ + nonexistent")?;
+    assert!(block_on(future).is_err());
+    assert!(repo.revs_async("(").is_err());
+
+    // The evaluation uses the commit graph index and options of the repo.
+    let git_dir = repo.git_repo().path().to_path_buf();
+    let mut opened =
+        crate::Repo::open_from_repo(Box::new(crate::git2::Repository::open(&git_dir)?))?;
+    opened.set_now(Some(3));
+    repo.drawdag("C--D");
+    assert!(opened.index_is_stale()?);
+    let future = opened.revs_async("all()")?;
+    assert_eq!(repo.desc_set(&block_on(future)?), ["C", "B", "A"]);
+    let code = r#"date("since 1s ago")"#;
+    let future = opened.revs_async(code)?;
+    assert_eq!(repo.desc_set(&block_on(future)?), ["C"]);
+    assert_eq!(repo.desc_set(&opened.revs(code)?), ["C"]);
+
+    // Many evaluations can be queued.
+    let futures = (0..20)
+        .map(|_| opened.revs_async("desc(B)::"))
+        .collect::<crate::Result<Vec<_>>>()?;
+    for future in futures {
+        assert_eq!(repo.desc_set(&block_on(future)?), ["C", "B"]);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_visible_descendants() {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C--D");
+    repo.amend("refs/heads/B");
+    repo.amend("refs/heads/D");
     assert_eq!(
-        oid_list.to_vec(),
-        set.to_oids()
-            .unwrap()
-            .collect::<crate::Result<Vec<_>>>()
-            .unwrap()
+        repo.query("descendants(A)"),
+        ["D_new", "B_new", "D", "C", "B", "A"]
+    );
+    assert_eq!(
+        repo.query("visibledescendants(A)"),
+        ["D_new", "B_new", "C", "A"]
+    );
+    assert_eq!(repo.query("visibledescendants(C)"), ["D_new", "C"]);
+    assert_eq!(repo.query("visibledescendants(B_old)"), ["D_new", "C"]);
+    assert_eq!(
+        repo.query("visibledescendants(A)"),
+        repo.query("descendants(A) - obsolete()")
     );
+    assert_eq!(repo.query("orphan()"), ["D_new", "C"]);
 }
 
 #[test]
-fn test_ast_macro() {
+fn test_main_branch() -> crate::Result<()> {
+    use crate::git2;
+    use crate::Repo;
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C--D");
+    assert!(repo.revs("main()").is_err());
+    assert!(repo.query("present(@main)").is_empty());
+
+    repo.add_ref("refs/remotes/origin/main", repo.query_single_oid("B"));
+    assert_eq!(repo.query("main()"), ["B"]);
+    assert_eq!(repo.query("@main"), ["B"]);
+    assert_eq!(repo.query("only(D, @main)"), ["D", "C"]);
+
+    repo.add_ref("refs/remotes/origin/master", repo.query_single_oid("A"));
+    assert_eq!(repo.query("@main"), ["A"]);
+
+    repo.set_config("revs.main-branch", "refs/heads/C");
+    repo.reload();
+    assert_eq!(repo.query("@main"), ["C"]);
+    assert_eq!(repo.query("draft() - ::@main"), ["D"]);
+
+    let git_dir = repo.git_repo().path().to_path_buf();
+    let built = Repo::builder()
+        .git_repo(Box::new(git2::Repository::open(&git_dir)?))
+        .main_branch("refs/heads/D")
+        .build()?;
+    assert_eq!(repo.desc_set(&built.revs("main()")?), ["D"]);
+    Ok(())
+}
+
+#[test]
+fn test_heads_roots_of_filters() {
+    let mut repo = TestRepo::new();
+    repo.drawdag(
+        r#"
+    A1--B1--C2--D1--E2
+          \
+           F1--G1--H2"#,
+    );
+    assert_eq!(repo.query("desc(1)"), ["G1", "F1", "D1", "B1", "A1"]);
+    assert_eq!(repo.query("heads(desc(1))"), ["G1", "D1"]);
+    assert_eq!(repo.query("roots(desc(1))"), ["D1", "A1"]);
+    assert_eq!(repo.query("heads(desc(2))"), ["H2", "E2", "C2"]);
+    assert_eq!(repo.query("roots(desc(2))"), ["H2", "E2", "C2"]);
+    assert_eq!(repo.query("heads(author(F1) + author(D1))"), ["F1", "D1"]);
+    assert_eq!(repo.query("heads(desc(1) & F1::)"), ["G1"]);
+    assert_eq!(repo.query("roots(desc(1) - A1)"), ["D1", "B1"]);
+    assert!(repo.query("heads(desc(3))").is_empty());
+}
+
+#[test]
+fn test_lazy_filter_scan() -> crate::Result<()> {
     use crate::ast;
-    let f = |e| format!("{:?}", e);
-    assert_eq!(f(ast!("foo")), "foo");
-    assert_eq!(f(ast!(parents("foo"))), "parents(foo)");
-    assert_eq!(f(ast!(draft())), "draft()");
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C--D--E--F--G--H--I--J");
+
+    // Taking the first match only checks commits until the match.
+    let checked = Arc::new(AtomicUsize::new(0));
+    let set = {
+        let checked = checked.clone();
+        crate::eval::filter_set(&repo, move |commit| {
+            checked.fetch_add(1, Ordering::SeqCst);
+            commit.author().name() == Some("H")
+        })?
+    };
+    assert_eq!(repo.desc_set(&repo.revs(ast!(first({ set })))?), ["H"]);
+    assert_eq!(checked.load(Ordering::SeqCst), 3);
+
+    assert_eq!(repo.query("first(author(H))"), ["H"]);
+    assert_eq!(repo.query("first(desc(C) + desc(B))"), ["C"]);
+    Ok(())
+}
+
+#[test]
+fn test_parallel_filter_scan() -> crate::Result<()> {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    let mut repo = TestRepo::new();
+    let names: Vec<String> = (0..300).map(|i| format!("N{}", i)).collect();
+    repo.drawdag(&names.join("--"));
+
+    let threads = Arc::new(Mutex::new(HashSet::new()));
+    let set = {
+        let threads = threads.clone();
+        crate::eval::filter_set(&repo, move |commit| {
+            threads.lock().unwrap().insert(std::thread::current().id());
+            commit.summary().unwrap().starts_with("N1")
+        })?
+    };
+    let expected: Vec<String> = names
+        .iter()
+        .rev()
+        .filter(|n| n.starts_with("N1"))
+        .cloned()
+        .collect();
+    assert_eq!(repo.desc_set(&set), expected);
+    let parallelism = std::thread::available_parallelism().map_or(1, |n| n.get());
+    assert_eq!(threads.lock().unwrap().len() > 1, parallelism > 1);
+    assert_eq!(repo.query("desc(N1)").len(), 111);
+    Ok(())
+}
+
+#[test]
+fn test_git_repo_pool() -> crate::Result<()> {
+    use std::sync::Arc;
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C");
+    let pool = repo.git_repo_pool()?;
+    assert!(Arc::ptr_eq(&pool, &repo.git_repo_pool()?));
+
+    // Filters evaluated one by one reuse the same handle.
     assert_eq!(
-        f(ast!(union(draft(), public()))),
-        "union(draft(), public())"
+        repo.query("author(B) + desc(C) + committer(A)"),
+        ["B", "C", "A"]
     );
+    assert_eq!(pool.lock().unwrap().len(), 1);
 
-    let name = "foo";
+    // Reloading drops idle handles.
+    crate::Repo::reload(&mut repo)?;
+    assert!(pool.lock().unwrap().is_empty());
+    assert_eq!(repo.query("author(B)"), ["B"]);
+    assert_eq!(pool.lock().unwrap().len(), 1);
+
+    // Failing to open a handle is an error, not an empty result.
+    let set = repo.revs("author(B)")?;
+    let set2 = repo.revs("author(C)")?;
+    let b = repo.query_single_oid("B").to_vertex();
+    pool.lock().unwrap().clear();
+    let git_dir = repo.git_repo().path().to_path_buf();
+    let moved_dir = git_dir.with_file_name("moved");
+    std::fs::rename(&git_dir, &moved_dir).unwrap();
+    let iterated: std::result::Result<Vec<_>, _> = set.iter()?.collect();
+    let contains = set2.contains(&b);
+    std::fs::rename(&moved_dir, &git_dir).unwrap();
+    assert!(iterated.is_err());
+    assert!(contains.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_filter_set_in() -> crate::Result<()> {
+    use crate::eval::filter_set;
+    use crate::eval::filter_set_in;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    let mut repo = TestRepo::new();
+    repo.drawdag(
+        r#"
+    A--B--C--D--E
+        \
+         F--G--H"#,
+    );
+    let odd = |commit: &crate::git2::Commit| "ACEGI".contains(commit.summary().unwrap());
+
+    // Scoped filtering matches filtering all commits then intersecting.
+    for code in ["all()", "B::", "F:H", "A + D", "none()"] {
+        let base = repo.revs(code)?;
+        let scoped = filter_set_in(&repo, Some(base.clone()), odd)?;
+        let full = filter_set(&repo, odd)? & base;
+        assert_eq!(repo.desc_set(&scoped), repo.desc_set(&full));
+    }
+    let scoped = filter_set_in(&repo, None, odd)?;
+    assert_eq!(repo.desc_set(&scoped), ["G", "E", "C", "A"]);
+
+    // Only commits in the base are checked.
+    let checked = Arc::new(AtomicUsize::new(0));
+    let set = {
+        let checked = checked.clone();
+        filter_set_in(&repo, Some(repo.revs("F:H")?), move |commit| {
+            checked.fetch_add(1, Ordering::SeqCst);
+            odd(commit)
+        })?
+    };
+    assert_eq!(repo.desc_set(&set), ["G"]);
+    assert_eq!(checked.load(Ordering::SeqCst), 3);
+    assert!(!repo.set_contains(&set, repo.query_single_oid("C"))?);
+    assert_eq!(checked.load(Ordering::SeqCst), 3);
+    Ok(())
+}
+
+#[test]
+fn test_refs_case_insensitive() {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C");
+    repo.add_ref("refs/heads/main", repo.query_single_oid("C"));
+    repo.add_ref("refs/tags/Release", repo.query_single_oid("B"));
+    repo.add_ref("refs/heads/Topic", repo.query_single_oid("A"));
+    repo.add_ref("refs/heads/topic", repo.query_single_oid("B"));
+
+    assert!(repo.revs(r#"ref("MAIN")"#).is_err());
+    assert!(repo.revs("release").is_err());
+    assert_eq!(repo.query("Topic"), ["A"]);
+
+    repo.set_config("revs.refs-case-insensitive", "true");
+    assert_eq!(repo.query(r#"ref("MAIN")"#), ["C"]);
+    assert_eq!(repo.query("Main"), ["C"]);
+    assert_eq!(repo.query("release"), ["B"]);
+    assert_eq!(repo.query("heads/MAIN"), ["C"]);
+    // Exact matches are preferred.
+    assert_eq!(repo.query("Topic"), ["A"]);
+    assert_eq!(repo.query("topic"), ["B"]);
+    assert!(matches!(
+        repo.revs("TOPIC"),
+        Err(crate::Error::AmbiguousName(..))
+    ));
+    assert!(repo.revs("missing").is_err());
+}
+
+#[test]
+fn test_gca_many() -> crate::Result<()> {
+    use gitdag::dag::DagAlgorithm;
+    let mut repo = TestRepo::new();
+    let n = 100;
+    let chain: Vec<String> = (0..n).map(|i| format!("X{}", i)).collect();
+    let mut ascii = chain.join("--");
+    for i in 0..n {
+        ascii += &format!("\nX{}--Y{}", i, i);
+    }
+    ascii += "\nZ";
+    repo.drawdag(&ascii);
+
+    let all_y = (0..n).map(|i| format!("Y{}", i)).collect::<Vec<_>>();
+    assert_eq!(repo.query(&format!("gca({})", all_y.join("+"))), ["X0"]);
+    assert_eq!(repo.query(&format!("gca({})", all_y.join(","))), ["X0"]);
+    assert_eq!(repo.query("gca(Y50, Y99)"), ["X50"]);
+    assert_eq!(repo.query("gca(desc(Y))"), ["X0"]);
+    assert!(repo.query("gca(desc(Y) + Z)").is_empty());
+    assert!(repo.query("gca(Y1 + Z)").is_empty());
+    assert_eq!(repo.query("gca(Y1)"), ["Y1"]);
+    assert!(repo.query("gca(none())").is_empty());
+
+    let dag = repo.dag();
+    for code in ["X3 + Y5 + Y9", "X5:X9 + Y7", "Y10 + Y20 + X30", "desc(Y)"] {
+        let expected = dag.gca_all(repo.revs(code)?)?;
+        let actual = repo.revs(&format!("gca({})", code) as &str)?;
+        assert_eq!(repo.desc_set(&actual), repo.desc_set(&expected));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_ambiguous_oids() {
+    let mut repo = TestRepo::new();
+    // 17 commits. At least 2 of them share the first hex digit.
+    repo.drawdag("A B C D E F G H I J K L M N O P Q");
+    let hexes: Vec<String> = repo
+        .query("all()")
+        .iter()
+        .map(|name| repo.query_single_oid(name).to_string())
+        .collect();
+    let prefix = (b'0'..=b'9')
+        .chain(b'a'..=b'f')
+        .map(|b| (b as char).to_string())
+        .find(|p| hexes.iter().filter(|h| h.starts_with(p)).count() > 1)
+        .unwrap();
+
+    let err = repo.revs(&prefix as &str).unwrap_err();
+    let oids = err.ambiguous_oids().unwrap();
+    assert!(oids.len() > 1);
+    assert!(oids.iter().all(|oid| oid.to_string().starts_with(&prefix)));
+    assert!(crate::Error::UnresolvedName(prefix)
+        .ambiguous_oids()
+        .is_none());
+}
+
+#[test]
+fn test_filter_intersection_scan() -> crate::Result<()> {
+    use crate::ast;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C--D--E--F--G--H--I--J");
+
+    let checked = Arc::new(AtomicUsize::new(0));
+    let set = {
+        let checked = checked.clone();
+        crate::eval::filter_set(&repo, move |commit| {
+            checked.fetch_add(1, Ordering::SeqCst);
+            commit.author().name() != Some("C")
+        })?
+    };
+    let range = repo.revs("B:E")?;
+
+    // Only commits in the range are checked, regardless of the order.
+    let scoped = repo.revs(ast!(intersection({ set.clone() }, { range.clone() })))?;
+    assert_eq!(repo.desc_set(&scoped), ["E", "D", "B"]);
+    assert_eq!(checked.swap(0, Ordering::SeqCst), 4);
+    let scoped = repo.revs(ast!(intersection({ range }, { set.clone() })))?;
+    assert_eq!(repo.desc_set(&scoped), ["E", "D", "B"]);
+    assert_eq!(checked.swap(0, Ordering::SeqCst), 4);
+
+    // Without the range, all commits are checked.
+    assert_eq!(repo.desc_set(&set).len(), 9);
+    assert_eq!(checked.swap(0, Ordering::SeqCst), 10);
+
+    assert_eq!(repo.query("modifies(C) & B:E"), ["C"]);
+    assert_eq!(repo.query("B:E & desc(C)"), ["C"]);
+    assert_eq!(repo.query("desc(C) & desc(C)"), ["C"]);
+
+    // Builtin filters only scan the other side. The result is the same as
+    // scanning all commits.
+    for (range, filter) in [
+        ("B:E", "author(C)"),
+        ("H:J", "author(D)"),
+        ("B:E", "file(D)"),
+    ] {
+        for code in [
+            format!("{} & {}", range, filter),
+            format!("{} & {}", filter, range),
+        ] {
+            let plan = repo.plan(&code as &str)?;
+            assert!(plan.children.iter().any(|c| c.scoped), "{}", code);
+            let full = repo.revs(filter)? & repo.revs(range)?;
+            assert_eq!(repo.query(&code), repo.desc_set(&full), "{}", code);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_refine() -> crate::Result<()> {
+    use crate::Expr;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C--D--E--F--G--H--I--J");
+    repo.add_ref("refs/remotes/origin/master", repo.query_single_oid("F"));
+
+    let checked = Arc::new(AtomicUsize::new(0));
+    let not_h = {
+        let checked = checked.clone();
+        crate::eval::filter_set(&repo, move |commit| {
+            checked.fetch_add(1, Ordering::SeqCst);
+            commit.author().name() != Some("H")
+        })?
+    };
+
+    // Refining only tests commits in the base.
+    let base = repo.revs("draft()")?;
+    let refined = repo.refine(&base, Expr::from(not_h.clone()))?;
+    assert_eq!(repo.desc_set(&refined), ["J", "I", "G"]);
+    assert_eq!(checked.swap(0, Ordering::SeqCst), 4);
+
+    // Filtering all commits then intersecting gives the same result, but
+    // tests all commits.
+    let base_names = repo.desc_set(&base);
+    let full: Vec<String> = repo
+        .desc_set(&not_h)
+        .into_iter()
+        .filter(|name| base_names.contains(name))
+        .collect();
+    assert_eq!(repo.desc_set(&refined), full);
+    assert_eq!(checked.swap(0, Ordering::SeqCst), 10);
+
+    // Refine again.
+    let refined = repo.refine(&refined, "desc(I) + desc(J)")?;
+    assert_eq!(repo.desc_set(&refined), ["J", "I"]);
+    let refined = repo.refine(&refined, "author(I)")?;
+    assert_eq!(repo.desc_set(&refined), ["I"]);
+    let refined = repo.refine(&refined, "author(J)")?;
+    assert!(repo.desc_set(&refined).is_empty());
+
+    // Builtin filters only test commits in the base.
+    let base = repo.revs("G + I + J")?;
+    let refined = repo.refine(&base, "author(I) + author(A)")?;
+    assert_eq!(repo.desc_set(&refined), ["I"]);
+    let refined = repo.refine(&base, "author(I)")?;
+    assert_eq!(repo.desc_set(&refined), ["I"]);
+    assert!(repo.desc_set(&repo.refine(&base, "file(A)")?).is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_hidden_refs() {
+    let mut repo = TestRepo::new();
+    repo.drawdag(
+        r#"
+    A---B---C
+         \
+          D---E"#,
+    );
+    repo.add_ref("refs/wip/E", repo.query_single_oid("E"));
+    repo.add_ref("refs/hidden/D", repo.query_single_oid("D"));
+    repo.git_repo()
+        .find_reference("refs/heads/E")
+        .unwrap()
+        .delete()
+        .unwrap();
+    repo.reload();
+    assert_eq!(repo.query("head()"), ["E", "C"]);
+    assert_eq!(repo.query("all()"), ["E", "D", "C", "B", "A"]);
+
+    repo.set_config("revs.hiddenrefs", "refs/wip/**");
+    repo.reload();
+    assert_eq!(repo.query("head()"), ["D", "C"]);
+    assert_eq!(repo.query("all()"), ["D", "C", "B", "A"]);
+    assert!(repo.query("children(D)").is_empty());
+    assert_eq!(repo.query("D::"), ["D"]);
+    assert_eq!(repo.query("ref(wip/E)"), ["E"]);
+    assert_eq!(repo.query("ref(\"wip/*\")"), ["E"]);
+
+    // Multiple patterns. D is still visible by "refs/heads/D".
+    let mut config = repo.git_repo().config().unwrap();
+    config
+        .set_multivar("revs.hiddenrefs", "^$", "refs/hidden/*")
+        .unwrap();
+    config
+        .set_multivar("revs.hiddenrefs", "^$", "refs/heads/C")
+        .unwrap();
+    repo.reload();
+    assert_eq!(repo.query("head()"), ["D"]);
+    assert_eq!(repo.query("all()"), ["D", "B", "A"]);
+}
+
+#[test]
+fn test_index_is_stale() -> crate::Result<()> {
+    use crate::git2;
+    use crate::Repo;
+    let repo = TestRepo::new();
+    let git_dir = repo.git_repo().path().to_path_buf();
+    let mut opened = Repo::open_from_repo(Box::new(git2::Repository::open(&git_dir)?))?;
+    assert!(!opened.index_is_stale()?);
+    assert!(!opened.reload_if_stale()?);
+
+    // Add commits and references.
+    let mut repo = repo;
+    repo.drawdag("A--B");
+    assert!(opened.index_is_stale()?);
+    assert!(opened.revs("all()")?.is_empty()?);
+    assert!(opened.reload_if_stale()?);
+    assert!(!opened.index_is_stale()?);
+    assert_eq!(repo.desc_set(&opened.revs("all()")?), ["B", "A"]);
+
+    // Move a reference.
+    repo.add_ref("refs/heads/B", repo.query_single_oid("A"));
+    assert!(opened.index_is_stale()?);
+    opened.reload()?;
+    assert!(!opened.index_is_stale()?);
+    assert_eq!(repo.desc_set(&opened.revs("B")?), ["A"]);
+    assert_eq!(repo.desc_set(&opened.revs("all()")?), ["A"]);
+
+    // Rebuild if stale after opening or reloading.
+    let mut opened = Repo::builder()
+        .git_repo(Box::new(git2::Repository::open(&git_dir)?))
+        .rebuild_if_stale(true)
+        .build()?;
+    assert!(!opened.index_is_stale()?);
+    repo.drawdag("C");
+    assert!(opened.index_is_stale()?);
+    opened.reload()?;
+    assert!(!opened.index_is_stale()?);
+    assert_eq!(repo.desc_set(&opened.revs("all()")?), ["C", "A"]);
+    Ok(())
+}
+
+#[test]
+fn test_sort() {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B");
+    // C, D, E have the same date.
+    repo.commit("D", &["B"], &[]);
+    repo.commit("E", &["B"], &[]);
+    repo.commit("C", &["B"], &[]);
+
+    assert_eq!(repo.query("sort(all(), topo)"), repo.query("all()"));
+    assert_eq!(repo.query("sort(all(), \"-topo\")"), {
+        let mut names = repo.query("all()");
+        names.reverse();
+        names
+    });
+    assert_eq!(repo.query("sort(all(), author)"), ["A", "B", "C", "D", "E"]);
     assert_eq!(
-        f(ast!(union(desc({ name }), author({ "bar" })))),
-        "union(desc(foo), author(bar))"
+        repo.query("sort(all(), \"-author\")"),
+        ["E", "D", "C", "B", "A"]
+    );
+    assert_eq!(
+        repo.query("sort(all(), \"date,author\")"),
+        ["A", "B", "C", "D", "E"]
+    );
+    assert_eq!(
+        repo.query("sort(all(), \"-date,author\")"),
+        ["C", "D", "E", "B", "A"]
+    );
+    assert_eq!(
+        repo.query("sort(all(), \"-date, -author\")"),
+        ["E", "D", "C", "B", "A"]
+    );
+    assert_eq!(
+        repo.query("sort(C + D + E, \"date,topo\")"),
+        repo.query("C + D + E")
+    );
+    assert_eq!(repo.query("first(sort(B::, \"-author\"))"), ["E"]);
+    assert_eq!(
+        repo.query("sort(all(), \"authordate,author\")"),
+        repo.query("sort(all(), \"date,author\")")
     );
 
-    let set = Set::from_static_names(vec!["A".into(), "B".into()]);
-    assert_eq!(f(ast!(parents({ set }))), "parents(<static [A, B]>)")
+    assert_eq!(repo.query("sort(A + B + D, date)"), ["A", "B", "D"]);
+    assert_eq!(repo.query("sort(A + B + D, \"-date\")"), ["D", "B", "A"]);
+    assert!(repo.revs("sort(all(), \"date,size\")").is_err());
+    assert!(repo.revs("sort(all())").is_err());
+}
+
+#[test]
+fn test_min_max() {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B");
+    // C, D, E have the same date.
+    repo.commit("D", &["B"], &[]);
+    repo.commit("E", &["B"], &[]);
+    repo.commit("C", &["B"], &[]);
+
+    assert_eq!(repo.query("min(all())"), ["A"]);
+    assert_eq!(repo.query("max(A + B)"), ["B"]);
+    assert_eq!(repo.query("min(B + C)"), ["B"]);
+    assert!(repo.query("min(A - A)").is_empty());
+    assert!(repo.query("max(A - A)").is_empty());
+
+    // Ties are broken by commit hashes.
+    let mut oids: Vec<_> = ["C", "D", "E"]
+        .iter()
+        .map(|name| repo.query_single_oid(name))
+        .collect();
+    oids.sort();
+    assert_eq!(repo.query_single_oid("min(C + D + E)"), oids[0]);
+    assert_eq!(repo.query_single_oid("max(all())"), oids[2]);
+}
+
+#[test]
+fn test_blame_commits() -> crate::Result<()> {
+    let mut repo = TestRepo::new();
+    repo.commit("A", &[], &[("f", Some("1\n2\n3\n4\n"))]);
+    repo.commit("B", &["A"], &[("f", Some("1\n2b\n3\n4\n"))]);
+    repo.commit("C", &["B"], &[("f", Some("1\n2b\n3\n4c\n5c\n"))]);
+    repo.commit("D", &["C"], &[("g", Some("1\n"))]);
+    repo.git_repo().set_head("refs/heads/D")?;
+
+    let blame = |lines| -> crate::Result<Vec<String>> {
+        Ok(repo.desc_set(&repo.blame_commits("f", lines)?))
+    };
+    assert_eq!(blame(1..=1)?, ["A"]);
+    assert_eq!(blame(1..=3)?, ["B", "A"]);
+    assert_eq!(blame(2..=2)?, ["B"]);
+    assert_eq!(blame(4..=5)?, ["C"]);
+    assert_eq!(blame(1..=5)?, ["C", "B", "A"]);
+    assert_eq!(repo.desc_set(&repo.blame_commits("g", 1..=1)?), ["D"]);
+
+    // Compose with other revsets.
+    let set = repo.blame_commits("f", 1..=5)? & repo.revs("B::")?;
+    assert_eq!(repo.desc_set(&set), ["C", "B"]);
+
+    assert!(repo.blame_commits("missing", 1..=1).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_follow() {
+    let mut repo = TestRepo::new();
+    let content = "1\n2\n3\n4\n5\n";
+    repo.commit("A", &[], &[("old", Some(content)), ("x", Some("x"))]);
+    repo.commit("B", &["A"], &[("old", Some("0\n1\n2\n3\n4\n5\n"))]);
+    repo.commit("C", &["B"], &[("x", Some("x1"))]);
+    repo.commit(
+        "D",
+        &["C"],
+        &[("old", None), ("new", Some("0\n1\n2\n3\n4\n5\n"))],
+    );
+    repo.commit("E", &["D"], &[("new", Some("0\n1\n2\n3\n4\n5\n6\n"))]);
+    repo.commit("F", &["E"], &[("x", Some("x2"))]);
+    // G changes "old" on another branch. It is not an ancestor of the rename.
+    repo.commit("G", &["B"], &[("old", Some(content))]);
+    // H renames "new" to "newer".
+    repo.commit(
+        "H",
+        &["F"],
+        &[("new", None), ("newer", Some("0\n1\n2\n3\n4\n5\n6\n"))],
+    );
+
+    assert_eq!(repo.query("modifies(new)"), ["E", "D"]);
+    assert_eq!(repo.query("follow(new)"), ["H", "E", "D", "B", "A"]);
+    assert_eq!(repo.query("follow(newer)"), ["H", "E", "D", "B", "A"]);
+    assert_eq!(repo.query("follow(old)"), ["G", "D", "B", "A"]);
+    assert_eq!(repo.query("follow(x)"), ["F", "C", "A"]);
+    assert!(repo.query("follow(missing)").is_empty());
+}
+
+#[test]
+fn test_pseudo_refs() {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C--D");
+    let git_dir = repo.git_repo().path().to_path_buf();
+    let write = |name: &str, content: String| std::fs::write(git_dir.join(name), content).unwrap();
+    let hex = |name: &str| repo.query_single_oid(name).to_string();
+
+    for name in ["ORIG_HEAD", "MERGE_HEAD", "FETCH_HEAD", "CHERRY_PICK_HEAD"] {
+        assert!(repo.revs(name).is_err());
+        assert!(repo.query(&format!("present({})", name)).is_empty());
+    }
+
+    write("ORIG_HEAD", format!("{}\n", hex("B")));
+    assert_eq!(repo.query("ORIG_HEAD"), ["B"]);
+    assert_eq!(repo.query("ORIG_HEAD::"), ["D", "C", "B"]);
+    assert_eq!(repo.query("id(ORIG_HEAD)"), ["B"]);
+
+    write("MERGE_HEAD", format!("{}\n{}\n", hex("A"), hex("C")));
+    assert_eq!(repo.query("MERGE_HEAD"), ["C", "A"]);
+
+    write("CHERRY_PICK_HEAD", format!("{}\n", hex("D")));
+    assert_eq!(repo.query("CHERRY_PICK_HEAD"), ["D"]);
+
+    write(
+        "FETCH_HEAD",
+        format!(
+            "{}\t\tbranch 'main' of https://example.com/repo\n\
+             {}\tnot-for-merge\tbranch 'dev' of https://example.com/repo\n",
+            hex("C"),
+            hex("A")
+        ),
+    );
+    assert_eq!(repo.query("FETCH_HEAD"), ["C"]);
+
+    write("ORIG_HEAD", "not a hash\n".to_string());
+    assert!(repo.revs("ORIG_HEAD").is_err());
+}
+
+#[test]
+fn test_previous_checkout() {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C");
+
+    assert!(repo.revs("@{-1}").is_err());
+
+    let hex = |name: &str| repo.query_single_oid(name).to_string();
+    let messages = [
+        "checkout: moving from C to A".to_string(),
+        "checkout: moving from A to B".to_string(),
+        "commit: B2".to_string(),
+        "checkout: moving from B to C".to_string(),
+        format!("checkout: moving from {} to C", hex("A")),
+    ];
+    let git_repo = repo.git_repo();
+    let mut reflog = git_repo.reflog("HEAD").unwrap();
+    let sig = crate::git2::Signature::now("test", "test@example.com").unwrap();
+    for message in &messages {
+        reflog
+            .append(repo.query_single_oid("C"), &sig, Some(message))
+            .unwrap();
+    }
+    reflog.write().unwrap();
+
+    assert_eq!(repo.query("@{-1}"), ["A"]);
+    assert_eq!(repo.query("@{-2}"), ["B"]);
+    assert_eq!(repo.query("@{-3}"), ["A"]);
+    assert_eq!(repo.query("@{-4}"), ["C"]);
+    assert_eq!(repo.query("@{-1} + @{-2}::"), ["C", "B", "A"]);
+    assert_eq!(repo.query("id(\"@{-2}\")"), ["B"]);
+    assert!(repo.revs("@{-5}").is_err());
+    assert!(repo.revs("\"@{-0}\"").is_err());
+    assert!(repo.query("present(@{-5})").is_empty());
+}
+
+#[test]
+fn test_expr_cache() -> crate::Result<()> {
+    use crate::git2;
+    use crate::Repo;
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C");
+    let git_dir = repo.git_repo().path().to_path_buf();
+    let open = |size| -> crate::Result<Repo> {
+        let git_repo = git2::Repository::open(&git_dir)?;
+        Repo::builder()
+            .git_repo(Box::new(git_repo))
+            .expr_cache_size(size)
+            .build()
+    };
+
+    // Disabled by default.
+    let cached = open(0)?;
+    cached.revs("heads(all())")?;
+    assert_eq!(cached.expr_cache_stats(), (0, 0));
+
+    let mut cached = open(2)?;
+    let set1 = cached.revs("heads(all())")?;
+    assert_eq!(cached.expr_cache_stats(), (2, 0));
+    let set2 = cached.revs("heads(all())")?;
+    assert_eq!(cached.expr_cache_stats(), (2, 1));
+    assert_eq!(repo.desc_set(&set1), repo.desc_set(&set2));
+    // Sub-expressions are cached too.
+    assert_eq!(repo.desc_set(&cached.revs("roots(all())")?), ["A"]);
+    assert_eq!(cached.expr_cache_stats(), (2, 2));
+    // Bounded by size. The least recently used "heads(all())" was evicted.
+    // "all()" is still cached.
+    cached.revs("heads(all())")?;
+    assert_eq!(cached.expr_cache_stats(), (2, 3));
+    // Expressions with aliases are cached separately.
+    repo.set_config("revsetalias.all", "A");
+    assert_eq!(repo.desc_set(&cached.anyrevs("heads(all())")?), ["A"]);
+    assert_eq!(repo.desc_set(&cached.revs("heads(all())")?), ["C"]);
+
+    // Reload invalidates the cache.
+    repo.commit("D", &["C"], &[]);
+    assert_eq!(repo.desc_set(&cached.revs("heads(all())")?), ["C"]);
+    cached.reload()?;
+    assert_eq!(repo.desc_set(&cached.revs("heads(all())")?), ["D"]);
+
+    // Expressions depending on HEAD are not cached.
+    let (len, _) = cached.expr_cache_stats();
+    let b = repo.query_single_oid("B");
+    repo.git_repo().set_head_detached(b)?;
+    assert_eq!(repo.desc_set(&cached.revs("parents(.)")?), ["A"]);
+    let c = repo.query_single_oid("C");
+    repo.git_repo().set_head_detached(c)?;
+    assert_eq!(repo.desc_set(&cached.revs("parents(.)")?), ["B"]);
+    assert_eq!(repo.desc_set(&cached.revs("only(HEAD, A)")?), ["C", "B"]);
+    assert_eq!(cached.expr_cache_stats().0, len);
+
+    // Expressions depending on the clock are not cached.
+    cached.set_now(Some(3));
+    assert_eq!(
+        repo.desc_set(&cached.revs(r#"date("since 1s ago") & all()"#)?),
+        ["D", "C"]
+    );
+    assert_eq!(cached.expr_cache_stats().0, 1);
+    cached.set_now(Some(100));
+    assert_eq!(
+        repo.desc_set(&cached.revs(r#"date("since 1s ago") & all()"#)?),
+        Vec::<String>::new()
+    );
+    assert_eq!(cached.expr_cache_stats().0, 1);
+    Ok(())
+}
+
+#[test]
+fn test_work_date() {
+    let mut repo = TestRepo::new();
+    // Dates: A: 0, B1: 1, ..., B5: 5, C: 1, D: 2, M: 6.
+    repo.commit("A", &[], &[]);
+    let mut parent = "A".to_string();
+    for i in 1..=5 {
+        let name = format!("B{}", i);
+        repo.commit(&name, &[&parent], &[]);
+        parent = name;
+    }
+    repo.commit("C", &["A"], &[]);
+    repo.commit("D", &["C"], &[]);
+    repo.commit("M", &["B5", "D"], &[]);
+
+    assert_eq!(repo.query("date(\"since 6 0\") & mergepoint(all())"), ["M"]);
+    assert!(repo.query("workdate(\"since 6 0\")").is_empty());
+    assert_eq!(repo.query("workdate(\"2 0 to 2 0\") & D::"), ["M", "D"]);
+    assert_eq!(
+        repo.query("workdate(\"since 3 0\")"),
+        repo.query("date(\"since 3 0\") - M")
+    );
+}
+
+#[test]
+fn test_create_delete_refs() -> crate::Result<()> {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C");
+    let prefix = "refs/revset-result";
+    repo.add_ref("refs/revset-result/2", repo.query_single_oid("A"));
+
+    let names = repo.create_refs(&repo.revs("B + C")?, "refs/revset-result/")?;
+    assert_eq!(names, ["refs/revset-result/1", "refs/revset-result/3"]);
+    assert!(repo.index_is_stale()?);
+    repo.reload();
+    assert_eq!(repo.query("ref(\"revset-result/*\")"), ["C", "B", "A"]);
+    assert_eq!(repo.query("ref(\"revset-result/1\")"), ["C"]);
+
+    let mut deleted = repo.delete_refs(prefix)?;
+    deleted.sort();
+    assert_eq!(
+        deleted,
+        [
+            "refs/revset-result/1",
+            "refs/revset-result/2",
+            "refs/revset-result/3"
+        ]
+    );
+    repo.reload();
+    assert!(repo.query("present(ref(\"revset-result/*\"))").is_empty());
+    assert!(repo.delete_refs(prefix)?.is_empty());
+
+    assert!(repo.create_refs(&repo.revs("A")?, "revset-result").is_err());
+    assert!(repo.delete_refs("refs/").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_set_from_oids() -> crate::Result<()> {
+    use crate::ast;
+    use crate::git2::Oid;
+    let mut repo = TestRepo::new();
+    repo.drawdag(
+        r#"
+    A---B---C
+         \
+          D---E"#,
+    );
+    let oids = ["A", "E", "C", "E"]
+        .iter()
+        .map(|name| repo.query_single_oid(name));
+    let set = repo.set_from_oids(oids)?;
+    assert_eq!(repo.desc_set(&set), ["E", "C", "A"]);
+    assert_eq!(
+        repo.desc_set(&repo.revs(ast!(heads(ancestors({ set.clone() }))))?),
+        ["E", "C"]
+    );
+    assert_eq!(repo.desc_set(&repo.revs(ast!(gca({ set })))?), ["A"]);
+    assert!(repo.set_from_oids(Vec::new())?.is_empty()?);
+
+    match repo.set_from_oids(vec![Oid::zero()]) {
+        Err(crate::Error::UnresolvedName(name)) => assert_eq!(name, Oid::zero().to_string()),
+        _ => panic!("expect UnresolvedName error"),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_precompute_phases() -> crate::Result<()> {
+    let mut repo = TestRepo::new();
+    repo.drawdag(
+        r#"
+    A---B---C---D
+         \
+          E---F"#,
+    );
+    repo.add_ref("refs/remotes/origin/master", repo.query_single_oid("C"));
+    repo.add_ref("refs/remotes/origin/stable", repo.query_single_oid("E"));
+
+    let git_repo = crate::git2::Repository::open(repo.git_repo().path())?;
+    let precomputed = crate::Repo::builder()
+        .git_repo(Box::new(git_repo))
+        .precompute_phases(true)
+        .build()?;
+    for code in ["public()", "draft()", "draft() + public()"] {
+        let expected = repo.query(code);
+        assert_eq!(repo.desc_set(&precomputed.revs(code)?), expected);
+    }
+    assert_eq!(repo.query("public()"), ["E", "C", "B", "A"]);
+    assert_eq!(repo.query("draft()"), ["D", "F"]);
+    Ok(())
+}
+
+#[test]
+fn test_mutation_worktree_reflog() {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C");
+    // B2 is B amended with a detached HEAD in a linked worktree.
+    let b2 = {
+        let b = repo.git_repo().find_commit(repo.query_single_oid("B"));
+        let b = b.unwrap();
+        b.amend(None, None, None, None, Some("B2"), None).unwrap()
+    };
+    repo.add_ref("refs/heads/B2", b2);
+
+    let worktree_dir = tempfile::tempdir().unwrap();
+    let add_worktree = |name: &str| {
+        let c = repo.query_single_oid("C");
+        let ref_name = format!("refs/heads/{}", name);
+        let reference = repo.git_repo().reference(&ref_name, c, true, "worktree");
+        let reference = reference.unwrap();
+        let mut opts = crate::git2::WorktreeAddOptions::new();
+        opts.reference(Some(&reference));
+        let path = worktree_dir.path().join(name);
+        let worktree = repo.git_repo().worktree(name, &path, Some(&opts));
+        crate::git2::Repository::open_from_worktree(&worktree.unwrap()).unwrap()
+    };
+    let worktree_repo = add_worktree("wt1");
+    // A worktree without rewrites in its reflog.
+    add_worktree("wt2");
+    assert!(repo.query("obsolete()").is_empty());
+
+    let mut reflog = worktree_repo.reflog("HEAD").unwrap();
+    let sig = crate::git2::Signature::now("test", "test@example.com").unwrap();
+    let b = repo.query_single_oid("B");
+    reflog
+        .append(b, &sig, Some("checkout: moving from C to B"))
+        .unwrap();
+    reflog.append(b2, &sig, Some("commit (amend): B2")).unwrap();
+    reflog.write().unwrap();
+    repo.reload();
+
+    assert_eq!(repo.query("obsolete()"), ["B"]);
+    assert_eq!(repo.query("successors(B)"), ["B2", "B"]);
+    assert_eq!(repo.query("predecessors(B2)"), ["B2", "B"]);
+}
+
+#[test]
+fn test_mutation_rewrite_stack() {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C");
+    // Reword B, and rebase C onto the new B.
+    let c2 = {
+        let git_repo = repo.git_repo();
+        let b = git_repo.find_commit(repo.query_single_oid("B")).unwrap();
+        let c = git_repo.find_commit(repo.query_single_oid("C")).unwrap();
+        let b2 = b.amend(None, None, None, None, Some("B2"), None).unwrap();
+        let b2 = git_repo.find_commit(b2).unwrap();
+        let (sig, tree) = (c.author(), c.tree().unwrap());
+        git_repo
+            .commit(None, &sig, &sig, "C2", &tree, &[&b2])
+            .unwrap()
+    };
+    // Keep the original C visible.
+    repo.add_ref("refs/heads/C_old", repo.query_single_oid("C"));
+    repo.git_repo()
+        .reference("refs/heads/C", c2, true, "rebase -i (finish): refs/heads/C")
+        .unwrap();
+    repo.reload();
+
+    assert_eq!(repo.query("obsolete()"), ["C", "B"]);
+    assert_eq!(repo.query("successors(B)"), ["B2", "B"]);
+    assert_eq!(repo.query("successors(C_old)"), ["C2", "C"]);
+    assert!(repo.query("orphan()").is_empty());
+}
+
+#[test]
+fn test_mutation_squash() {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C");
+    // Squash C into B, as `rebase -i` does.
+    let (b, c) = (repo.query_single_oid("B"), repo.query_single_oid("C"));
+    let bc = {
+        let git_repo = repo.git_repo();
+        let a = git_repo.find_commit(repo.query_single_oid("A")).unwrap();
+        let c = git_repo.find_commit(c).unwrap();
+        let (sig, tree) = (c.author(), c.tree().unwrap());
+        git_repo
+            .commit(None, &sig, &sig, "BC", &tree, &[&a])
+            .unwrap()
+    };
+    let mut reflog = repo.git_repo().reflog("HEAD").unwrap();
+    let sig = crate::git2::Signature::now("test", "test@example.com").unwrap();
+    reflog.append(b, &sig, Some("rebase -i (pick): B")).unwrap();
+    reflog
+        .append(bc, &sig, Some("rebase -i (squash): BC"))
+        .unwrap();
+    reflog.write().unwrap();
+    repo.add_ref("refs/heads/C_old", c);
+    repo.git_repo()
+        .reference("refs/heads/C", bc, true, "rebase -i (finish): refs/heads/C")
+        .unwrap();
+    repo.reload();
+
+    assert_eq!(repo.query("obsolete()"), ["C", "B"]);
+    assert_eq!(repo.query("predecessors(C)"), ["BC", "C", "B"]);
+    assert_eq!(repo.query("successors(B)"), ["BC", "B"]);
+
+    // Resetting to a version rewritten elsewhere.
+    let d = repo.commit("D", &["A"], &[]);
+    let d2 = repo.reword("D", "D2");
+    repo.add_ref("refs/heads/E", d);
+    assert_eq!(repo.query("predecessors(D)"), ["D2"]);
+    repo.git_repo()
+        .reference("refs/heads/E", d2, true, "reset: moving to D")
+        .unwrap();
+    repo.reload();
+    assert_eq!(repo.query("predecessors(D)"), ["D2", "D"]);
+}
+
+#[test]
+fn test_mutation_notes() {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C");
+    repo.commit("B2", &["A"], &[]);
+    repo.amend("refs/heads/C");
+
+    let b = repo.query_single_oid("B");
+    let b2 = repo.query_single_oid("B2");
+    let sig = crate::git2::Signature::now("test", "test@example.com").unwrap();
+    repo.git_repo()
+        .note(
+            &sig,
+            &sig,
+            Some("refs/notes/mutation"),
+            b2,
+            &format!("{}\n", b),
+            false,
+        )
+        .unwrap();
+    repo.reload();
+
+    assert_eq!(repo.query("obsolete()"), ["C"]);
+    assert_eq!(repo.query("successors(B)"), ["B"]);
+
+    repo.set_config("revs.mutation-source", "notes");
+    repo.reload();
+    assert_eq!(repo.query("obsolete()"), ["B"]);
+    assert_eq!(repo.query("successors(B)"), ["B2", "B"]);
+    assert_eq!(repo.query("predecessors(B2)"), ["B2", "B"]);
+    assert_eq!(repo.query("predecessors(C)"), ["C_new"]);
+
+    repo.set_config("revs.mutation-source", "both");
+    repo.reload();
+    assert_eq!(repo.query("obsolete()"), ["C", "B"]);
+
+    repo.set_config("revs.mutation-source", "foo");
+    repo.reload();
+    assert!(repo.revs("obsolete()").is_err());
+}
+
+#[test]
+fn test_root_predecessor() {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C");
+    // Keep the original B visible.
+    repo.add_ref("refs/heads/orig", repo.query_single_oid("B"));
+    repo.amend("refs/heads/B");
+    repo.amend("refs/heads/B");
+    assert_eq!(repo.query("B"), ["B_new_new"]);
+    assert_eq!(repo.query("predecessors(B)"), ["B_new_new", "B_new", "B"]);
+    assert_eq!(repo.query("rootpredecessor(B)"), ["B"]);
+    assert_eq!(repo.query("rootpredecessor(B_old)"), ["B"]);
+    assert_eq!(repo.query("rootpredecessor(orig)"), ["B"]);
+    assert_eq!(repo.query("rootpredecessor(A + C)"), ["C", "A"]);
+    assert_eq!(repo.query("rootpredecessor(A + B)"), ["B", "A"]);
+
+    // A commit folded from multiple commits.
+    repo.commit("S", &["A"], &[]);
+    let sig = crate::git2::Signature::now("test", "test@example.com").unwrap();
+    let note = format!(
+        "{}\n{}\n",
+        repo.query_single_oid("C"),
+        repo.query_single_oid("B")
+    );
+    let s = repo.query_single_oid("S");
+    repo.git_repo()
+        .note(&sig, &sig, Some("refs/notes/mutation"), s, &note, false)
+        .unwrap();
+    repo.set_config("revs.mutation-source", "both");
+    repo.reload();
+    assert_eq!(repo.query("rootpredecessor(S)"), ["C", "B"]);
+}
+
+#[test]
+fn test_mutation_dag() -> crate::Result<()> {
+    use gitdag::dag::DagAlgorithm;
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B");
+    let a = repo.query_single_oid("A");
+    let b = repo.query_single_oid("B");
+    repo.amend("refs/heads/B");
+    let b1 = repo.query_single_oid("B");
+    repo.amend("refs/heads/B");
+    let b2 = repo.query_single_oid("B");
+    assert_eq!(repo.mutation_dag()?.all()?.count()?, 3);
+    assert_eq!(repo.predecessors_of(b2)?, [b1]);
+    assert_eq!(repo.successors_of(b1)?, [b2]);
+    assert_eq!(repo.successors_of(b)?, [b1]);
+    assert!(repo.predecessors_of(b)?.is_empty());
+    assert!(repo.successors_of(a)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_divergent() {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C");
+    repo.amend("refs/heads/C");
+    assert!(repo.query("divergent()").is_empty());
+
+    // B is amended in two branches.
+    repo.add_ref("refs/heads/B2", repo.query_single_oid("B"));
+    repo.amend_with_message("refs/heads/B", "B1");
+    repo.amend_with_message("refs/heads/B2", "B2");
+    assert_eq!(repo.query("divergent()"), ["B2", "B1"]);
+    assert_eq!(repo.query("B_old"), ["B"]);
+    assert_eq!(repo.query("orphan()"), ["C_new"]);
+
+    // Amending one side again does not resolve the divergence.
+    repo.amend_with_message("refs/heads/B2", "B3");
+    assert_eq!(repo.query("divergent()"), ["B3", "B1"]);
+}
+
+#[test]
+fn test_unreachable() -> crate::Result<()> {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C");
+    assert!(repo.query("unreachable()").is_empty());
+
+    // C was indexed before the reset.
+    let b = repo.query_single_oid("B");
+    repo.add_ref("refs/heads/C", b);
+    assert_eq!(repo.query("unreachable()"), ["C"]);
+    assert_eq!(repo.query("secret()"), ["C"]);
+    assert_eq!(repo.query("all()"), ["B", "A"]);
+
+    // Commits referred by hidden or other non-branch references are not
+    // secret.
+    repo.set_config("revs.hiddenrefs", "refs/wip/**");
+    let c = repo.query_single_oid("unreachable()");
+    for name in ["refs/wip/C", "refs/custom/C", "refs/remotes/origin/C"] {
+        repo.add_ref(name, c);
+        assert!(repo.query("unreachable()").is_empty());
+        assert!(repo.query("secret()").is_empty());
+        repo.git_repo().find_reference(name)?.delete()?;
+        repo.reload();
+        assert_eq!(repo.query("secret()"), ["C"]);
+    }
+
+    // D is only referred by the reflog.
+    let git_repo = crate::git2::Repository::open(repo.git_repo().path())?;
+    let d = {
+        let commit = git_repo.find_commit(b)?;
+        let sig = commit.author();
+        let tree = commit.tree()?;
+        git_repo.commit(None, &sig, &sig, "D", &tree, &[&commit])?
+    };
+    git_repo.reference("refs/heads/tmp", d, true, "commit: D")?;
+    git_repo.reference("refs/heads/tmp", b, true, "reset: moving to B")?;
+    repo.reload();
+    assert_eq!(repo.query("unreachable()"), ["C"]);
+
+    let reopened = crate::Repo::builder()
+        .git_repo(Box::new(git_repo))
+        .index_reflogs(true)
+        .build()?;
+    assert_eq!(repo.desc_set(&reopened.revs("unreachable()")?), ["D", "C"]);
+    assert_eq!(
+        repo.desc_set(&reopened.revs("parents(unreachable())")?),
+        ["B"]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_date_named_periods() -> crate::Result<()> {
+    let mut repo = TestRepo::new();
+    // Noon in UTC. Far from day boundaries in other timezones.
+    let commits = [
+        ("Y", "2020-06-03 12:00:00"),
+        ("M", "2021-02-10 12:00:00"),
+        ("L", "2021-03-11 12:00:00"),
+        ("W", "2021-03-16 12:00:00"),
+    ];
+    {
+        let git_repo = repo.git_repo();
+        let tree = git_repo.find_tree(git_repo.treebuilder(None)?.write()?)?;
+        for (name, date) in commits.iter() {
+            let epoch = HgTime::parse(&format!("{} +0000", date)).unwrap().unixtime;
+            let sig = crate::git2::Signature::new(
+                name,
+                "test@example.com",
+                &crate::git2::Time::new(epoch, 0),
+            )?;
+            let oid = git_repo.commit(None, &sig, &sig, name, &tree, &[])?;
+            git_repo.reference(&format!("refs/heads/{}", name), oid, true, "commit")?;
+        }
+    }
+    repo.reload();
+
+    // Wednesday.
+    let now = HgTime::parse("2021-03-17 12:00:00 +0000").unwrap().unixtime;
+    repo.set_now(Some(now));
+    assert_eq!(repo.query(r#"date("thisweek")"#), ["W"]);
+    assert_eq!(repo.query(r#"date("thismonth")"#), ["W", "L"]);
+    assert_eq!(repo.query(r#"date("thisyear")"#), ["W", "M", "L"]);
+    assert!(repo.query(r#"date("today")"#).is_empty());
+    assert_eq!(repo.query(r#"committerdate("yesterday")"#), ["W"]);
+    assert!(matches!(
+        repo.revs(r#"date("thisdecade")"#),
+        Err(crate::Error::InvalidDate(name)) if name == "thisdecade"
+    ));
+    assert!(matches!(
+        repo.revs(r#"date("not a date")"#),
+        Err(crate::Error::InvalidDate(_))
+    ));
+
+    // Relative dates follow the clock.
+    assert_eq!(repo.query(r#"date("since 3 days ago")"#), ["W"]);
+    assert_eq!(repo.query(r#"date("-3")"#), ["W"]);
+    assert_eq!(repo.query(r#"date("before 30 days ago")"#), ["Y", "M"]);
+    assert_eq!(
+        repo.query(r#"date("40 days ago to 5 days ago")"#),
+        ["M", "L"]
+    );
+    assert_eq!(repo.query(r#"date("before now")"#).len(), 4);
+    let day = 24 * 60 * 60;
+    repo.set_now(Some(now + 7 * day));
+    assert_eq!(repo.query(r#"date("thisweek")"#), Vec::<String>::new());
+    assert_eq!(
+        repo.query(r#"date("since 7 days ago")"#),
+        Vec::<String>::new()
+    );
+    assert_eq!(repo.query(r#"date("since 8 days ago")"#), ["W"]);
+    repo.set_now(Some(now + 300 * day));
+    assert_eq!(repo.query(r#"date("thisyear")"#), Vec::<String>::new());
+    Ok(())
+}
+
+#[test]
+fn test_recent() {
+    let mut repo = TestRepo::new();
+    let chain: Vec<String> = (0..50).map(|i| format!("C{}", i)).collect();
+    repo.drawdag(&format!("{}\nC45--D1--D2\nC40--E1", chain.join("--")));
+
+    assert!(repo.query("recent(0)").is_empty());
+    assert_eq!(repo.query("recent(1)"), ["E1", "D2", "C49"]);
+    assert_eq!(
+        repo.query("recent(2)"),
+        ["E1", "D2", "D1", "C49", "C48", "C40"]
+    );
+    assert_eq!(
+        repo.query("recent(3)"),
+        repo.query("recent(2) + C47 + C45 + C39")
+    );
+    assert_eq!(repo.query("recent(4)").len(), 12);
+    assert_eq!(repo.query("recent(1000)"), repo.query("all()"));
+    assert!(repo.revs("recent(x)").is_err());
+}
+
+#[test]
+fn test_grep() {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A B C");
+    repo.reword("A", "Fix parser\n\nFixes #12");
+    repo.reword("B", "Fixes #x");
+    repo.reword("C", "Update docs");
+
+    assert_eq!(
+        repo.query(r#"grep("Fixes #\\d+")"#),
+        ["Fix parser\n\nFixes #12"]
+    );
+    assert_eq!(
+        repo.query(r#"grep("^Fix")"#),
+        ["Fixes #x", "Fix parser\n\nFixes #12"]
+    );
+    assert_eq!(repo.query(r#"grep("(?i)^update")"#), ["Update docs"]);
+    assert!(repo.query("grep(nothing)").is_empty());
+    match repo.revs("grep(\"(\")") {
+        Err(crate::Error::ParseError(message)) => assert!(message.contains("invalid regex")),
+        _ => panic!("expect ParseError"),
+    }
+
+    // Regex literals.
+    assert_eq!(
+        repo.query(r"grep(/Fixes #\d+/)"),
+        ["Fix parser\n\nFixes #12"]
+    );
+    assert_eq!(repo.query("grep(/(?i)^update docs/)"), ["Update docs"]);
+    assert_eq!(
+        repo.query(r"author(/^[AB] </) - grep(/\//)"),
+        ["Fixes #x", "Fix parser\n\nFixes #12"]
+    );
+    assert!(repo.revs("grep(/(/)").is_err());
+    assert!(repo.revs("/Fix/").is_err());
+    assert!(repo.revs("desc(/Fix/)").is_err());
+    let f = |code: &str| format!("{:?}", crate::Expr::parse(code).unwrap());
+    assert_eq!(f(r"grep(/a\/b c/)"), r"grep(/a\/b c/)");
+    assert_eq!(
+        f("origin/master + /abs/path"),
+        "union(origin/master, /abs/path)"
+    );
+}
+
+#[test]
+fn test_summary() {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A B C D");
+    repo.reword("A", "Fix bug");
+    repo.reword("B", "Fix bug in parser");
+    repo.reword("C", "Fix bug\n\nDetails");
+    repo.reword("D", "fix bug");
+
+    assert_eq!(
+        repo.query("desc(\"Fix bug\")"),
+        [
+            "fix bug",
+            "Fix bug\n\nDetails",
+            "Fix bug in parser",
+            "Fix bug"
+        ]
+    );
+    assert_eq!(repo.query("desc(DETAILS)"), ["Fix bug\n\nDetails"]);
+    assert_eq!(
+        repo.query("descexact(\"Fix bug\")"),
+        ["Fix bug\n\nDetails", "Fix bug in parser", "Fix bug"]
+    );
+    assert!(repo.query("descexact(Details)").is_empty());
+    assert_eq!(
+        repo.query("summary(\"Fix bug\")"),
+        ["Fix bug\n\nDetails", "Fix bug"]
+    );
+    assert_eq!(
+        repo.query("summary(\"Fix bug in parser\")"),
+        ["Fix bug in parser"]
+    );
+    assert!(repo.query("summary(\"Fix\")").is_empty());
+}
+
+#[test]
+fn test_merge_of() {
+    let mut repo = TestRepo::new();
+    repo.drawdag(
+        r#"
+    A---B---C
+         \
+          F1--F2"#,
+    );
+    repo.commit("M", &["C", "F2"], &[]);
+    repo.commit("N", &["M"], &[]);
+    repo.commit("G", &["B"], &[]);
+    repo.commit("H", &["A"], &[]);
+    // Octopus merge.
+    repo.commit("O", &["N", "G", "H"], &[]);
+
+    assert_eq!(repo.query("mergeof(F1, N)"), ["M"]);
+    assert_eq!(repo.query("mergeof(F2, N)"), ["M"]);
+    assert_eq!(repo.query("mergeof(F1 + F2, O)"), ["M"]);
+    assert!(repo.query("mergeof(F1, C)").is_empty());
+    assert!(repo.query("mergeof(F1, F2)").is_empty());
+    assert!(repo.query("mergeof(A + B, O)").is_empty());
+    assert_eq!(repo.query("mergeof(G, O)"), ["O"]);
+    assert_eq!(repo.query("mergeof(H, O)"), ["O"]);
+    assert_eq!(repo.query("mergeof(G + H, O)"), ["O"]);
+}
+
+#[test]
+fn test_simplify_merges() {
+    let mut repo = TestRepo::new();
+    repo.drawdag(
+        r#"
+    A---B---C
+     \
+      F"#,
+    );
+    // M is redundant: B is an ancestor of C.
+    repo.commit("M", &["C", "B"], &[]);
+    // N joins F, which is not reachable from M.
+    repo.commit("N", &["M", "F"], &[]);
+
+    assert_eq!(
+        repo.query("simplifymerges(all())"),
+        ["N", "F", "C", "B", "A"]
+    );
+    // Without F, N only joins history reachable from M.
+    assert_eq!(repo.query("simplifymerges(all() - F)"), ["C", "B", "A"]);
+    // M has no ancestors in the set and is kept. N only joins M.
+    assert_eq!(repo.query("simplifymerges(M + N)"), ["M"]);
+    assert_eq!(repo.query("simplifymerges(A:C)"), ["C", "B", "A"]);
+}
+
+#[test]
+fn test_merge_predicates() {
+    let mut repo = TestRepo::new();
+    assert!(repo.query("merge()").is_empty());
+    assert!(repo.query("nonmerge()").is_empty());
+
+    repo.drawdag(
+        r#"
+    A---B---C
+     \
+      D"#,
+    );
+    repo.commit("M", &["C", "D"], &[]);
+    repo.commit("N", &["M"], &[]);
+    repo.commit("O", &["N", "B", "D"], &[]);
+
+    assert_eq!(repo.query("merge()"), ["O", "M"]);
+    assert_eq!(repo.query("merge() & ::N"), ["M"]);
+    assert_eq!(repo.query("nonmerge()"), ["N", "D", "C", "B", "A"]);
+    assert_eq!(repo.query("all() - merge() - nonmerge()").len(), 0);
+    assert_eq!(repo.query("merge() & nonmerge()").len(), 0);
+    assert_eq!(repo.query("first(merge())"), ["O"]);
+    assert!(repo.revs("merge(A)").is_err());
+}
+
+#[test]
+fn test_nth_parent() {
+    let mut repo = TestRepo::new();
+    repo.drawdag(
+        r#"
+    A---B---C
+     \
+      D"#,
+    );
+    repo.commit("M", &["C", "D"], &[]);
+    repo.commit("O", &["M", "B", "A"], &[]);
+
+    assert_eq!(repo.query("M^1"), ["C"]);
+    assert_eq!(repo.query("M^2"), ["D"]);
+    assert!(repo.query("M^3").is_empty());
+    assert_eq!(repo.query("M^0"), ["M"]);
+    assert_eq!(repo.query("O^3"), ["A"]);
+    assert_eq!(repo.query("O^1^2"), ["D"]);
+    assert_eq!(repo.query("O^2^"), ["A"]);
+    assert_eq!(repo.query("(M + O)^2"), ["D", "B"]);
+    assert_eq!(repo.query("(M + C)^2"), ["D"]);
+    assert_eq!(repo.query("nthparent(O, 1)"), ["M"]);
+    assert_eq!(repo.query("M^2::"), ["O", "M", "D"]);
+    assert!(repo.revs("nthparent(O)").is_err());
+    assert!(repo.revs("nthparent(O, x)").is_err());
+}
+
+#[test]
+fn test_nth_ancestor() {
+    let mut repo = TestRepo::new();
+    repo.drawdag(
+        r#"
+    A---B---C
+     \
+      D"#,
+    );
+    repo.commit("M", &["D", "C"], &[]);
+    repo.commit("N", &["M"], &[]);
+
+    assert_eq!(repo.query("N~0"), ["N"]);
+    assert_eq!(repo.query("N~"), ["M"]);
+    assert_eq!(repo.query("N~1"), ["M"]);
+    assert_eq!(repo.query("N~2"), ["D"]);
+    assert_eq!(repo.query("N~3"), ["A"]);
+    assert!(repo.query("N~4").is_empty());
+    assert_eq!(repo.query("N~~"), ["D"]);
+    assert_eq!(repo.query("M^2~2"), ["A"]);
+    assert_eq!(repo.query("(N + C)~2"), ["D", "A"]);
+    assert_eq!(repo.query("N~2:N"), ["N", "M", "D"]);
+    assert_eq!(repo.query("only(N, N~2)"), ["N", "M", "C", "B"]);
+    assert_eq!(repo.query("nthancestor(C, 1)"), ["B"]);
+}
+
+#[test]
+fn test_flags_for() -> crate::Result<()> {
+    use crate::CommitFlags;
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C");
+    repo.add_ref("refs/remotes/origin/master", repo.query_single_oid("A"));
+    repo.amend("refs/heads/B");
+
+    let set = repo.revs("all()")?;
+    let flags = repo.flags_for(&set)?;
+    assert_eq!(flags.len(), 4);
+    let flag = |name| flags[&repo.query_single_oid(name)];
+    let flag_new = |obsolete, public, head| CommitFlags {
+        obsolete,
+        public,
+        head,
+    };
+    assert_eq!(flag("A"), flag_new(false, true, false));
+    assert_eq!(flag("B_old"), flag_new(true, false, false));
+    assert_eq!(flag("B"), flag_new(false, false, true));
+    assert_eq!(flag("C"), flag_new(false, false, true));
+
+    let flags = repo.flags_for(&repo.revs("A")?)?;
+    assert_eq!(flags.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_edges() -> crate::Result<()> {
+    use crate::git2::Oid;
+    // Criss-cross: D and E both merge B and C.
+    let mut repo = TestRepo::new();
+    repo.drawdag(
+        r#"
+    D   E   B   C
+    |\  |\  |   |
+    B C C B A   A"#,
+    );
+    let names = |edges: Vec<(Oid, Oid)>| -> Vec<String> {
+        let name = |oid| repo.desc_set(&repo.set_from_oids(Some(oid)).unwrap())[0].clone();
+        edges
+            .into_iter()
+            .map(|(p, c)| format!("{}-{}", name(p), name(c)))
+            .collect()
+    };
+
+    let edges = repo.edges(&repo.revs("all()")?)?;
+    assert_eq!(names(edges), ["B-E", "C-E", "B-D", "C-D", "A-C", "A-B"]);
+    assert!(repo.dangling_edges(&repo.revs("all()")?)?.is_empty());
+
+    let set = repo.revs("B + D + E")?;
+    assert_eq!(names(repo.edges(&set)?), ["B-E", "B-D"]);
+    assert_eq!(names(repo.dangling_edges(&set)?), ["C-E", "C-D", "A-B"]);
+    Ok(())
+}
+
+#[test]
+fn test_heads_annotated() -> crate::Result<()> {
+    let mut repo = TestRepo::new();
+    repo.drawdag(
+        r#"
+    A--B--C
+     \
+      D--E
+    A--G--H
+    F"#,
+    );
+    let set = repo.revs("B + D + G + F + A")?;
+    let d = repo.query_single_oid("D");
+    for name in ["B", "D", "G"] {
+        let ref_name = format!("refs/heads/{}", name);
+        let mut reference = repo.git_repo().find_reference(&ref_name).unwrap();
+        reference.delete().unwrap();
+    }
+    repo.add_ref("refs/tags/v1", d);
+    repo.add_ref("refs/tags/a", repo.query_single_oid("F"));
+    repo.add_ref("refs/remotes/origin/x", repo.query_single_oid("E"));
+
+    let heads = repo.heads_annotated(&set)?;
+    let heads: Vec<(String, Option<String>)> = heads
+        .into_iter()
+        .map(|(oid, name)| {
+            let set = repo.set_from_oids(Some(oid)).unwrap();
+            (repo.desc_set(&set)[0].clone(), name)
+        })
+        .collect();
+    let expected = [
+        ("F", Some("F")),
+        ("D", Some("v1")),
+        ("G", None),
+        ("B", None),
+    ];
+    let expected: Vec<(String, Option<String>)> = expected
+        .iter()
+        .map(|(desc, name)| (desc.to_string(), name.map(|s| s.to_string())))
+        .collect();
+    assert_eq!(heads, expected);
+
+    let heads = repo.heads_annotated(&repo.revs("C + E")?)?;
+    let names: Vec<_> = heads.into_iter().map(|(_, name)| name).collect();
+    assert_eq!(names, [Some("C".to_string()), Some("E".to_string())]);
+    Ok(())
+}
+
+#[test]
+fn test_upstream_push() {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C--D");
+    repo.add_ref("refs/remotes/origin/main", repo.query_single_oid("B"));
+    repo.add_ref("refs/remotes/origin/C", repo.query_single_oid("A"));
+    repo.add_ref("refs/remotes/fork/C", repo.query_single_oid("D"));
+    repo.set_config("remote.origin.url", "https://example.com/repo");
+    repo.set_config("remote.origin.fetch", "+refs/heads/*:refs/remotes/origin/*");
+    repo.set_config("remote.fork.url", "https://example.com/fork");
+    repo.set_config("remote.fork.fetch", "+refs/heads/*:refs/remotes/fork/*");
+    repo.set_config("branch.C.remote", "origin");
+    repo.set_config("branch.C.merge", "refs/heads/main");
+    repo.git_repo().set_head("refs/heads/C").unwrap();
+    repo.reload();
+
+    assert_eq!(repo.query("C@{upstream}"), ["B"]);
+    assert_eq!(repo.query("C@{u}"), ["B"]);
+    assert_eq!(repo.query("@{u}"), ["B"]);
+    assert_eq!(repo.query("@{u}..C"), ["C"]);
+    assert_eq!(repo.query("C@{push}"), ["A"]);
+    repo.set_config("remote.pushDefault", "fork");
+    assert_eq!(repo.query("@{push}"), ["D"]);
+    repo.set_config("push.default", "upstream");
+    assert_eq!(repo.query("@{push}"), ["B"]);
+
+    match repo.revs("B@{u}") {
+        Err(crate::Error::UnresolvedName(message)) => {
+            assert!(message.contains("no upstream configured for branch B"))
+        }
+        _ => panic!("expect UnresolvedName"),
+    }
+    assert!(repo.revs("X@{u}").is_err());
+}
+
+#[test]
+fn test_branch_status() -> crate::Result<()> {
+    use crate::BranchStatus;
+    let mut repo = TestRepo::new();
+    repo.drawdag(
+        r#"
+    A--B--C--D
+        \
+         E--F"#,
+    );
+    repo.add_ref("refs/remotes/origin/D", repo.query_single_oid("B"));
+    repo.add_ref("refs/remotes/origin/F", repo.query_single_oid("D"));
+    repo.add_ref("refs/remotes/origin/A", repo.query_single_oid("A"));
+    repo.add_ref("refs/remotes/origin/main", repo.query_single_oid("F"));
+    // The configured upstream takes precedence.
+    repo.set_config("remote.origin.url", "https://example.com/repo");
+    repo.set_config("remote.origin.fetch", "+refs/heads/*:refs/remotes/origin/*");
+    repo.set_config("branch.C.remote", "origin");
+    repo.set_config("branch.C.merge", "refs/heads/main");
+    repo.reload();
+
+    let status = |name: &str, upstream: Option<&str>, ahead, behind| BranchStatus {
+        name: name.to_string(),
+        upstream: upstream.map(|s| s.to_string()),
+        ahead,
+        behind,
+    };
+    assert_eq!(
+        repo.branch_status("origin/{}")?,
+        [
+            status("A", Some("origin/A"), 0, 0),
+            status("B", None, 0, 0),
+            status("C", Some("origin/main"), 1, 2),
+            status("D", Some("origin/D"), 2, 0),
+            status("E", None, 0, 0),
+            status("F", Some("origin/F"), 2, 2),
+        ]
+    );
+    assert_eq!(
+        repo.branch_status("")?[2..4],
+        [
+            status("C", Some("origin/main"), 1, 2),
+            status("D", None, 0, 0)
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_remote_names() {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C");
+    repo.add_ref("refs/remotes/origin/feature", repo.query_single_oid("A"));
+    repo.add_ref("refs/remotes/upstream/feature", repo.query_single_oid("B"));
+    repo.add_ref("refs/remotes/upstream/other", repo.query_single_oid("C"));
+
+    assert_eq!(repo.query("other"), ["C"]);
+    assert_eq!(repo.query("ref(other)"), ["C"]);
+
+    // The remote of the main branch (origin) is preferred.
+    assert_eq!(repo.query("feature"), ["A"]);
+    assert_eq!(repo.query("upstream/feature"), ["B"]);
+
+    repo.set_config("revs.default-remote", "upstream");
+    assert_eq!(repo.query("feature"), ["B"]);
+    assert_eq!(repo.query("origin/feature"), ["A"]);
+
+    repo.set_config("revs.default-remote", "fork");
+    let err = repo.revs("feature").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        r#"name "feature" is ambiguous: ["refs/remotes/origin/feature", "refs/remotes/upstream/feature"]"#
+    );
+}
+
+#[test]
+fn test_authors() -> crate::Result<()> {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C--D");
+    let mailmap = "Alice <alice@example.com> B <test@example.com>\n\
+                   Alice <alice@example.com> C <test@example.com>\n";
+    let workdir = repo.git_repo().workdir().unwrap().to_path_buf();
+    std::fs::write(workdir.join(".mailmap"), mailmap).unwrap();
+
+    let count = |v: &[(&str, usize)]| -> Vec<(String, usize)> {
+        v.iter().map(|(s, n)| (s.to_string(), *n)).collect()
+    };
+    assert_eq!(
+        repo.authors(&repo.revs("all()")?)?,
+        count(&[
+            ("Alice <alice@example.com>", 2),
+            ("A <test@example.com>", 1),
+            ("D <test@example.com>", 1),
+        ])
+    );
+    assert_eq!(
+        repo.committers(&repo.revs("B + C")?)?,
+        count(&[("Alice <alice@example.com>", 2)])
+    );
+    assert_eq!(
+        repo.authors(&repo.revs("D")?)?,
+        count(&[("D <test@example.com>", 1)])
+    );
+    assert!(repo.authors(&repo.revs("none()")?)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_tags_sorted() -> crate::Result<()> {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C");
+    let tags = [
+        ("v1.10.0", "C"),
+        ("v1.9.0", "B"),
+        ("v1.2.0-rc.10", "A"),
+        ("v1.2.0-rc.2", "A"),
+        ("v1.2.0", "A"),
+        ("1.2.0-alpha", "A"),
+        ("v2", "C"),
+        ("nightly", "C"),
+        ("latest", "C"),
+    ];
+    for (tag, commit) in &tags {
+        let oid = repo.query_single_oid(commit);
+        repo.add_ref(&format!("refs/tags/{}", tag), oid);
+    }
+
+    let names = |pattern: &str| -> crate::Result<Vec<String>> {
+        Ok(repo
+            .tags_sorted(pattern)?
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect())
+    };
+    assert_eq!(
+        names("*")?,
+        [
+            "1.2.0-alpha",
+            "v1.2.0-rc.2",
+            "v1.2.0-rc.10",
+            "v1.2.0",
+            "v1.9.0",
+            "v1.10.0",
+            "v2",
+            "latest",
+            "nightly",
+        ]
+    );
+    assert_eq!(names("v1.1*")?, ["v1.10.0"]);
+    assert!(names("foo*")?.is_empty());
+    assert!(repo.tags_sorted("[").is_err());
+
+    let tags = repo.tags_sorted("v1.9.0")?;
+    assert_eq!(tags[0].1, repo.query_single_oid("B"));
+    Ok(())
+}
+
+#[test]
+fn test_reverts() {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C");
+    let b = repo.query_single_oid("B");
+    let c = repo.query_single_oid("C");
+    // D mentions a hash, but not in a revert line.
+    repo.commit("D", &["C"], &[]);
+    repo.reword("D", &format!("Fix C\n\nRelated to commit {}.\n", c));
+    repo.commit("E", &["D"], &[]);
+    repo.reword("E", &format!("Revert B\n\nThis reverts commit {}.\n", b));
+    repo.commit("F", &["E"], &[]);
+    let message = format!(
+        "Revert B and C\n\nThis reverts commit {}.\nThis reverts commit {}.\n",
+        b, c
+    );
+    repo.reword("F", &message);
+
+    let query = |code: &str| -> Vec<String> {
+        let messages = repo.query(code);
+        messages
+            .iter()
+            .map(|m| m.lines().next().unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(query("revertcommits()"), ["Revert B and C", "Revert B"]);
+    assert_eq!(query("reverts(B)"), ["Revert B and C", "Revert B"]);
+    assert_eq!(query("reverts(C)"), ["Revert B and C"]);
+    assert!(query("reverts(A + D)").is_empty());
+    assert!(query("reverts(revertcommits())").is_empty());
+}
+
+#[test]
+fn test_cherry_picks() {
+    let mut repo = TestRepo::new();
+    repo.drawdag(
+        r#"
+    A---B---C
+     \
+      D---E"#,
+    );
+    let b = repo.query_single_oid("B");
+    let c = repo.query_single_oid("C");
+    repo.commit("F", &["E"], &[]);
+    repo.reword("F", &format!("B\n\n(cherry picked from commit {})\n", b));
+    repo.commit("G", &["F"], &[]);
+    let message = format!(
+        "C\n\n(cherry picked from commit {})\n(cherry picked from commit {})\n",
+        c, b
+    );
+    repo.reword("G", &message);
+    // Mentioning a hash without the cherry-pick footer does not count.
+    repo.commit("H", &["G"], &[]);
+    repo.reword("H", &format!("H\n\nSee commit {}\n", c));
+
+    let query = |code: &str| -> Vec<String> {
+        let messages = repo.query(code);
+        let summaries = messages.iter().map(|m| m.lines().next().unwrap());
+        summaries.map(|s| s.to_string()).collect()
+    };
+    assert_eq!(query("cherrypicks()"), ["C", "B"]);
+    assert_eq!(query("cherrypicks() & D::"), ["C", "B"]);
+    assert_eq!(query("cherrypickedfrom(B)"), ["C", "B"]);
+    assert_eq!(query("cherrypickedfrom(C)"), ["C"]);
+    assert_eq!(query("cherrypickedfrom(A:C) - A:C"), ["C", "B"]);
+    assert!(query("cherrypickedfrom(D::)").is_empty());
+}
+
+#[test]
+fn test_fixes() {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A");
+    let messages = [
+        ("B", "Fix crash\n\nFixes: #123\nCloses: #45\n"),
+        ("C", "Fix typo (#1234)\n"),
+        ("D", "Fix another crash #123\n\nAlso see PROJ-7.\n"),
+        ("E", "Fix PROJ-7 and PROJ-70\n\nCloses: PROJ-8\n"),
+    ];
+    let mut parent = "A";
+    for (name, message) in messages.iter() {
+        repo.commit(name, &[parent], &[]);
+        repo.reword(name, message);
+        parent = name;
+    }
+
+    let query = |repo: &TestRepo, code: &str| -> Vec<String> {
+        let messages = repo.query(code);
+        messages
+            .iter()
+            .map(|m| m.lines().next().unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(
+        query(&repo, "fixes(\"#123\")"),
+        ["Fix another crash #123", "Fix crash"]
+    );
+    assert_eq!(query(&repo, "fixes(\"#45\")"), ["Fix crash"]);
+    assert_eq!(query(&repo, "fixes(\"#1234\")"), ["Fix typo (#1234)"]);
+    assert!(query(&repo, "fixes(\"#12\")").is_empty());
+    assert!(query(&repo, "fixes(\"PROJ-7\")").is_empty());
+
+    repo.set_config("revs.issue-pattern", r"\b[A-Z]+-[0-9]+\b");
+    assert_eq!(
+        query(&repo, "fixes(\"PROJ-7\")"),
+        ["Fix PROJ-7 and PROJ-70", "Fix another crash #123"]
+    );
+    assert_eq!(
+        query(&repo, "fixes(\"PROJ-8\")"),
+        ["Fix PROJ-7 and PROJ-70"]
+    );
+    assert!(query(&repo, "fixes(\"#123\")").is_empty());
+
+    repo.set_config("revs.issue-pattern", "(");
+    assert!(repo.revs("fixes(\"#123\")").is_err());
+}
+
+#[test]
+fn test_revset_alias_config() {
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C");
+    repo.set_config("revsetalias.t", "B");
+    repo.set_config("revsetalias.f", "$1^^ + $1");
+    repo.set_config("revsetalias.g", "apply(f($1), children($1))");
+
+    assert_eq!(repo.query_with_alias_config("t"), ["B"]);
+    assert_eq!(repo.query_with_alias_config("t()"), ["B"]);
+    assert_eq!(repo.query_with_alias_config("f(C)"), ["C", "A"]);
+    assert_eq!(repo.query_with_alias_config("f(t())"), ["B"]);
+    assert_eq!(repo.query_with_alias_config("g(t)"), ["C", "A"]);
+}
+
+#[test]
+fn test_revset_reuse() -> crate::Result<()> {
+    use crate::Revset;
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C");
+    repo.set_config("revsetalias.t", "B");
+    repo.set_config("revsetalias.f", "$1^^ + $1");
+    repo.set_config("revsetalias.g", "apply(f($1), children($1))");
+    repo.set_config("revsetalias.loop", "loop($1)");
+
+    let revset = Revset::parse("heads(all())")?;
+    let aliased = Revset::parse_with_aliases(&repo, "f(g(t))")?;
+    assert_eq!(
+        aliased.expr().to_string(),
+        "union(parents(parents(apply(union(parents(parents($1)), $1), children(t)))), \
+         apply(union(parents(parents($1)), $1), children(t)))"
+    );
+    assert_eq!(repo.desc_set(&revset.eval(&repo)?), ["C"]);
+    assert_eq!(repo.desc_set(&aliased.eval(&repo)?), ["C", "A"]);
+    assert!(Revset::parse_with_aliases(&repo, "loop(A)").is_err());
+
+    // The same `Revset` picks up graph changes.
+    repo.commit("D", &["B"], &[]);
+    assert_eq!(repo.desc_set(&revset.eval(&repo)?), ["D", "C"]);
+    assert_eq!(repo.desc_set(&aliased.eval(&repo)?), ["D", "C", "A"]);
+
+    // Aliases are expanded at parse time.
+    repo.set_config("revsetalias.f", "$1");
+    assert_eq!(repo.desc_set(&aliased.eval(&repo)?), ["D", "C", "A"]);
+    Ok(())
+}
+
+#[test]
+fn test_plan() -> crate::Result<()> {
+    use crate::PlanKind;
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C");
+
+    let plan = repo.plan("sort(draft() & author(alice), date) + ::C - modifies(x)")?;
+    assert_eq!(
+        plan.to_string(),
+        r#"union [dag]
+  sort(date) [dag]
+    intersection [dag]
+      draft() [cached]
+      author(alice) [filter, scoped]
+  difference [dag]
+    ancestors [dag]
+      C [name]
+    modifies(x) [diff filter, scoped]
+"#
+    );
+    assert_eq!(plan.kind, PlanKind::Dag);
+    let modifies = &plan.children[1].children[1];
+    assert_eq!(modifies.kind, PlanKind::DiffFilter);
+    assert!(modifies.scoped);
+
+    let plan = repo.plan("desc(a) & desc(b)")?;
+    assert!(plan.children.iter().all(|c| !c.scoped));
+    let plan = repo.plan("C & follow(x)")?;
+    assert_eq!(
+        plan.to_string(),
+        "intersection [dag]\n  C [name]\n  follow(x) [dag]\n"
+    );
+    let plan = repo.plan("reverts(ref(main))")?;
+    assert_eq!(plan.to_string(), "reverts [filter]\n  ref(main) [name]\n");
+
+    assert!(repo.plan("nonexistent(A)").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_context_builder() -> crate::Result<()> {
+    use crate::ast;
+    use crate::EvalContext;
+    let mut repo = TestRepo::new();
+    repo.drawdag("A--B--C--D");
+    let mut ctx = EvalContext::default();
+    ctx.with_name("stable", repo.revs("C")?)
+        .with_fn("grandparent", |repo, args, ctx| {
+            repo.revs_with_context(ast!(parents(parents({ args[0].clone() }))), ctx)
+        })
+        .with_fn("oldstable", |repo, _args, ctx| {
+            repo.revs_with_context("grandparent(stable)", ctx)
+        });
+    let query = |code: &str| repo.desc_set(&repo.revs_with_context(code, &ctx).unwrap());
+    assert_eq!(query("stable"), ["C"]);
+    assert_eq!(query("grandparent(D)"), ["B"]);
+    assert_eq!(query("grandparent(D) + stable"), ["C", "B"]);
+    // Functions can use names and functions defined by the context.
+    assert_eq!(query("grandparent(stable)"), ["A"]);
+    assert_eq!(query("oldstable"), ["A"]);
+    assert_eq!(query("grandparent(oldstable + D)"), ["B"]);
+    Ok(())
+}
+
+#[test]
+fn test_ext() {
+    use crate::ext::OidExt;
+    use crate::ext::OidIterExt;
+    use crate::ext::SetExt;
+    use crate::ext::VertexExt;
+    use crate::ext::VertexIterExt;
+    use gitdag::git2::Oid;
+
+    let oid = Oid::zero();
+    let v = oid.to_vertex();
+    assert_eq!(oid, v.to_oid().unwrap());
+
+    let oid2 = {
+        let mut bytes = oid.as_bytes().to_vec();
+        bytes[0] ^= 1;
+        Oid::from_bytes(&bytes).unwrap()
+    };
+    let oid_list = [oid, oid2];
+    let set = oid_list.to_vec().to_set();
+    assert_eq!(
+        oid_list.to_vec(),
+        set.to_oids()
+            .unwrap()
+            .collect::<crate::Result<Vec<_>>>()
+            .unwrap()
+    );
+    assert_eq!(oid_list.to_vec(), set.to_oid_vec().unwrap());
+    let bad = Set::from_static_names(vec![gitdag::dag::Vertex::copy_from(b"x")]);
+    assert!((set | bad).to_oid_vec().is_err());
+
+    let vertexes = vec![oid.to_vertex(), oid2.to_vertex()];
+    assert_eq!(vertexes.to_oid_vec().unwrap(), oid_list);
+    let bad = vec![oid.to_vertex(), gitdag::dag::Vertex::copy_from(b"x")];
+    assert_eq!(
+        bad.to_oid_vec().unwrap_err().to_string(),
+        "vertex x is not a commit hash"
+    );
+}
+
+#[test]
+fn test_ast_macro() {
+    use crate::ast;
+    let f = |e| format!("{:?}", e);
+    assert_eq!(f(ast!("foo")), "foo");
+    assert_eq!(f(ast!(parents("foo"))), "parents(foo)");
+    assert_eq!(f(ast!(draft())), "draft()");
+    assert_eq!(
+        f(ast!(union(draft(), public()))),
+        "union(draft(), public())"
+    );
+
+    let name = "foo";
+    assert_eq!(
+        f(ast!(union(desc({ name }), author({ "bar" })))),
+        "union(desc(foo), author(bar))"
+    );
+
+    let set = Set::from_static_names(vec!["A".into(), "B".into()]);
+    assert_eq!(f(ast!(parents({ set }))), "parents(<static [A, B]>)")
+}
+
+#[test]
+fn test_parse_error() {
+    let message = |code: &str| match crate::Expr::parse(code) {
+        Err(crate::Error::ParseError(message)) => message,
+        _ => panic!("expect ParseError"),
+    };
+    assert!(message("a + + b").contains("\n  a + + b\n      ^ (at byte 4)\n"));
+    assert!(message("foo(a").contains("\n  foo(a\n       ^ (at byte 5)"));
+    assert!(message("a # b").ends_with("\n  a # b\n    ^ (at byte 2)"));
+    assert!(message("a +\nb)").contains("\n  b)\n   ^ (at byte 5)"));
+    assert!(message("\"é\" c").contains("\n  \"é\" c\n      ^ (at byte 5)"));
+
+    // "^<digits>" is the n-th parent. It cannot exclude a name.
+    let parse = |code: &str| format!("{:?}", crate::Expr::parse(code).unwrap());
+    assert_eq!(parse("x^2"), "nthparent(x, 2)");
+    assert_eq!(parse("(x)^2^1"), "nthparent(nthparent(x, 2), 1)");
+    assert_eq!(parse("x ^2a"), parse("::x - ::2a"));
+    assert!(message("x ^2").starts_with("cannot exclude \"2\" using '^'"));
+    assert!(message("x ^2").ends_with("\n  x ^2\n    ^^ (at byte 2)"));
+    assert!(message("^12 x").starts_with("cannot exclude \"12\" using '^'"));
+    assert!(message("x ^y ^3").contains("Use 'x^3' for the n-th parent"));
+
+    // "^name" needs whitespace before it to be an exclusion.
+    assert_eq!(parse("x ^y"), parse("::x - ::y"));
+    assert_eq!(parse("f(^y x, ^z)"), parse("f(::x - ::y, ::none() - ::z)"));
+    assert!(message("HEAD^main").starts_with("cannot exclude \"main\" using '^'"));
+    assert!(message("HEAD^main").ends_with("\n  HEAD^main\n      ^^^^^ (at byte 4)"));
+    assert!(message("x ^y^z").contains("Use 'x ^z'"));
+    assert!(message("(x)^y").contains("Use 'x ^y'"));
 }
 
 #[test]
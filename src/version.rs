@@ -0,0 +1,86 @@
+use std::cmp::Ordering;
+
+/// Compare tag names. Semantic versions like `v1.10.0` are compared by their
+/// version numbers. Other names are compared lexicographically and sorted
+/// after semantic versions.
+pub(crate) fn compare_tag_names(a: &str, b: &str) -> Ordering {
+    match (Version::parse(a), Version::parse(b)) {
+        (Some(va), Some(vb)) => va.cmp(&vb).then_with(|| a.cmp(b)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.cmp(b),
+    }
+}
+
+/// A parsed semantic version. Build metadata (`+...`) is ignored.
+#[derive(PartialEq, Eq)]
+struct Version {
+    numbers: [u64; 3],
+    pre: Vec<Identifier>,
+}
+
+/// Pre-release identifier. Numeric identifiers are lower than alphanumeric
+/// ones.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum Identifier {
+    Numeric(u64),
+    Alphanumeric(String),
+}
+
+impl Version {
+    /// Parse `v1.2.3`, `1.2`, `1.2.3-rc.1+build`, etc.
+    fn parse(name: &str) -> Option<Self> {
+        let name = name
+            .strip_prefix('v')
+            .or_else(|| name.strip_prefix('V'))
+            .unwrap_or(name);
+        let name = name.split('+').next()?;
+        let (core, pre) = match name.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (name, None),
+        };
+        let mut numbers = [0; 3];
+        let parts: Vec<&str> = core.split('.').collect();
+        if parts.len() > numbers.len() {
+            return None;
+        }
+        for (number, part) in numbers.iter_mut().zip(parts) {
+            if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            *number = part.parse().ok()?;
+        }
+        let pre = match pre {
+            None => Vec::new(),
+            Some(pre) => pre
+                .split('.')
+                .map(|s| match s.parse() {
+                    Ok(n) => Some(Identifier::Numeric(n)),
+                    Err(_) if s.is_empty() => None,
+                    Err(_) => Some(Identifier::Alphanumeric(s.to_string())),
+                })
+                .collect::<Option<Vec<_>>>()?,
+        };
+        Some(Self { numbers, pre })
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // A pre-release is lower than the release.
+        self.numbers.cmp(&other.numbers).then_with(|| {
+            match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => self.pre.cmp(&other.pre),
+            }
+        })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
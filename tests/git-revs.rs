@@ -0,0 +1,339 @@
+use gitrevset::git2;
+use std::path::Path;
+use std::process::Command;
+
+/// Create a repo with a linear history. Return the commit hashes, oldest
+/// first.
+fn linear_repo(dir: &Path, names: &[&str]) -> Vec<String> {
+    let repo = git2::Repository::init(dir).unwrap();
+    let mut parent: Option<git2::Oid> = None;
+    let mut result = Vec::new();
+    for (i, name) in names.iter().enumerate() {
+        let time = git2::Time::new(i as i64, 0);
+        let sig = git2::Signature::new(name, "test@example.com", &time).unwrap();
+        let mut tree_builder = repo.treebuilder(None).unwrap();
+        let blob_oid = repo.blob(name.as_bytes()).unwrap();
+        tree_builder.insert(name, blob_oid, 0o100644).unwrap();
+        let tree = repo.find_tree(tree_builder.write().unwrap()).unwrap();
+        let parents: Vec<_> = parent
+            .iter()
+            .map(|p| repo.find_commit(*p).unwrap())
+            .collect();
+        let parent_refs: Vec<_> = parents.iter().collect();
+        let oid = repo
+            .commit(Some("HEAD"), &sig, &sig, name, &tree, &parent_refs)
+            .unwrap();
+        parent = Some(oid);
+        result.push(oid.to_string());
+    }
+    result
+}
+
+/// Run `git-revs` with `args` in the repo. Return stdout.
+fn git_revs(dir: &Path, args: &[&str]) -> String {
+    git_revs_with_status(dir, args).0
+}
+
+/// Run `git-revs` with `args` in the repo. Return stdout and the exit code.
+fn git_revs_with_status(dir: &Path, args: &[&str]) -> (String, i32) {
+    let output = Command::new(env!("CARGO_BIN_EXE_git-revs"))
+        .args(args)
+        .env("GIT_DIR", dir.join(".git"))
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    (stdout, output.status.code().unwrap())
+}
+
+#[test]
+fn test_reverse() {
+    let dir = tempfile::tempdir().unwrap();
+    let hashes = linear_repo(dir.path(), &["A", "B", "C"]);
+    let newest_first = format!("{}\n{}\n{}\n", hashes[2], hashes[1], hashes[0]);
+    let oldest_first = format!("{}\n{}\n{}\n", hashes[0], hashes[1], hashes[2]);
+    assert_eq!(git_revs(dir.path(), &["::."]), newest_first);
+    assert_eq!(git_revs(dir.path(), &["--reverse", "::."]), oldest_first);
+    assert_eq!(
+        git_revs(dir.path(), &["--reverse", "-z", "::."]),
+        oldest_first.replace('\n', "\0")
+    );
+    assert_eq!(
+        git_revs(dir.path(), &["--limit", "2", "::."]),
+        format!("{}\n{}\n", hashes[2], hashes[1])
+    );
+    assert_eq!(
+        git_revs(dir.path(), &["--reverse", "-n", "1", "::."]),
+        format!("{}\n", hashes[0])
+    );
+    assert_eq!(git_revs(dir.path(), &["--limit", "0", "::."]), "");
+    assert_eq!(git_revs(dir.path(), &["--limit", "5", "::."]), newest_first);
+    assert_eq!(
+        git_revs_with_status(dir.path(), &["--limit", "x", "::."]).1,
+        2
+    );
+}
+
+#[test]
+fn test_error_exit_code() {
+    let dir = tempfile::tempdir().unwrap();
+    linear_repo(dir.path(), &["A"]);
+    assert_eq!(git_revs_with_status(dir.path(), &["::. +"]), ("".into(), 2));
+    assert_eq!(git_revs_with_status(dir.path(), &["(."]), ("".into(), 2));
+    assert_eq!(
+        git_revs_with_status(dir.path(), &["nonexistent"]),
+        ("".into(), 2)
+    );
+    assert_eq!(git_revs_with_status(dir.path(), &["."]).1, 0);
+}
+
+#[test]
+fn test_template() {
+    let dir = tempfile::tempdir().unwrap();
+    let hashes = linear_repo(dir.path(), &["A", "B", "C"]);
+    let run = |args: &[&str]| git_revs_with_status(dir.path(), args);
+    assert_eq!(
+        run(&["--template", "{author}: {summary} ({date})", "::desc(B)"]).0,
+        "B: B (1970-01-01 00:00:01 +0000)\nA: A (1970-01-01 00:00:00 +0000)\n"
+    );
+    assert_eq!(
+        run(&["--template", "{hash}", "."]).0,
+        format!("{}\n", hashes[2])
+    );
+    let short = run(&["--template", "{short}", "."]).0;
+    assert!(short.len() < 40 && hashes[2].starts_with(short.trim_end()));
+    assert_eq!(
+        run(&["-z", "--template", "{summary}\n{author}", "::desc(B)"]).0,
+        "B\nB\0A\nA\0"
+    );
+    assert_eq!(run(&["--template", "{oid}", "."]), (String::new(), 2));
+    assert_eq!(run(&["--template", "{short", "."]), (String::new(), 2));
+}
+
+#[test]
+fn test_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let hashes = linear_repo(dir.path(), &["A", "B"]);
+    let (out, code) = git_revs_with_status(dir.path(), &["--json", "::."]);
+    assert_eq!(code, 0);
+    let value: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(
+        value,
+        serde_json::json!([
+            {
+                "oid": hashes[1],
+                "summary": "B",
+                "author_name": "B",
+                "author_email": "test@example.com",
+                "committer_time": 1,
+                "obsolete": false,
+                "public": false,
+                "head": true,
+            },
+            {
+                "oid": hashes[0],
+                "summary": "A",
+                "author_name": "A",
+                "author_email": "test@example.com",
+                "committer_time": 0,
+                "obsolete": false,
+                "public": false,
+                "head": false,
+            },
+        ])
+    );
+    assert_eq!(git_revs(dir.path(), &["--json", "none()"]), "[]\n");
+    assert_eq!(git_revs(dir.path(), &["-z", "--json", "none()"]), "[]\0");
+    assert_eq!(
+        git_revs_with_status(dir.path(), &["--json", "nonexistent"]),
+        (String::new(), 2)
+    );
+}
+
+#[test]
+fn test_graph() {
+    let dir = tempfile::tempdir().unwrap();
+    let hashes = linear_repo(dir.path(), &["A", "B"]);
+    // C: A; M: B + C
+    let repo = git2::Repository::open(dir.path()).unwrap();
+    let sig = git2::Signature::new("C", "test@example.com", &git2::Time::new(2, 0)).unwrap();
+    let a = repo
+        .find_commit(git2::Oid::from_str(&hashes[0]).unwrap())
+        .unwrap();
+    let b = repo
+        .find_commit(git2::Oid::from_str(&hashes[1]).unwrap())
+        .unwrap();
+    let tree = a.tree().unwrap();
+    let c = repo.commit(None, &sig, &sig, "C", &tree, &[&a]).unwrap();
+    let c = repo.find_commit(c).unwrap();
+    let m = repo
+        .commit(Some("HEAD"), &sig, &sig, "M", &tree, &[&b, &c])
+        .unwrap();
+
+    // Remove hashes so the output is stable.
+    let graph = |code: &str| -> String {
+        let mut out = git_revs(dir.path(), &["--graph", code]);
+        for oid in [a.id(), b.id(), c.id(), m] {
+            out = out.replace(&format!("{} ", &oid.to_string()[..7]), "");
+        }
+        out
+    };
+    assert_eq!(graph("::."), "* M\n|\\\n| * C\n* | B\n|/\n* A\n");
+    assert_eq!(graph("::. - desc(A)"), "* M\n|\\\n| * C\n| ~\n* B\n~\n");
+    assert_eq!(graph("none()"), "");
+}
+
+#[test]
+fn test_count() {
+    let dir = tempfile::tempdir().unwrap();
+    linear_repo(dir.path(), &["A", "B", "C"]);
+    assert_eq!(
+        git_revs(dir.path(), &["--count", "::.", "none()"]),
+        "3\n0\n"
+    );
+    assert_eq!(git_revs(dir.path(), &["--count", "desc(B) + ."]), "2\n");
+}
+
+#[test]
+fn test_contains() {
+    let dir = tempfile::tempdir().unwrap();
+    let hashes = linear_repo(dir.path(), &["A", "B", "C"]);
+    let run = |args: &[&str]| git_revs_with_status(dir.path(), args);
+    let b = hashes[1].as_str();
+    assert_eq!(run(&["--contains", b, "::."]), ("true\n".to_string(), 0));
+    assert_eq!(run(&["--contains", b, "."]), ("false\n".to_string(), 1));
+    assert_eq!(run(&["--contains", b, "-q", "::."]), (String::new(), 0));
+    assert_eq!(run(&["--contains", b, "-q", "."]), (String::new(), 1));
+    assert_eq!(run(&["--contains", &b[..8], "desc(B)"]).1, 0);
+    assert_eq!(
+        run(&["--contains", "HEAD", "::.", "desc(A)"]),
+        ("true\nfalse\n".to_string(), 1)
+    );
+    assert_eq!(run(&["--contains", "none()", "::."]).1, 2);
+    // The argument must be a single commit.
+    assert_eq!(run(&["--contains", "::.", "::."]), (String::new(), 2));
+    assert_eq!(
+        run(&["--contains", "desc(A) + desc(B)", "all()"]),
+        (String::new(), 2)
+    );
+}
+
+#[test]
+fn test_since_last_tag() {
+    let dir = tempfile::tempdir().unwrap();
+    let hashes = linear_repo(dir.path(), &["A", "B", "C", "D"]);
+    let all = format!(
+        "{}\n{}\n{}\n{}\n",
+        hashes[3], hashes[2], hashes[1], hashes[0]
+    );
+    assert_eq!(git_revs(dir.path(), &["--since-last-tag"]), all);
+
+    let repo = git2::Repository::open(dir.path()).unwrap();
+    let tag = |name: &str, hash: &str| {
+        let commit = repo
+            .find_commit(git2::Oid::from_str(hash).unwrap())
+            .unwrap();
+        repo.tag_lightweight(name, commit.as_object(), false)
+            .unwrap();
+    };
+    tag("v1.0", &hashes[0]);
+    tag("v1.1", &hashes[1]);
+    let since_b = format!("{}\n{}\n", hashes[3], hashes[2]);
+    assert_eq!(git_revs(dir.path(), &["--since-last-tag"]), since_b);
+    // Flags after --since-last-tag apply to it, like other expressions.
+    let run = |args: &[&str]| git_revs(dir.path(), args);
+    assert_eq!(run(&["--since-last-tag", "--count"]), "2\n");
+    assert_eq!(
+        run(&["--since-last-tag", "--reverse"]),
+        format!("{}\n{}\n", hashes[2], hashes[3])
+    );
+    assert_eq!(
+        run(&["--since-last-tag", "--limit", "1", "::."]),
+        format!("{}\n{}\n", hashes[3], hashes[3])
+    );
+    assert_eq!(
+        run(&["--since-last-tag", "--contains", &hashes[1]]),
+        "false\n"
+    );
+
+    tag("v2.0", &hashes[3]);
+    assert_eq!(git_revs(dir.path(), &["--since-last-tag"]), "");
+}
+
+#[test]
+fn test_pick_non_interactive() {
+    let dir = tempfile::tempdir().unwrap();
+    let names: Vec<String> = (0..17).map(|i| format!("C{}", i)).collect();
+    let names: Vec<&str> = names.iter().map(|s| s.as_str()).collect();
+    let hashes = linear_repo(dir.path(), &names);
+    // 17 commits. At least 2 of them share the first hex digit.
+    let prefix = (b'0'..=b'9')
+        .chain(b'a'..=b'f')
+        .map(|b| (b as char).to_string())
+        .find(|p| hashes.iter().filter(|h| h.starts_with(p)).count() > 1)
+        .unwrap();
+    // stdin is not a terminal. The error is reported without prompting.
+    let (stdout, code) = git_revs_with_status(dir.path(), &["--pick", &prefix]);
+    assert_eq!((stdout.as_str(), code), ("", 2));
+    // The error lists the candidates, with or without --pick.
+    for args in [&["--pick", prefix.as_str()][..], &[prefix.as_str()][..]] {
+        let output = Command::new(env!("CARGO_BIN_EXE_git-revs"))
+            .args(args)
+            .env("GIT_DIR", dir.path().join(".git"))
+            .output()
+            .unwrap();
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("Candidates:"));
+        for (hash, name) in hashes.iter().zip(&names) {
+            let line = format!(") {} {}\n", hash, name);
+            assert_eq!(
+                stderr.contains(&line),
+                hash.starts_with(&prefix),
+                "{}",
+                stderr
+            );
+        }
+    }
+    assert_eq!(
+        git_revs(dir.path(), &["--pick", &hashes[3]]),
+        format!("{}\n", hashes[3])
+    );
+}
+
+#[test]
+fn test_repo_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let hashes = linear_repo(dir.path(), &["A", "B"]);
+    let other_dir = tempfile::tempdir().unwrap();
+    let run = |args: &[&str]| -> String {
+        let output = Command::new(env!("CARGO_BIN_EXE_git-revs"))
+            .args(args)
+            .env_remove("GIT_DIR")
+            .current_dir(other_dir.path())
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let path = dir.path().to_str().unwrap();
+    let newest_first = format!("{}\n{}\n", hashes[1], hashes[0]);
+    let oldest_first = format!("{}\n{}\n", hashes[0], hashes[1]);
+    assert_eq!(run(&["-C", path, "::."]), newest_first);
+    assert_eq!(run(&["--repo", path, "::."]), newest_first);
+    // The path can be specified after other flags and expressions.
+    assert_eq!(run(&["--reverse", "::.", "-C", path]), oldest_first);
+    assert_eq!(
+        run(&["--contains", "HEAD", "-C", path, "::."]),
+        "true\n".to_string()
+    );
+    // Subdirectories of the working copy are accepted.
+    let sub_dir = dir.path().join("sub");
+    std::fs::create_dir(&sub_dir).unwrap();
+    let sub_path = sub_dir.to_str().unwrap();
+    assert_eq!(run(&["-C", sub_path, "."]), format!("{}\n", hashes[1]));
+
+    // A trailing -C or --repo without a path is a usage error.
+    for flag in ["-C", "--repo"] {
+        let (stdout, code) = git_revs_with_status(dir.path(), &["::.", flag]);
+        assert_eq!(stdout, "");
+        assert_eq!(code, 2);
+    }
+}